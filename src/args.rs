@@ -0,0 +1,120 @@
+use crate::builder::DropDownBuilder;
+use crate::item::MenuItem;
+use crate::output::Output;
+use clap::builder::PossibleValue;
+use clap::error::ErrorKind;
+use clap::{Arg, ArgMatches, Command};
+use std::ffi::OsString;
+use std::io::{self, IsTerminal};
+
+/// Like clap's [`Command::get_matches`], but asks for missing arguments with a menu; see
+/// [`try_get_matches_from_interactive`]. Needs the `clap` feature.
+///
+/// On errors, clap prints its message and exits the process.
+pub fn get_matches_interactive(cmd: Command) -> ArgMatches {
+    try_get_matches_from_interactive(cmd, std::env::args_os()).unwrap_or_else(|e| e.exit())
+}
+
+/// Like clap's [`Command::try_get_matches_from`], but when required arguments with a known set
+/// of values are missing and standard input is a terminal, lets the user pick each of them from
+/// a menu instead of failing. Needs the `clap` feature.
+///
+/// The menu is drawn on standard error, so that standard output stays clean, with the
+/// argument's help as its prompt and the values' help as item descriptions. The picked values
+/// are added to `args` as if they had been typed, so clap validates and reports them as usual.
+///
+/// # Errors
+/// clap's error for the missing argument if standard input is not a terminal, an argument
+/// without possible values is missing too, or the user cancels a menu. Other errors, such as
+/// `--help`, are returned as they are.
+///
+/// # Example
+/// ```no_run
+/// use clap::{Arg, Command};
+///
+/// let cmd = Command::new("deploy").arg(
+///     Arg::new("env")
+///         .long("env")
+///         .required(true)
+///         .value_parser(["staging", "prod"])
+///         .help("Where to deploy"),
+/// );
+/// // `deploy` alone asks for `--env` with a menu of staging and prod.
+/// let matches = ter_menu::get_matches_interactive(cmd);
+/// let env: &String = matches.get_one("env").unwrap();
+/// ```
+pub fn try_get_matches_from_interactive<I, A>(
+    cmd: Command,
+    args: I,
+) -> Result<ArgMatches, clap::Error>
+where
+    I: IntoIterator<Item = A>,
+    A: Into<OsString>,
+{
+    let mut args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+    let error = match cmd.clone().try_get_matches_from(&args) {
+        Err(e) if e.kind() == ErrorKind::MissingRequiredArgument && io::stdin().is_terminal() => e,
+        result => return result,
+    };
+    // Parsing again with nothing required tells which arguments were given.
+    let Ok(given) = cmd
+        .clone()
+        .mut_args(|arg| arg.required(false))
+        .try_get_matches_from(&args)
+    else {
+        return Err(error);
+    };
+    for arg in cmd.get_arguments() {
+        if !arg.is_required_set() || given.contains_id(arg.get_id().as_str()) {
+            continue;
+        }
+        let choices: Vec<PossibleValue> = arg
+            .get_possible_values()
+            .into_iter()
+            .filter(|value| !value.is_hide_set())
+            .collect();
+        if choices.is_empty() {
+            return Err(error);
+        }
+        let Some(value) = pick(arg, &choices) else {
+            return Err(error);
+        };
+        match (arg.get_long(), arg.get_short()) {
+            (Some(long), _) => args.push(format!("--{}={}", long, value).into()),
+            (None, Some(short)) => args.extend([format!("-{}", short), value].map(Into::into)),
+            (None, None) => args.push(value.into()),
+        }
+    }
+    cmd.try_get_matches_from(args)
+}
+
+/// Shows a menu of `choices` for `arg` and returns the picked value, or `None` if the user
+/// cancelled or the terminal failed.
+fn pick(arg: &Arg, choices: &[PossibleValue]) -> Option<String> {
+    let name = match (arg.get_long(), arg.get_value_names()) {
+        (Some(long), _) => format!("--{}", long),
+        (None, Some([value_name, ..])) => format!("<{}>", value_name),
+        _ => arg.get_id().to_string(),
+    };
+    let prompt = match arg.get_help() {
+        Some(help) => format!("{}: {}", name, help),
+        None => name,
+    };
+    let items = choices.iter().map(|choice| {
+        let item = MenuItem::new(choice.get_name(), choice.get_name().to_string());
+        match choice.get_help() {
+            Some(help) => item.description(help.to_string()),
+            None => item,
+        }
+    });
+    DropDownBuilder::new()
+        .values(items)
+        .prompt(prompt)
+        .output(Output::Stderr)
+        .confirm_message("")
+        .cancel_message("")
+        .build()
+        .wait()
+        .selected()
+        .map(|item| item.value)
+}
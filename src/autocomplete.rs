@@ -0,0 +1,267 @@
+use crate::error::TerMenuError;
+use crate::events::{EventSource, SharedEvents};
+use crate::fuzzy::fuzzy_match;
+use crate::input::{LineEditor, draw_prompt, finish_prompt, next_key};
+use crate::item::MenuEntry;
+use crate::menu::MenuConfig;
+use crate::output::Output;
+use crate::render::{Frame, Line};
+use crate::terminal::TerminalSession;
+use crate::theme::Theme;
+use crossterm::event::{KeyCode, KeyModifiers};
+use crossterm::style::ContentStyle;
+use std::io::{self, IsTerminal, prelude::*};
+use unicode_width::UnicodeWidthStr;
+
+/// Number of completions listed below the text when no page size is set.
+const DEFAULT_PAGE_SIZE: usize = 8;
+
+/// What the user accepted in an [`Autocomplete`] prompt.
+///
+/// # Variants
+/// * `Item` - A completion picked from the list.
+/// * `Text` - The typed text, submitted without picking a completion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Completion<T> {
+    Item(T),
+    Text(String),
+}
+
+/// Where the completions come from.
+enum Source<'a, T> {
+    /// A fixed list, narrowed by fuzzy matching against the typed text.
+    List(Vec<T>),
+    /// Called with the typed text whenever it changes.
+    Provider(Provider<'a, T>),
+}
+
+/// Produces the completions for the typed text.
+type Provider<'a, T> = Box<dyn Fn(&str) -> Vec<T> + 'a>;
+
+/// A text prompt with a dropdown of completions below it, also known as a combobox.
+///
+/// The completions follow the typed text. Tab or Down moves into the list, where Up/Down/Tab
+/// move the highlight and Enter accepts the highlighted completion; typing, or Up on the first
+/// completion, returns to the text. Enter in the text submits the text as typed. Escape leaves
+/// the list, and cancels from the text; Ctrl-C interrupts like in the menus.
+///
+/// # Type Parameters
+/// * `T` - The type of the completions. Their labels are listed.
+///
+/// # Example
+/// ```no_run
+/// use ter_menu::{Autocomplete, Completion};
+///
+/// let hosts = ["web-1.prod", "web-2.prod", "db-1.prod", "web-1.staging"];
+/// match Autocomplete::new("Host: ", hosts).run()? {
+///     Some(Completion::Item(host)) => println!("Deploying to {}", host),
+///     Some(Completion::Text(text)) => println!("Unknown host {}", text),
+///     None => {}
+/// }
+/// # Ok::<(), ter_menu::TerMenuError>(())
+/// ```
+pub struct Autocomplete<'a, T> {
+    prompt: String,
+    source: Source<'a, T>,
+    page_size: usize,
+    config: MenuConfig,
+}
+
+impl<'a, T: MenuEntry + Clone> Autocomplete<'a, T> {
+    /// Creates a prompt completing from a fixed list of `items`. Items whose label fuzzily
+    /// matches the typed text are listed, best matches first; disabled items are left out.
+    pub fn new(prompt: impl Into<String>, items: impl IntoIterator<Item = T>) -> Self {
+        Self::from_source(prompt.into(), Source::List(items.into_iter().collect()))
+    }
+
+    /// Creates a prompt whose completions are produced by `provider` from the typed text, e.g.
+    /// by looking up hosts or running a search. It is called again whenever the text changes.
+    pub fn with_provider(
+        prompt: impl Into<String>,
+        provider: impl Fn(&str) -> Vec<T> + 'a,
+    ) -> Self {
+        Self::from_source(prompt.into(), Source::Provider(Box::new(provider)))
+    }
+
+    fn from_source(prompt: String, source: Source<'a, T>) -> Self {
+        Self {
+            prompt,
+            source,
+            page_size: DEFAULT_PAGE_SIZE,
+            config: MenuConfig::default(),
+        }
+    }
+
+    /// Sets the maximum number of completions listed at once. Always at least one.
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Sets the colors and glyphs used for the prompt and the list. Ignored when `NO_COLOR` is
+    /// set or `TERM=dumb`.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        if !self.config.plain {
+            self.config.theme = theme;
+        }
+        self
+    }
+
+    /// Sets where the prompt is drawn. Defaults to [`Output::Stdout`].
+    pub fn output(mut self, output: Output) -> Self {
+        self.config.output = output;
+        self
+    }
+
+    /// Reads the input from `source` instead of the terminal.
+    pub fn events(mut self, source: impl EventSource + 'static) -> Self {
+        self.config.events = Some(SharedEvents::new(source));
+        self
+    }
+
+    /// Shows the prompt and blocks until the user accepts something or cancels.
+    ///
+    /// When stdin or the output is not a terminal, or `NO_COLOR`/`TERM=dumb` ask for plain
+    /// output, a line of text is read instead and returned as [`Completion::Text`].
+    ///
+    /// # Returns
+    /// `Ok(Some(completion))` with what the user accepted, `Ok(None)` on Escape or at the end of
+    /// input, or a [`TerMenuError`] if the terminal could not be driven or the user pressed
+    /// Ctrl-C.
+    pub fn run(self) -> Result<Option<Completion<T>>, TerMenuError> {
+        let headless = self.config.events.is_some();
+        if !headless
+            && (self.config.plain
+                || !io::stdin().is_terminal()
+                || !self.config.output.is_terminal())
+        {
+            return self.read_line();
+        }
+        let _session = if headless {
+            None
+        } else {
+            Some(TerminalSession::start_with_cursor(&self.config)?)
+        };
+        let mut editor = LineEditor::new(String::new());
+        let mut completions = self.complete(editor.text());
+        // `Some` while the user is in the list, holding the highlighted completion.
+        let mut highlighted: Option<usize> = None;
+        self.draw(&editor, &completions, highlighted)?;
+        loop {
+            let key = next_key(&self.config)?;
+            match (key.code, highlighted) {
+                (KeyCode::Char('c'), _) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.finish(&editor)?;
+                    return Err(TerMenuError::Interrupted);
+                }
+                (KeyCode::Esc, None) => {
+                    self.finish(&editor)?;
+                    return Ok(None);
+                }
+                (KeyCode::Enter, None) => {
+                    self.finish(&editor)?;
+                    return Ok(Some(Completion::Text(editor.into_text())));
+                }
+                (KeyCode::Enter, Some(pos)) => {
+                    let item = completions.swap_remove(pos);
+                    editor = LineEditor::new(item.label());
+                    self.finish(&editor)?;
+                    return Ok(Some(Completion::Item(item)));
+                }
+                (KeyCode::Tab | KeyCode::Down, None) if !completions.is_empty() => {
+                    highlighted = Some(0);
+                }
+                (KeyCode::Tab | KeyCode::Down, Some(pos)) => {
+                    highlighted = Some((pos + 1) % completions.len());
+                }
+                (KeyCode::Up, Some(pos)) => highlighted = pos.checked_sub(1),
+                (KeyCode::Esc, Some(_)) => highlighted = None,
+                _ if editor.handle_key(&key) => {
+                    highlighted = None;
+                    completions = self.complete(editor.text());
+                }
+                _ => continue,
+            }
+            self.draw(&editor, &completions, highlighted)?;
+        }
+    }
+
+    /// The completions for `text`.
+    fn complete(&self, text: &str) -> Vec<T> {
+        match &self.source {
+            Source::List(items) => {
+                let mut matches: Vec<(usize, i64)> = items
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, item)| !item.is_disabled())
+                    .filter_map(|(i, item)| {
+                        fuzzy_match(text, &item.label(), false).map(|m| (i, m.score))
+                    })
+                    .collect();
+                matches.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+                matches.into_iter().map(|(i, _)| items[i].clone()).collect()
+            }
+            Source::Provider(provider) => provider(text),
+        }
+    }
+
+    /// The prompt line for the current text.
+    fn line(&self, editor: &LineEditor) -> Line {
+        let mut line = Line::styled(self.prompt.as_str(), self.config.theme.prompt);
+        line.push(editor.text(), ContentStyle::new());
+        line
+    }
+
+    /// Redraws the prompt line with a page of completions below it.
+    fn draw(
+        &self,
+        editor: &LineEditor,
+        completions: &[T],
+        highlighted: Option<usize>,
+    ) -> io::Result<()> {
+        let theme = &self.config.theme;
+        let mut frame = Frame::default();
+        frame.push(self.line(editor));
+        let total = completions.len();
+        let start = match highlighted {
+            Some(pos) if total > self.page_size => pos
+                .saturating_sub(self.page_size / 2)
+                .min(total - self.page_size),
+            _ => 0,
+        };
+        let end = (start + self.page_size).min(total);
+        let indent = " ".repeat(theme.marker.width());
+        for (pos, item) in completions.iter().enumerate().take(end).skip(start) {
+            let line = if highlighted == Some(pos) {
+                Line::styled(format!("{}{}", theme.marker, item.label()), theme.highlight)
+            } else {
+                Line::plain(format!("{}{}", indent, item.label()))
+            };
+            frame.push(line);
+        }
+        let column = self.prompt.width() + editor.cursor_width();
+        draw_prompt(&self.config.output, &frame, column)
+    }
+
+    /// Removes the completions and leaves the prompt line with its final text on screen.
+    fn finish(&self, editor: &LineEditor) -> io::Result<()> {
+        finish_prompt(&self.config.output, &self.line(editor))
+    }
+
+    /// Line-based fallback for when the prompt cannot be drawn, e.g. under a pipe.
+    fn read_line(&self) -> Result<Option<Completion<T>>, TerMenuError> {
+        let mut stdout = self.config.output.clone();
+        write!(stdout, "{}", self.prompt)?;
+        stdout.flush()?;
+        let mut text = String::new();
+        let read = io::stdin()
+            .lock()
+            .read_line(&mut text)
+            .map_err(TerMenuError::EventRead)?;
+        if read == 0 {
+            return Ok(None);
+        }
+        let text = text.trim_end_matches(['\r', '\n']);
+        Ok(Some(Completion::Text(text.to_string())))
+    }
+}
@@ -0,0 +1,137 @@
+use crate::render::{Frame, Line};
+use crossterm::style::ContentStyle;
+use unicode_width::UnicodeWidthStr;
+
+/// The characters a box around a menu is drawn with; see
+/// [`DropDownBuilder::border`](crate::DropDownBuilder::border).
+///
+/// [`Border::default`] draws single Unicode box-drawing lines, [`Border::rounded`] the same with
+/// rounded corners, and [`Border::ascii`] uses `+`, `-` and `|` for terminals and fonts without
+/// box-drawing characters. Menus drawn as plain text always use [`Border::ascii`].
+///
+/// # Fields
+/// * `top_left` / `top_right` / `bottom_left` / `bottom_right` - The corners.
+/// * `horizontal` - Fills the top and bottom edges.
+/// * `vertical` - The left and right edges.
+///
+/// # Example
+/// ```
+/// use ter_menu::Border;
+///
+/// let double = Border {
+///     top_left: '╔',
+///     top_right: '╗',
+///     bottom_left: '╚',
+///     bottom_right: '╝',
+///     horizontal: '═',
+///     vertical: '║',
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Border {
+    pub top_left: char,
+    pub top_right: char,
+    pub bottom_left: char,
+    pub bottom_right: char,
+    pub horizontal: char,
+    pub vertical: char,
+}
+
+impl Border {
+    /// Single lines with rounded corners: `╭─╮`, `│ │`, `╰─╯`.
+    pub fn rounded() -> Self {
+        Self {
+            top_left: '╭',
+            top_right: '╮',
+            bottom_left: '╰',
+            bottom_right: '╯',
+            ..Self::default()
+        }
+    }
+
+    /// Plain ASCII: `+-+`, `| |`, `+-+`.
+    pub fn ascii() -> Self {
+        Self {
+            top_left: '+',
+            top_right: '+',
+            bottom_left: '+',
+            bottom_right: '+',
+            horizontal: '-',
+            vertical: '|',
+        }
+    }
+
+    /// Draws `frame` inside the box, with `title` in the top edge. The box is as wide as the
+    /// widest line or the title, but no wider than `max_width` columns; longer lines are cut off
+    /// with `…`. Each edge is drawn with `style`, the title with `title_style`.
+    pub(crate) fn enclose(
+        &self,
+        frame: Frame,
+        title: Option<&str>,
+        max_width: Option<usize>,
+        style: ContentStyle,
+        title_style: ContentStyle,
+    ) -> Frame {
+        // Two columns of edge and padding on each side.
+        let max_inner = max_width.map(|width| width.saturating_sub(4));
+        let title_width = title.map_or(0, |title| title.width() + 1);
+        let widest = frame
+            .lines
+            .iter()
+            .map(Line::width)
+            .max()
+            .unwrap_or_default();
+        let inner = widest.max(title_width).min(max_inner.unwrap_or(usize::MAX));
+        let horizontal = |count: usize| self.horizontal.to_string().repeat(count);
+
+        let mut boxed = Frame::default();
+        let mut top = Line::styled(format!("{}{}", self.top_left, self.horizontal), style);
+        let mut filled = 1;
+        if let Some(title) = title {
+            let mut title = Line::styled(format!(" {} ", title), title_style);
+            title.truncate(inner + 1);
+            filled += title.width();
+            top.spans.extend(title.spans);
+        }
+        top.push(
+            format!(
+                "{}{}",
+                horizontal((inner + 2).saturating_sub(filled)),
+                self.top_right
+            ),
+            style,
+        );
+        boxed.push(top);
+        for mut line in frame.lines {
+            line.truncate(inner);
+            line.pad(inner, ContentStyle::new());
+            let mut row = Line::styled(format!("{} ", self.vertical), style);
+            row.spans.extend(line.spans);
+            row.push(format!(" {}", self.vertical), style);
+            boxed.push(row);
+        }
+        boxed.push(Line::styled(
+            format!(
+                "{}{}{}",
+                self.bottom_left,
+                horizontal(inner + 2),
+                self.bottom_right
+            ),
+            style,
+        ));
+        boxed
+    }
+}
+
+impl Default for Border {
+    fn default() -> Self {
+        Self {
+            top_left: '┌',
+            top_right: '┐',
+            bottom_left: '└',
+            bottom_right: '┘',
+            horizontal: '─',
+            vertical: '│',
+        }
+    }
+}
@@ -0,0 +1,818 @@
+use crate::border::Border;
+use crate::drop_down::TerminalDropDown;
+use crate::events::{EventSource, SharedEvents};
+use crate::hooks::{Hooks, TryCallback};
+use crate::intercept::KeyInterceptor;
+use crate::item::{DescriptionLayout, MenuEntry, MenuItem};
+use crate::keymap::KeyMap;
+use crate::live::ItemsHandle;
+use crate::matcher::MatchMode;
+use crate::menu::{Divider, MenuConfig};
+use crate::output::Output;
+use crate::preview::PreviewLayout;
+use crate::reusable::ReusableDropDown;
+use crate::session::SessionLog;
+use crate::sort::SortMode;
+use crate::store::SelectionStore;
+use crate::strings::Strings;
+use crate::table::{Column, Table};
+use crate::theme::Theme;
+use crate::timeout::TimeoutAction;
+use crossterm::style::StyledContent;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Step-by-step configuration for a [`TerminalDropDown`].
+///
+/// Every setting has a sensible default, so new options can be added over time without changing
+/// the signature of existing constructors. Nothing is shown until [`DropDownBuilder::build`] is
+/// called.
+///
+/// # Type Parameters
+/// * `T` - The type of items in the dropdown, as for [`TerminalDropDown`].
+/// * `F` - The type of callback function triggered when an item is selected.
+pub struct DropDownBuilder<T, F>
+where
+    T: MenuEntry + Clone + Send + 'static,
+    F: FnOnce(&T) + Send + 'static,
+{
+    items: Vec<(T, F)>,
+    /// The callbacks of items added with [`DropDownBuilder::try_item`], by index into `items`.
+    fallible: HashMap<usize, TryCallback<T>>,
+    /// Receives the items of [`DropDownBuilder::item_stream`] sources.
+    stream: ItemsHandle<T>,
+    config: MenuConfig,
+    hooks: Hooks<T>,
+}
+
+impl<T, F> DropDownBuilder<T, F>
+where
+    T: MenuEntry + Clone + Send + 'static,
+    F: FnOnce(&T) + Send + 'static,
+{
+    /// Creates a builder with no items, a page size that fits the terminal, wrap-around navigation
+    /// and no filter.
+    pub fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            fallible: HashMap::new(),
+            stream: ItemsHandle::new(),
+            config: MenuConfig::default(),
+            hooks: Hooks::default(),
+        }
+    }
+
+    /// Appends `(item, callback)` pairs. Items are displayed in the order they are added.
+    pub fn items<I>(mut self, items: I) -> Self
+    where
+        I: IntoIterator<Item = (T, F)>,
+    {
+        self.items.extend(items);
+        self
+    }
+
+    /// Appends a single item with its callback.
+    pub fn item(mut self, item: T, callback: F) -> Self {
+        self.items.push((item, callback));
+        self
+    }
+
+    /// Appends the items of a slow source, such as a paginated API or a large directory scan, as
+    /// they arrive; see [`ItemsHandle::load`]. Reading starts right away on a thread of its own,
+    /// and the menu shows a spinner and a "Loading…" row below the items until the source runs
+    /// out, while the user can already pick from the items loaded so far. Streamed items have no
+    /// callback, but [`wait`](TerminalDropDown::wait) returns them like any other.
+    ///
+    /// Not applied by [`build_reusable`](DropDownBuilder::build_reusable); `select_async` fails
+    /// with [`TerMenuError::Unsupported`](crate::TerMenuError::Unsupported).
+    pub fn item_stream<I>(self, items: I) -> Self
+    where
+        I: IntoIterator<Item = T> + Send + 'static,
+    {
+        self.stream.load(items);
+        self
+    }
+
+    /// Sets the maximum number of items to display in the terminal at once. Without it, the menu
+    /// shows as many items as fit in the terminal and adjusts when the terminal is resized.
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.config.page_size = Some(page_size);
+        self
+    }
+
+    /// Sets the title line printed above the items.
+    pub fn prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.config.prompt = prompt.into();
+        self
+    }
+
+    /// Sets the index of the item highlighted when the menu opens. Out-of-range values select the
+    /// last item.
+    pub fn default_index(mut self, index: usize) -> Self {
+        self.config.default_index = index;
+        self
+    }
+
+    /// Highlights the first item equal to `value` when the menu opens, e.g. the git branch that is
+    /// currently checked out. Only items added before this call are searched; if none matches,
+    /// the highlight stays where it was.
+    pub fn default_value(mut self, value: &T) -> Self
+    where
+        T: PartialEq,
+    {
+        if let Some(index) = self.items.iter().position(|(item, _)| item == value) {
+            self.config.default_index = index;
+        }
+        self
+    }
+
+    /// Starts the menu on the item the user chose the last time a menu with the same `menu_id`
+    /// was shown, and records the new choice in `store` once they confirm one, so that repeated
+    /// runs of a tool default to the previous answer. Items are matched by their label. Only
+    /// items added before this call are searched; if none matches, the highlight stays where
+    /// it was.
+    ///
+    /// Failures to save are ignored: a choice that could not be remembered should not fail the
+    /// interaction it came from.
+    pub fn remember(
+        mut self,
+        menu_id: impl Into<String>,
+        mut store: impl SelectionStore + 'static,
+    ) -> Self {
+        let menu_id = menu_id.into();
+        if let Some(label) = store.load(&menu_id)
+            && let Some(index) = self
+                .items
+                .iter()
+                .position(|(item, _)| item.label() == label)
+        {
+            self.config.default_index = index;
+        }
+        self.hooks.on_confirm = Some(Box::new(move |item: &T| {
+            let _ = store.save(&menu_id, &item.label());
+        }));
+        self
+    }
+
+    /// Sets whether moving up from the first item (or down from the last) wraps around to the
+    /// other end. When disabled the cursor stops at the ends. Enabled by default.
+    pub fn wrap_navigation(mut self, wrap: bool) -> Self {
+        self.config.wrap_navigation = wrap;
+        self
+    }
+
+    /// Rings the terminal bell when Up or Down cannot move any further because
+    /// [`wrap_navigation`](DropDownBuilder::wrap_navigation) is off, so that the end of a long
+    /// list is noticed. Off by default.
+    pub fn bell_at_edges(mut self, bell: bool) -> Self {
+        self.config.bell_at_edges = bell;
+        self
+    }
+
+    /// Lays the items out `columns` to a row, for many short labels such as language codes or
+    /// colors. Left and Right move across a row, Up and Down between rows; the page size counts
+    /// rows. Dividers are not shown in a grid. A value of 1 lists one item per row as usual.
+    pub fn grid(mut self, columns: usize) -> Self {
+        self.config.grid = Some(columns);
+        self
+    }
+
+    /// Draws the menu on a single line after the prompt, e.g. `< staging | [prod] | dev >`, for
+    /// small choices inside larger output. Left and Right move the highlight; the page size is
+    /// the number of items shown at once. The menu is drawn in place, as with
+    /// [`DropDownBuilder::inline`], without a footer or mouse support.
+    pub fn horizontal(mut self, horizontal: bool) -> Self {
+        self.config.horizontal = horizontal;
+        self
+    }
+
+    /// Sets how the filter line matches items: fuzzy (the default), as a substring, or as a
+    /// regular expression with the `regex` feature. Users can switch modes with Ctrl-R while
+    /// filtering. Only has an effect together with [`DropDownBuilder::filter`].
+    pub fn match_mode(mut self, mode: MatchMode) -> Self {
+        self.config.match_mode = mode;
+        self
+    }
+
+    /// Makes the filter line respect case instead of ignoring it. Users can switch with Alt-C
+    /// while filtering. Only has an effect together with [`DropDownBuilder::filter`].
+    pub fn case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.config.case_sensitive = case_sensitive;
+        self
+    }
+
+    /// Wraps items wider than the terminal onto further lines, lined up with the start of the
+    /// label, instead of cutting them off with `…`. For long labels such as commit messages.
+    /// Each item still counts once towards the page size, but a page holds no more lines than
+    /// fit on the screen.
+    pub fn soft_wrap(mut self, wrap: bool) -> Self {
+        self.config.soft_wrap = wrap;
+        self
+    }
+
+    /// Enables the fuzzy filter line, so that typing narrows the visible items.
+    pub fn filter(mut self, filter: bool) -> Self {
+        self.config.filter = filter;
+        self
+    }
+
+    /// Sets the message printed once the user confirms an item. Occurrences of `{item}` are
+    /// replaced with the selected item; an empty message prints nothing.
+    pub fn confirm_message(mut self, message: impl Into<String>) -> Self {
+        self.config.confirm_message = message.into();
+        self
+    }
+
+    /// Replaces the confirm message with a status line that `finish` produces once the confirmed
+    /// item's callback has run, from the item and the callback's outcome: the error a
+    /// [`try_item`](DropDownBuilder::try_item) callback failed with, `Ok` otherwise. The line keeps its styling
+    /// unless the menu is drawn as plain text.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use crossterm::style::Stylize;
+    /// use ter_menu::TerminalDropDown;
+    ///
+    /// let result = TerminalDropDown::builder()
+    ///     .try_items(["staging", "prod"].map(|env| (env, |_: &&str| deploy())))
+    ///     .finish_message(|env, outcome| match outcome {
+    ///         Ok(()) => format!("✔ Deployed to {}", env).green(),
+    ///         Err(e) => format!("✘ Failed: {}", e).red(),
+    ///     })
+    ///     .build()
+    ///     .wait();
+    /// # fn deploy() -> std::io::Result<()> { Ok(()) }
+    /// ```
+    pub fn finish_message(
+        mut self,
+        finish: impl Fn(&T, Result<(), &(dyn Error + Send + Sync)>) -> StyledContent<String>
+        + Send
+        + 'static,
+    ) -> Self {
+        self.hooks.finish_message = Some(Box::new(finish));
+        self
+    }
+
+    /// Sets the message printed when the user cancels; an empty message prints nothing.
+    pub fn cancel_message(mut self, message: impl Into<String>) -> Self {
+        self.config.cancel_message = message.into();
+        self
+    }
+
+    /// Replaces the key instructions shown below the items. The text may contain placeholders
+    /// that are filled in on every redraw: `{total}` for the number of items, `{matched}` for
+    /// those matching the filter, `{position}` for the highlighted row, `{item}` for the
+    /// highlighted item, `{selected}` for the number of checked items and `{filter}` for the
+    /// filter text, e.g. `"{position}/{matched} | Enter: Deploy {item}"`.
+    pub fn help_line(mut self, help: impl Into<String>) -> Self {
+        self.config.help = Some(help.into());
+        self
+    }
+
+    /// Sets the texts the menu shows on its own, e.g. [`Strings::zh_cn`] for Chinese menus. Also
+    /// sets the prompt and the confirm and cancel messages to those of `strings`, so call it
+    /// before [`prompt`](DropDownBuilder::prompt) and the message setters to override them.
+    pub fn strings(mut self, strings: Strings) -> Self {
+        self.config.prompt = strings.prompt.clone();
+        self.config.confirm_message = strings.confirm_message.clone();
+        self.config.cancel_message = strings.cancel_message.clone();
+        self.config.strings = strings;
+        self
+    }
+
+    /// Sets whether the prompt and the totals line are drawn above the items. On by default;
+    /// turned off, only the filter line (while filtering) is left above them, for minimal menus
+    /// embedded in other output.
+    pub fn show_header(mut self, show: bool) -> Self {
+        self.config.show_header = show;
+        self
+    }
+
+    /// Draws the menu inside a box made of `border`, so that it stands out among other output
+    /// of an [`inline`](DropDownBuilder::inline) menu. [`Border::default`] uses Unicode
+    /// box-drawing characters; plain-text menus fall back to [`Border::ascii`].
+    pub fn border(mut self, border: Border) -> Self {
+        self.config.border = Some(border);
+        self
+    }
+
+    /// Shows `title` in the top edge of the box, e.g. `┌─ Deploy ───┐`. Draws the box with
+    /// [`Border::default`] unless [`border`](DropDownBuilder::border) picked other characters.
+    pub fn border_title(mut self, title: impl Into<String>) -> Self {
+        self.config.border.get_or_insert_with(Border::default);
+        self.config.border_title = Some(title.into());
+        self
+    }
+
+    /// Sets whether the key instructions are drawn below the items. On by default.
+    pub fn show_footer(mut self, show: bool) -> Self {
+        self.config.show_footer = show;
+        self
+    }
+
+    /// Sets the rate at which a held key repeats: repeats of the key just handled that arrive
+    /// sooner than `interval` after it are dropped. Typed text is never dropped, so doubled
+    /// letters in a fast-typed or pasted filter stay doubled. Terminals that report repeats
+    /// separately, with [`DropDownBuilder::keyboard_enhancement`], keep every key press; others
+    /// cannot tell a held key from quick presses, so there navigation keys such as the arrows are
+    /// limited. Defaults to 50 ms; `Duration::ZERO` handles every event, so held arrow keys scroll
+    /// at the terminal's repeat rate.
+    ///
+    /// Input that queued up while the menu was busy is handled in one go before the menu is
+    /// drawn again.
+    pub fn key_repeat_interval(mut self, interval: Duration) -> Self {
+        self.config.repeat_interval = interval;
+        self
+    }
+
+    /// Sets how long to ignore repeats of the key just handled.
+    #[deprecated(note = "use `key_repeat_interval`, which only limits repeats of the same key")]
+    pub fn debounce(self, debounce: Duration) -> Self {
+        self.key_repeat_interval(debounce)
+    }
+
+    /// Reads keys with the kitty keyboard protocol where the terminal supports it, so that
+    /// combinations plain terminal input cannot tell apart, such as Shift-Enter and Enter, can be
+    /// bound separately with [`KeyMap::bind_with`]. Key releases are then reported too; like on
+    /// Windows, they reach [key interceptors](DropDownBuilder::key_interceptor) but are otherwise
+    /// ignored. Off by default; terminals without support are read as usual.
+    pub fn keyboard_enhancement(mut self, enhance: bool) -> Self {
+        self.config.keyboard_enhancement = enhance;
+        self
+    }
+
+    /// Enables mouse support: the wheel scrolls, a click highlights a row and a double-click
+    /// selects it.
+    pub fn mouse(mut self, mouse: bool) -> Self {
+        self.config.mouse = mouse;
+        self
+    }
+
+    /// Makes a single click select the row under the pointer instead of only highlighting it.
+    /// Only has an effect together with [`DropDownBuilder::mouse`].
+    pub fn click_to_select(mut self, click_to_select: bool) -> Self {
+        self.config.click_to_select = click_to_select;
+        self
+    }
+
+    /// Draws the menu on the terminal's alternate screen. The original screen contents and cursor
+    /// position come back when the menu closes, and the confirm/cancel message is printed there.
+    pub fn alternate_screen(mut self, alternate_screen: bool) -> Self {
+        self.config.alternate_screen = alternate_screen;
+        self
+    }
+
+    /// Draws the menu in place below the cursor instead of clearing the screen. The menu is
+    /// erased when the interaction ends, leaving only the confirm/cancel message as a one-line
+    /// summary (or nothing, if the message is empty). Ignored together with
+    /// [`DropDownBuilder::alternate_screen`].
+    pub fn inline(mut self, inline: bool) -> Self {
+        self.config.inline = inline;
+        self
+    }
+
+    /// Draws the menu as a popup with its top-left corner at `row` and `column` (both counted
+    /// from 0), over whatever is on screen instead of clearing it, e.g. as a context menu in a
+    /// larger TUI. Nothing outside the popup is touched, and its cells are restored when it
+    /// closes; see [`DropDownBuilder::backdrop`]. Takes precedence over
+    /// [`DropDownBuilder::inline`].
+    pub fn popup(mut self, row: u16, column: u16) -> Self {
+        self.config.popup = Some((row, column));
+        self
+    }
+
+    /// Hands a popup the text currently on screen, one string per terminal row from the top,
+    /// e.g. from the TUI's own screen buffer. Terminals cannot report what they show, so this is
+    /// what the cells covered by the popup are restored from when it shrinks or closes; without
+    /// it they are left blank.
+    pub fn backdrop<S: Into<String>>(mut self, lines: impl IntoIterator<Item = S>) -> Self {
+        self.config.backdrop = lines.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Replaces the key bindings, e.g. to add vi-style `j`/`k` navigation or a `q` to cancel.
+    /// Defaults to [`KeyMap::default`].
+    pub fn keymap(mut self, keymap: KeyMap) -> Self {
+        self.config.keymap = keymap;
+        self
+    }
+
+    /// Sets the colors and glyphs used for drawing, e.g. [`Theme::dark`]. Defaults to
+    /// [`Theme::default`].
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.config.theme = theme;
+        self
+    }
+
+    /// Draws plain text without colors, cursor movement or any other escape sequences, printing
+    /// each update below the previous one. Suited to minimal CI shells and serial consoles.
+    /// Defaults to on when `NO_COLOR` is set or `TERM=dumb`, and off otherwise.
+    pub fn plain(mut self, plain: bool) -> Self {
+        self.config.plain = plain;
+        self
+    }
+
+    /// Numbers the first nine rows on each page so that pressing a digit picks that row directly,
+    /// without moving the highlight there first. Digits are typed into the filter instead when
+    /// [`DropDownBuilder::filter`] is on.
+    pub fn numbered(mut self, numbered: bool) -> Self {
+        self.config.numbered = numbered;
+        self
+    }
+
+    /// Adds a separator line between the items added so far and the ones added next. Separators
+    /// cannot be highlighted and are hidden while a filter is typed.
+    pub fn separator(mut self) -> Self {
+        self.config
+            .dividers
+            .push((self.items.len(), Divider::Separator));
+        self
+    }
+
+    /// Starts a group of items under `title`, e.g. "Staging" or "Production". Headers cannot be
+    /// highlighted, stay visible at the top of the page while any of their items are shown, and
+    /// are hidden while a filter is typed.
+    pub fn header(mut self, title: impl Into<String>) -> Self {
+        self.config
+            .dividers
+            .push((self.items.len(), Divider::Header(title.into())));
+        self
+    }
+
+    /// Registers a closure called with the highlighted item when the menu opens and every time
+    /// the highlight moves to a different item, e.g. to show a live preview elsewhere on screen.
+    pub fn on_highlight(mut self, on_highlight: impl FnMut(&T) + Send + 'static) -> Self {
+        self.hooks.on_highlight = Some(Box::new(on_highlight));
+        self
+    }
+
+    /// Registers a closure called with the highlighted item on
+    /// [`Action::ConfirmAndStay`](crate::Action::ConfirmAndStay), bound to Shift+Enter by
+    /// default, after which the menu stays open for the next item. Terminals only tell Shift+Enter
+    /// from Enter with [`DropDownBuilder::keyboard_enhancement`].
+    pub fn on_confirm_and_stay(mut self, on_confirm: impl FnMut(&T) + Send + 'static) -> Self {
+        self.hooks.on_confirm_and_stay = Some(Box::new(on_confirm));
+        self
+    }
+
+    /// Shows each item as the text `format` returns instead of its
+    /// [`label`](MenuEntry::label), so that one type can be listed differently in different
+    /// menus, e.g. commits as a shortened hash and a relative date. The filter, type-ahead and
+    /// confirm message use the same text; sorting still goes by the label.
+    pub fn format(mut self, format: impl Fn(&T) -> String + Send + 'static) -> Self {
+        self.hooks.format = Some(Box::new(format));
+        self
+    }
+
+    /// Shows the items as rows of a table with aligned `columns`, headed by the column titles,
+    /// e.g. the name, status and age of each pod. Cells wider than their column are cut off with
+    /// `…`. Columns without a fixed width fit the widest cell among the items added before this
+    /// call. Replaces [`DropDownBuilder::format`]; the filter matches the whole row.
+    pub fn columns(mut self, columns: impl IntoIterator<Item = Column<T>>) -> Self {
+        let table = Table::new(
+            columns.into_iter().collect(),
+            self.items.iter().map(|(item, _)| item),
+        );
+        self.config.table_header = Some(table.header());
+        self.hooks.format = Some(Box::new(move |item| table.row(item)));
+        self
+    }
+
+    /// Shows a preview pane with the text `preview` returns for the highlighted item, such as a
+    /// file's contents or a commit's diff. The pane is redrawn whenever the highlight moves; see
+    /// [`DropDownBuilder::preview_layout`] for where it goes.
+    pub fn preview(mut self, preview: impl Fn(&T) -> String + Send + 'static) -> Self {
+        self.hooks.preview = Some(Box::new(preview));
+        self
+    }
+
+    /// Registers a predicate the user can switch on and off with Tab (see
+    /// [`Action::TogglePredicate`](crate::Action::TogglePredicate)), e.g. to show only failing
+    /// services. While it is on, only the items it accepts are listed, together with the fuzzy
+    /// filter if there is one. The menu opens with it off, showing every item.
+    pub fn filter_predicate(mut self, predicate: impl Fn(&T) -> bool + Send + 'static) -> Self {
+        self.hooks.filter_predicate = Some(Box::new(predicate));
+        self
+    }
+
+    /// Lists the items in `mode` order instead of the order they were added in, e.g.
+    /// [`SortMode::Natural`] for file names with numbers. The user can switch to another order
+    /// with `s` ([`Action::CycleSort`](crate::Action::CycleSort)), the highlight staying on the
+    /// same item. Sorting applies while no filter text is typed, and hides separators and
+    /// headers, since their items are no longer next to each other.
+    pub fn sort(mut self, mode: SortMode) -> Self {
+        self.config.sort = Some(mode);
+        self
+    }
+
+    /// Lists the items in the order `compare` defines, e.g. newest first, with
+    /// [`SortMode::Custom`]. Items `compare` considers equal keep the order they were added in.
+    /// See [`DropDownBuilder::sort`] for switching orders at runtime.
+    pub fn sort_by(mut self, compare: impl Fn(&T, &T) -> Ordering + Send + 'static) -> Self {
+        self.hooks.compare = Some(Box::new(compare));
+        self.config.sort = Some(SortMode::Custom);
+        self
+    }
+
+    /// Draws item descriptions after the label (the default), on a line of their own under it,
+    /// or under the highlighted item only. [`DescriptionLayout::Below`] halves the number of
+    /// items that fit on the screen.
+    pub fn description_layout(mut self, layout: DescriptionLayout) -> Self {
+        self.config.description_layout = layout;
+        self
+    }
+
+    /// Places the preview pane to the right of the items (the default) or in a fixed number of
+    /// lines below them. Only has an effect together with [`DropDownBuilder::preview`].
+    pub fn preview_layout(mut self, layout: PreviewLayout) -> Self {
+        self.config.preview_layout = layout;
+        self
+    }
+
+    /// Registers a closure called when the user dismisses the menu without choosing, after the
+    /// terminal has been restored and the cancel message printed, e.g. to clean up or log. The
+    /// outcome is still reported as [`SelectionResult::Cancelled`](crate::SelectionResult).
+    pub fn on_cancel(mut self, on_cancel: impl FnMut() + Send + 'static) -> Self {
+        self.hooks.on_cancel = Some(Box::new(on_cancel));
+        self
+    }
+
+    /// Adds an interceptor that sees every key press before the key map, e.g. to log keys or
+    /// add shortcuts of its own. Interceptors are asked in the order they were added until one
+    /// reports the key as handled; see [`KeyInterceptor`].
+    pub fn key_interceptor(mut self, interceptor: impl KeyInterceptor<T> + 'static) -> Self {
+        self.hooks.key_interceptors.push(Box::new(interceptor));
+        self
+    }
+
+    /// Sets where the menu is drawn and its messages printed. Defaults to [`Output::Stdout`];
+    /// [`Output::Stderr`] keeps standard output clean for piping the result elsewhere.
+    pub fn output(mut self, output: Output) -> Self {
+        self.config.output = output;
+        self
+    }
+
+    /// Sets the menu up to back a shell pipeline, as in `vim $(my-tool pick)`: it is drawn on
+    /// standard error and prints no confirm or cancel message, so that standard output only
+    /// receives what [`SelectionResult::print_selected`](crate::SelectionResult::print_selected)
+    /// writes there.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::process::ExitCode;
+    /// use ter_menu::TerminalDropDown;
+    ///
+    /// fn main() -> ExitCode {
+    ///     TerminalDropDown::builder()
+    ///         .values(["Cargo.toml", "src/lib.rs", "README.md"])
+    ///         .filter(true)
+    ///         .shell_mode()
+    ///         .build()
+    ///         .wait()
+    ///         .print_selected()
+    /// }
+    /// ```
+    pub fn shell_mode(mut self) -> Self {
+        self.config.output = Output::Stderr;
+        self.config.confirm_message = String::new();
+        self.config.cancel_message = String::new();
+        self
+    }
+
+    /// Reads input from `events` instead of the terminal, e.g. [`ScriptedEvents`](crate::ScriptedEvents) in tests.
+    ///
+    /// The menu then leaves the terminal alone: raw mode, the alternate screen and mouse capture
+    /// are not touched, and repeated key presses are not dropped. Together with [`Output::Frames`] this
+    /// runs a menu headlessly. `select_async` always reads the terminal and fails with
+    /// [`TerMenuError::Unsupported`](crate::TerMenuError::Unsupported).
+    pub fn events(mut self, events: impl EventSource + 'static) -> Self {
+        self.config.events = Some(SharedEvents::new(events));
+        self
+    }
+
+    /// Records the session to the file at `path`: every key press and resize with the time it
+    /// came at, and every frame drawn, so that a user's navigation can be played back with
+    /// [`Replay`](crate::Replay). The file is replaced when the menu opens; menus shown again by
+    /// a [`ReusableDropDown`] add to it.
+    pub fn record_session(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.session = Some(SessionLog::new(path.into()));
+        self
+    }
+
+    /// Closes the menu automatically if the user has not pressed a key within `timeout`, so that
+    /// unattended runs do not hang forever. The footer counts down the remaining seconds, e.g.
+    /// "Auto-selecting 'prod' in 8s", and `action` decides whether the default item is selected
+    /// or the menu is cancelled. Any key press or click stops the countdown.
+    ///
+    /// Not applied to the line prompt used when stdin is not a terminal. `select_async` fails
+    /// with [`TerMenuError::Unsupported`](crate::TerMenuError::Unsupported); race it against the
+    /// async runtime's own timer instead.
+    pub fn timeout(mut self, timeout: Duration, action: TimeoutAction) -> Self {
+        self.config.timeout = Some((timeout, action));
+        self
+    }
+
+    /// When the callback of a confirmed [`try_item`](DropDownBuilder::try_item) fails, asks
+    /// "Action failed: …" and lets the user retry it, go back to pick another item, or abort,
+    /// instead of ending with the error right away. Aborting, or pressing Escape, makes
+    /// [`wait`](TerminalDropDown::wait) return the error as without this setting. The texts
+    /// come from [`Strings`], e.g. [`Strings::action_failed`].
+    ///
+    /// `select_async`, which would block its task on the question, fails with
+    /// [`TerMenuError::Unsupported`](crate::TerMenuError::Unsupported).
+    pub fn retry_on_error(mut self, retry: bool) -> Self {
+        self.config.retry_prompt = retry;
+        self
+    }
+
+    /// Holds on to a confirmed item for `window` before the menu closes and the callback runs,
+    /// showing "Selected … — press U within 5s to undo" in place of the footer. `u` or Escape
+    /// takes the item back and returns to the menu, and Enter closes it right away. Suited to
+    /// destructive menus, where a slip of the finger is costly.
+    ///
+    /// `select_async`, which only wakes up for key presses, fails with
+    /// [`TerMenuError::Unsupported`](crate::TerMenuError::Unsupported).
+    pub fn undo_window(mut self, window: Duration) -> Self {
+        self.config.undo_window = Some(window);
+        self
+    }
+
+    /// Shows the dropdown and starts the interaction thread.
+    ///
+    /// # Returns
+    /// A [`TerminalDropDown`] whose [`wait`](TerminalDropDown::wait) yields the outcome.
+    pub fn build(self) -> TerminalDropDown<T, F> {
+        TerminalDropDown::spawn(
+            self.items,
+            self.fallible,
+            self.stream,
+            self.config,
+            self.hooks,
+        )
+    }
+
+    /// Shows the dropdown as a future on the current task. Requires the `async` feature; see
+    /// [`TerminalDropDown::select_async`].
+    ///
+    /// Fallible callbacks and the finish message work as with [`build`](DropDownBuilder::build).
+    /// A timeout, undo window, item stream, retry prompt or event source cannot be honoured by a
+    /// future that only wakes up for terminal input, so setting any of them makes it resolve to
+    /// [`TerMenuError::Unsupported`](crate::TerMenuError::Unsupported) without drawing anything.
+    #[cfg(feature = "async")]
+    pub async fn select_async(self) -> crate::SelectionResult<T> {
+        TerminalDropDown::run_async(
+            self.items,
+            self.fallible,
+            self.stream,
+            self.config,
+            self.hooks,
+        )
+        .await
+    }
+
+    /// Builds a [`ReusableDropDown`] with the same configuration instead of showing a one-shot
+    /// dropdown. Nothing is displayed until [`ReusableDropDown::show`] is called.
+    pub fn build_reusable(self) -> ReusableDropDown<T, F>
+    where
+        F: FnMut(&T),
+    {
+        ReusableDropDown::with_config(self.items, self.fallible, self.config, self.hooks)
+    }
+}
+
+impl<T> DropDownBuilder<T, fn(&T)>
+where
+    T: MenuEntry + Clone + Send + 'static,
+{
+    /// Appends a single item with a callback that can fail, e.g. because it writes a file or
+    /// calls a service. If the item is confirmed and its callback returns an error,
+    /// [`wait`](TerminalDropDown::wait) returns [`SelectionResult::Error`](crate::SelectionResult::Error)
+    /// with [`TerMenuError::Callback`](crate::TerMenuError::Callback) carrying it.
+    ///
+    /// The callback is `FnMut` so that it can run again when the user chooses to retry; see
+    /// [`DropDownBuilder::retry_on_error`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::fs;
+    /// use ter_menu::TerminalDropDown;
+    ///
+    /// let result = TerminalDropDown::builder()
+    ///     .try_item("default", |profile: &&str| fs::write("profile", profile))
+    ///     .item("skip", |_| {})
+    ///     .build()
+    ///     .wait();
+    /// ```
+    pub fn try_item<E>(
+        mut self,
+        item: T,
+        mut callback: impl FnMut(&T) -> Result<(), E> + Send + 'static,
+    ) -> Self
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.fallible.insert(
+            self.items.len(),
+            Box::new(move |item: &T| callback(item).map_err(Into::into)),
+        );
+        self.items.push((item, |_| {}));
+        self
+    }
+
+    /// Appends `(item, callback)` pairs whose callbacks can fail; see
+    /// [`DropDownBuilder::try_item`].
+    pub fn try_items<I, G, E>(self, items: I) -> Self
+    where
+        I: IntoIterator<Item = (T, G)>,
+        G: FnMut(&T) -> Result<(), E> + Send + 'static,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        items.into_iter().fold(self, |builder, (item, callback)| {
+            builder.try_item(item, callback)
+        })
+    }
+
+    /// Appends items without callbacks, for menus whose outcome is only read from
+    /// [`wait`](TerminalDropDown::wait).
+    pub fn values<I>(mut self, items: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.items
+            .extend(items.into_iter().map(|item| (item, (|_| {}) as fn(&T))));
+        self
+    }
+}
+
+/// Collects items without callbacks; see [`DropDownBuilder::values`].
+///
+/// # Example
+/// ```no_run
+/// use ter_menu::DropDownBuilder;
+///
+/// let builder: DropDownBuilder<String, _> = std::env::args().skip(1).collect();
+/// let result = builder.build().wait();
+/// ```
+impl<T> FromIterator<T> for DropDownBuilder<T, fn(&T)>
+where
+    T: MenuEntry + Clone + Send + 'static,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(items: I) -> Self {
+        Self::new().values(items)
+    }
+}
+
+impl<T> From<Vec<T>> for DropDownBuilder<T, fn(&T)>
+where
+    T: MenuEntry + Clone + Send + 'static,
+{
+    fn from(items: Vec<T>) -> Self {
+        Self::new().values(items)
+    }
+}
+
+/// Collects `(label, value)` pairs into [`MenuItem`]s without callbacks, so that the value of
+/// the chosen one comes back from [`wait`](TerminalDropDown::wait).
+impl<L, V> FromIterator<(L, V)> for DropDownBuilder<MenuItem<V>, fn(&MenuItem<V>)>
+where
+    L: Into<String>,
+    V: Clone + Send + 'static,
+{
+    fn from_iter<I: IntoIterator<Item = (L, V)>>(items: I) -> Self {
+        Self::new().values(items.into_iter().map(MenuItem::from))
+    }
+}
+
+/// Lists the entries of `map` as [`MenuItem`]s labeled with their keys, in key order.
+///
+/// # Example
+/// ```no_run
+/// use std::collections::BTreeMap;
+/// use ter_menu::DropDownBuilder;
+///
+/// let ports = BTreeMap::from([("http", 80), ("https", 443), ("ssh", 22)]);
+/// let result = DropDownBuilder::from(ports).build().wait();
+/// let port = result.selected().map(|item| item.value);
+/// ```
+impl<K, V> From<BTreeMap<K, V>> for DropDownBuilder<MenuItem<V>, fn(&MenuItem<V>)>
+where
+    K: Into<String>,
+    V: Clone + Send + 'static,
+{
+    fn from(map: BTreeMap<K, V>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+impl<T, F> Default for DropDownBuilder<T, F>
+where
+    T: MenuEntry + Clone + Send + 'static,
+    F: FnOnce(&T) + Send + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,35 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// How often a cancellable menu wakes up from waiting for input to check its [`CancelHandle`].
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Closes a running menu from another thread, e.g. when a background task makes the choice moot.
+///
+/// Obtained from [`TerminalDropDown::cancel_handle`](crate::TerminalDropDown::cancel_handle) or
+/// [`TerminalMultiSelect::cancel_handle`](crate::TerminalMultiSelect::cancel_handle). Handles are
+/// cheap to clone and can be moved to any thread; they stay valid after the menu has closed, when
+/// cancelling does nothing.
+///
+/// # Behavior
+/// The menu notices the request within a few milliseconds, restores the terminal, prints its
+/// cancel message and reports [`SelectionResult::Cancelled`](crate::SelectionResult), exactly as
+/// if the user had pressed Escape. A menu that fell back to the line prompt because stdin is not
+/// a terminal only notices once the current line has been read.
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelHandle {
+    /// Asks the menu to close as if the user had cancelled it.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [`CancelHandle::cancel`] has been called on this handle or one of its clones.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,646 @@
+use crate::builder::DropDownBuilder;
+use crate::cancel::CancelHandle;
+use crate::error::TerMenuError;
+use crate::hooks::{Hooks, TryCallback};
+use crate::item::{MenuEntry, follow};
+use crate::live::ItemsHandle;
+use crate::menu::{Menu, MenuConfig, display_menu, display_menu_to};
+use crate::result::SelectionResult;
+use crate::selection::{Interaction, Selection};
+use crate::strings::fill;
+use crate::worker::Worker;
+use std::collections::HashMap;
+use std::error::Error;
+use std::hash::Hash;
+use std::io::{self, Write};
+use std::marker::PhantomData;
+use std::panic::{self, AssertUnwindSafe};
+use std::thread;
+use std::time::Duration;
+
+/// What the interaction thread hands back: the path to the confirmed item and how the user got
+/// there.
+type Outcome<T> = (SelectionResult<Vec<T>>, Interaction);
+
+/// A terminal-based interactive dropdown selection component.
+///
+/// Allows users to navigate through options using keyboard arrows, confirm selections with Enter,
+/// and cancel with Escape. Uses raw terminal mode for input handling and provides visual feedback
+/// with highlighted selected items.
+///
+/// # Type Parameters
+/// * `T` - The type of items in the dropdown. Must be cloneable and sendable to the interaction
+///   thread; only [`TerminalDropDown::use_drop_down`] also needs `Hash` and `Eq`.
+/// * `F` - The type of callback function triggered when an item is selected. Takes a reference to `T`
+///   as a parameter. When the item was chosen in a submenu, the callback paired with the top-level
+///   item that opened it runs with the chosen item.
+#[derive(Debug)]
+pub struct TerminalDropDown<T, F>
+where
+    T: MenuEntry + Clone + Send + 'static,
+    F: FnOnce(&T) + Send + 'static,
+{
+    /// Produces the path to the confirmed item, see [`TerminalDropDown::wait_path`], and how the
+    /// user got there.
+    worker: Worker<Outcome<T>>,
+    cancel: CancelHandle,
+    items: ItemsHandle<T>,
+    callbacks: PhantomData<F>,
+}
+
+impl<T, F> TerminalDropDown<T, F>
+where
+    T: MenuEntry + Clone + Send + 'static,
+    F: FnOnce(&T) + Send + 'static,
+{
+    /// Creates a new TerminalDropDown instance and starts the interaction thread.
+    ///
+    /// # Parameters
+    /// * `drop_down` - A HashMap containing items as keys and their corresponding callback functions
+    ///   as values.
+    /// * `item_n` - Maximum number of items to display in the terminal at once.
+    ///
+    /// # Returns
+    /// A new TerminalDropDown instance ready for user interaction.
+    ///
+    /// # Behavior
+    /// Spawns a new thread that handles user input, maintains selection state, and updates the display.
+    /// Enables raw terminal mode for low-level input handling and properly cleans up resources.
+    ///
+    /// Items are shown in the HashMap's iteration order, which is unspecified and may change between
+    /// runs. Prefer [`TerminalDropDown::use_ordered_drop_down`] when the order matters; this
+    /// constructor is kept for compatibility.
+    pub fn use_drop_down(drop_down: HashMap<T, F>, item_n: usize) -> Self
+    where
+        T: Hash + Eq,
+    {
+        Self::use_ordered_drop_down(drop_down.into_iter().collect(), item_n)
+    }
+
+    /// Creates a new TerminalDropDown instance whose items are rendered in insertion order.
+    ///
+    /// # Parameters
+    /// * `drop_down` - A Vec of `(item, callback)` pairs. Items are displayed in the order given.
+    /// * `item_n` - Maximum number of items to display in the terminal at once.
+    ///
+    /// # Returns
+    /// A new TerminalDropDown instance ready for user interaction.
+    ///
+    /// # Behavior
+    /// Same as [`TerminalDropDown::use_drop_down`]: spawns the interaction thread immediately and
+    /// invokes the callback paired with the confirmed item.
+    ///
+    /// The items are moved into the interaction thread rather than copied, and the confirmed one
+    /// is moved back out by [`wait`](TerminalDropDown::wait); only items chosen inside a submenu
+    /// are cloned. Large items therefore cost nothing extra to list.
+    pub fn use_ordered_drop_down(drop_down: Vec<(T, F)>, item_n: usize) -> Self {
+        Self::spawn(
+            drop_down,
+            HashMap::new(),
+            ItemsHandle::new(),
+            MenuConfig::new(item_n),
+            Hooks::default(),
+        )
+    }
+
+    /// Creates a new TerminalDropDown instance with a fuzzy filter line above the items.
+    ///
+    /// # Parameters
+    /// * `drop_down` - A Vec of `(item, callback)` pairs, shown in the order given while the filter
+    ///   is empty.
+    /// * `item_n` - Maximum number of items to display in the terminal at once.
+    ///
+    /// # Behavior
+    /// Typing narrows the list to items that fuzzily match the filter text, best matches first,
+    /// with the matched characters highlighted. See [`select_filtered`](crate::select_filtered)
+    /// for the full key handling.
+    pub fn use_filtered_drop_down(drop_down: Vec<(T, F)>, item_n: usize) -> Self {
+        let config = MenuConfig {
+            filter: true,
+            ..MenuConfig::new(item_n)
+        };
+        Self::spawn(
+            drop_down,
+            HashMap::new(),
+            ItemsHandle::new(),
+            config,
+            Hooks::default(),
+        )
+    }
+
+    /// Returns a [`DropDownBuilder`] for configuring a dropdown before it is shown.
+    pub fn builder() -> DropDownBuilder<T, F> {
+        DropDownBuilder::new()
+    }
+
+    /// Shows a dropdown as a future on the current task instead of spawning a thread.
+    ///
+    /// Requires the `async` feature. Input is read through crossterm's `EventStream`, so the
+    /// interaction can be raced against shutdown signals with `select!`; dropping the future
+    /// restores the terminal. This is also how the menu is cancelled from elsewhere, as there
+    /// is no [`CancelHandle`] for it.
+    ///
+    /// # Parameters
+    /// * `drop_down` - A Vec of `(item, callback)` pairs. Items are displayed in the order given.
+    /// * `item_n` - Maximum number of items to display in the terminal at once.
+    ///
+    /// # Returns
+    /// The [`SelectionResult`] of the interaction. The confirmed item's callback has run by the
+    /// time the future resolves; if it panicked, the result is an error as with
+    /// [`wait`](TerminalDropDown::wait).
+    #[cfg(feature = "async")]
+    pub async fn select_async(drop_down: Vec<(T, F)>, item_n: usize) -> SelectionResult<T> {
+        Self::run_async(
+            drop_down,
+            HashMap::new(),
+            ItemsHandle::new(),
+            MenuConfig::new(item_n),
+            Hooks::default(),
+        )
+        .await
+    }
+
+    /// Runs the menu as a future; see [`DropDownBuilder::select_async`]. Options that need the
+    /// menu to wake up without a key press, or to block, make it fail with
+    /// [`TerMenuError::Unsupported`] before anything is drawn.
+    #[cfg(feature = "async")]
+    pub(crate) async fn run_async(
+        drop_down: Vec<(T, F)>,
+        mut fallible: HashMap<usize, TryCallback<T>>,
+        stream: ItemsHandle<T>,
+        mut config: MenuConfig,
+        mut hooks: Hooks<T>,
+    ) -> SelectionResult<T> {
+        let unsupported = [
+            ("timeout", config.timeout.is_some()),
+            ("undo_window", config.undo_window.is_some()),
+            ("item_stream", stream.has_changes()),
+            ("events", config.events.is_some()),
+            ("retry_on_error", config.retry_prompt),
+        ];
+        if let Some((option, _)) = unsupported.into_iter().find(|&(_, set)| set) {
+            return SelectionResult::Error(TerMenuError::Unsupported(option));
+        }
+        let finish = hooks.finish_message.take();
+        if finish.is_some() {
+            // The status line takes the place of the confirm message.
+            config.confirm_message.clear();
+        }
+        let (options, callbacks): (Vec<T>, Vec<F>) = drop_down.into_iter().unzip();
+        let mut menu = Menu::new(&options, config).with_hooks(&mut hooks);
+        let path = match menu.run_async().await {
+            Ok(Some(idx)) => menu.path_to(idx),
+            Ok(None) => return SelectionResult::Cancelled,
+            Err(e) => return e.into(),
+        };
+        let item = menu.item_on(&path);
+        let called = panic::catch_unwind(AssertUnwindSafe(|| match fallible.get_mut(&path[0]) {
+            Some(func) => func(item).map_err(TerMenuError::Callback),
+            None => {
+                if let Some(func) = callbacks.into_iter().nth(path[0]) {
+                    func(item);
+                }
+                Ok(())
+            }
+        }));
+        let called = match called {
+            Ok(called) => called,
+            Err(payload) => return TerMenuError::from_panic(payload).into(),
+        };
+        if let Some(finish) = &finish {
+            let status = finish(item, called.as_ref().map_err(cause).copied());
+            if let Err(e) = menu.print_status(status) {
+                return TerMenuError::from(e).into();
+            }
+        }
+        if let Err(e) = called {
+            return e.into();
+        }
+        drop(menu);
+        let mut items = take_path(options, &path);
+        SelectionResult::Selected(items.pop().expect("a selection path is never empty"))
+    }
+
+    /// Starts the interaction thread. Changes requested through `items` before then are applied
+    /// before the menu is first drawn. The items at the indices in `fallible` run that callback
+    /// in place of their own.
+    pub(crate) fn spawn(
+        drop_down: Vec<(T, F)>,
+        mut fallible: HashMap<usize, TryCallback<T>>,
+        items: ItemsHandle<T>,
+        config: MenuConfig,
+        hooks: Hooks<T>,
+    ) -> Self {
+        let (options, callbacks): (Vec<T>, Vec<F>) = drop_down.into_iter().unzip();
+        let mut callbacks: Vec<Option<F>> = callbacks.into_iter().map(Some).collect();
+        let on_select = move |idx, item: &T| {
+            if let Some(func) = fallible.get_mut(&idx) {
+                return func(item).map_err(TerMenuError::Callback);
+            }
+            if let Some(func) = callbacks.get_mut(idx).and_then(Option::take) {
+                func(item);
+            }
+            Ok(())
+        };
+        let (worker, cancel) = run_thread(options, on_select, items.clone(), config, hooks);
+        Self {
+            worker,
+            cancel,
+            items,
+            callbacks: PhantomData,
+        }
+    }
+
+    /// Renders the current state of the dropdown menu in the terminal.
+    ///
+    /// # Parameters
+    /// * `options` - Slice of all available items in the dropdown.
+    /// * `current_idx` - Index of the currently selected item.
+    /// * `max_show` - Maximum number of items to display at once.
+    ///
+    /// # Behavior
+    /// Clears the terminal, displays a header with total/max items, renders visible items with
+    /// highlighting for the selected item, and shows navigation instructions. Implements a sliding
+    /// window for when there are more items than can be displayed at once.
+    pub fn display_menu(options: &[T], current_idx: usize, max_show: usize) {
+        display_menu(options, current_idx, max_show);
+    }
+
+    /// Like [`TerminalDropDown::display_menu`], but draws into `out` instead of stdout, e.g. a
+    /// PTY the caller manages or a buffer in a test.
+    ///
+    /// # Returns
+    /// Any error from writing to `out`.
+    pub fn display_menu_to<W: Write>(
+        out: &mut W,
+        options: &[T],
+        current_idx: usize,
+        max_show: usize,
+    ) -> io::Result<()> {
+        display_menu_to(out, options, current_idx, max_show)
+    }
+
+    /// Closes the menu from the calling thread as if the user had pressed Escape: the terminal is
+    /// restored and [`wait`](TerminalDropDown::wait) returns [`SelectionResult::Cancelled`].
+    /// Does nothing if the interaction has already finished.
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    /// Returns a [`CancelHandle`] for closing the menu from another thread, e.g. from a
+    /// background task whose result makes the choice moot.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+
+    /// Returns an [`ItemsHandle`] for adding and removing items from another thread while the
+    /// menu is open, e.g. hosts discovered on the network. Items added this way have no callback;
+    /// if one of them is confirmed, [`wait`](TerminalDropDown::wait) still returns it.
+    pub fn items_handle(&self) -> ItemsHandle<T> {
+        self.items.clone()
+    }
+
+    /// Returns `true` once the interaction is over and its outcome is ready, so that
+    /// [`wait`](TerminalDropDown::wait) would not block.
+    pub fn is_finished(&self) -> bool {
+        self.worker.is_finished()
+    }
+
+    /// Returns the outcome if the interaction is over, without blocking. Suited to event loops
+    /// that poll their components once per frame.
+    ///
+    /// # Returns
+    /// `Some` with the same [`SelectionResult`] [`wait`](TerminalDropDown::wait) would return,
+    /// or `None` while the user is still deciding. The outcome is handed out only once: later
+    /// calls return `None`.
+    pub fn try_wait(&mut self) -> Option<SelectionResult<T>> {
+        self.worker
+            .try_join()
+            .map(|joined| outcome(joined.map(|(result, _)| result)).map(leaf))
+    }
+
+    /// Like [`try_wait`](TerminalDropDown::try_wait), but blocks for up to `timeout` for the
+    /// interaction to end.
+    pub fn wait_timeout(&mut self, timeout: Duration) -> Option<SelectionResult<T>> {
+        self.worker
+            .join_timeout(timeout)
+            .map(|joined| outcome(joined.map(|(result, _)| result)).map(leaf))
+    }
+
+    /// Blocks until the user interaction thread completes.
+    ///
+    /// # Returns
+    /// A [`SelectionResult`] describing the outcome: the selected item, a cancellation, or an
+    /// error if the terminal could not be driven or the selection callback panicked or failed.
+    /// Once [`try_wait`](TerminalDropDown::try_wait) or
+    /// [`wait_timeout`](TerminalDropDown::wait_timeout) returned the outcome, the error is
+    /// [`TerMenuError::OutcomeTaken`].
+    ///
+    /// # Usage
+    /// Call this method after creating the TerminalDropDown to wait for user input completion.
+    pub fn wait(self) -> SelectionResult<T> {
+        self.wait_path().map(leaf)
+    }
+
+    /// Like [`wait`](TerminalDropDown::wait), but returns every item along the way to the
+    /// confirmed one: the top-level item first, then the item chosen in each submenu opened
+    /// from it, e.g. `[Settings, Network, DNS]`. Outside submenus the path holds just the
+    /// confirmed item.
+    pub fn wait_path(self) -> SelectionResult<Vec<T>> {
+        match self.worker.join() {
+            Some(joined) => outcome(joined.map(|(result, _)| result)),
+            None => SelectionResult::Error(TerMenuError::OutcomeTaken),
+        }
+    }
+
+    /// Like [`wait`](TerminalDropDown::wait), but also reports the position of the confirmed
+    /// item, how long the menu was open, how many keys were pressed and why it closed if nothing
+    /// was chosen.
+    ///
+    /// # Returns
+    /// A [`Selection`] for a confirmed item as well as for a cancellation, including Ctrl-C,
+    /// or a [`TerMenuError`] if the terminal could not be driven or the selection callback
+    /// panicked.
+    pub fn wait_selection(self) -> Result<Selection<T>, TerMenuError> {
+        let (result, interaction) = match self.worker.join() {
+            Some(Ok(joined)) => joined,
+            Some(Err(payload)) => return Err(TerMenuError::from_panic(payload)),
+            None => return Err(TerMenuError::OutcomeTaken),
+        };
+        let value = match result {
+            SelectionResult::Selected(path) => Some(leaf(path)),
+            SelectionResult::Cancelled | SelectionResult::Interrupted => None,
+            SelectionResult::Error(e) => return Err(e),
+        };
+        Ok(Selection {
+            value,
+            index: interaction.index,
+            elapsed: interaction.elapsed,
+            keystrokes: interaction.keystrokes,
+            cancelled_by: interaction.cancelled_by,
+        })
+    }
+}
+
+impl<T> TerminalDropDown<T, fn(&T)>
+where
+    T: MenuEntry + Clone + Send + 'static,
+{
+    /// Creates a new TerminalDropDown instance with one callback for all items, told which item
+    /// was chosen by its position.
+    ///
+    /// # Parameters
+    /// * `items` - The items, displayed in the order given.
+    /// * `item_n` - Maximum number of items to display in the terminal at once.
+    /// * `on_select` - Called with the index in `items` and the confirmed item. For an item
+    ///   chosen inside a submenu, the index is that of the top-level item it was opened from.
+    ///
+    /// # Behavior
+    /// Same as [`TerminalDropDown::use_ordered_drop_down`], but no callback per item is needed,
+    /// so items without `Eq` or `Hash`, such as floating-point values, are listed as easily as
+    /// any other.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ter_menu::TerminalDropDown;
+    ///
+    /// let scales = vec![0.5, 1.0, 1.5, 2.0];
+    /// let result = TerminalDropDown::use_indexed_drop_down(scales, 4, |idx, scale| {
+    ///     println!("Preset {} scales by {}", idx, scale);
+    /// })
+    /// .wait();
+    /// ```
+    pub fn use_indexed_drop_down(
+        items: Vec<T>,
+        item_n: usize,
+        on_select: impl FnOnce(usize, &T) + Send + 'static,
+    ) -> Self {
+        let handle = ItemsHandle::new();
+        let (worker, cancel) = run_thread(
+            items,
+            {
+                let mut on_select = Some(on_select);
+                move |idx, item: &T| {
+                    if let Some(on_select) = on_select.take() {
+                        on_select(idx, item);
+                    }
+                    Ok(())
+                }
+            },
+            handle.clone(),
+            MenuConfig::new(item_n),
+            Hooks::default(),
+        );
+        Self {
+            worker,
+            cancel,
+            items: handle,
+            callbacks: PhantomData,
+        }
+    }
+
+    /// Creates a new TerminalDropDown instance whose callbacks can fail, e.g. because they write
+    /// a file or call a service.
+    ///
+    /// # Parameters
+    /// * `drop_down` - A Vec of `(item, callback)` pairs. Items are displayed in the order given.
+    /// * `item_n` - Maximum number of items to display in the terminal at once.
+    ///
+    /// # Behavior
+    /// Same as [`TerminalDropDown::use_ordered_drop_down`], but if the confirmed item's callback
+    /// returns an error, [`wait`](TerminalDropDown::wait) returns [`SelectionResult::Error`] with
+    /// [`TerMenuError::Callback`] carrying it, so that the caller need not stash it somewhere the
+    /// callback can reach.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::fs;
+    /// use std::io;
+    /// use ter_menu::{SelectionResult, TerMenuError, TerminalDropDown};
+    ///
+    /// let save = |profile: &&str| -> io::Result<()> { fs::write("profile", profile) };
+    /// let result = TerminalDropDown::use_fallible_drop_down(
+    ///     vec![("default", save), ("minimal", save)],
+    ///     5,
+    /// )
+    /// .wait();
+    /// if let SelectionResult::Error(TerMenuError::Callback(e)) = result {
+    ///     eprintln!("Could not save the profile: {}", e);
+    /// }
+    /// ```
+    pub fn use_fallible_drop_down<G, E>(drop_down: Vec<(T, G)>, item_n: usize) -> Self
+    where
+        G: FnMut(&T) -> Result<(), E> + Send + 'static,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        DropDownBuilder::new()
+            .page_size(item_n)
+            .try_items(drop_down)
+            .build()
+    }
+}
+
+/// Runs the menu on a thread of its own and calls `on_select` with the index of the confirmed
+/// top-level item and the confirmed item itself. Items added through `items` have no index, so
+/// confirming one of them skips `on_select`. An error from `on_select` becomes the outcome,
+/// unless `config` asks the user what to do about it; see [`after_failure`]. A `finish_message`
+/// hook among `hooks` prints its status line once `on_select` has run.
+fn run_thread<T, C>(
+    options: Vec<T>,
+    mut on_select: C,
+    items: ItemsHandle<T>,
+    mut config: MenuConfig,
+    mut hooks: Hooks<T>,
+) -> (Worker<Outcome<T>>, CancelHandle)
+where
+    T: MenuEntry + Clone + Send + 'static,
+    C: FnMut(usize, &T) -> Result<(), TerMenuError> + Send + 'static,
+{
+    let cancel = CancelHandle::default();
+    let worker_cancel = cancel.clone();
+    let worker = Worker::spawn(move || {
+        let mut interaction = Interaction::default();
+        let follow_up = config.retry_prompt.then(|| config.clone());
+        let finish = hooks.finish_message.take();
+        if finish.is_some() {
+            // The status line takes the place of the confirm message.
+            config.confirm_message.clear();
+        }
+        let selection = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut menu = Menu::live(options, items, config)
+                .with_hooks(&mut hooks)
+                .with_cancel(worker_cancel);
+            loop {
+                let outcome = menu.run();
+                interaction = menu.interaction();
+                let path = match outcome {
+                    Ok(Some(idx)) => menu.path_to(idx),
+                    Ok(None) => return Ok(None),
+                    Err(e) => return Err(e),
+                };
+                interaction.index = Some(path[0]);
+                // Items added through the handle come without a callback.
+                if let Some(origin) = menu.origin(path[0]) {
+                    let item = menu.item_on(&path);
+                    let decided = loop {
+                        let Err(e) = on_select(origin, item) else {
+                            break Ok(false);
+                        };
+                        let Some(config) = &follow_up else {
+                            break Err(e);
+                        };
+                        match after_failure(config, &e)? {
+                            AfterFailure::Retry => {}
+                            AfterFailure::PickAnother => break Ok(true),
+                            AfterFailure::Abort => break Err(e),
+                        }
+                    };
+                    match decided {
+                        Ok(false) => {}
+                        Ok(true) => {
+                            menu.reopen();
+                            continue;
+                        }
+                        Err(e) => {
+                            if let Some(finish) = &finish {
+                                menu.print_status(finish(item, Err(cause(&e))))?;
+                            }
+                            return Err(e);
+                        }
+                    }
+                }
+                if let Some(finish) = &finish {
+                    menu.print_status(finish(menu.item_on(&path), Ok(())))?;
+                }
+                return Ok(Some(take_path(menu.into_options(), &path)));
+            }
+        }));
+        match selection {
+            Ok(Ok(Some(items))) => (SelectionResult::Selected(items), interaction),
+            Ok(Ok(None)) => (SelectionResult::Cancelled, interaction),
+            Ok(Err(e)) => (e.into(), interaction),
+            Err(payload) => {
+                let error = TerMenuError::from_panic(payload);
+                (SelectionResult::Error(error), interaction)
+            }
+        }
+    });
+    (worker, cancel)
+}
+
+/// The error a failed callback returned, or `error` itself if it failed otherwise, as handed to
+/// a `finish_message` hook.
+pub(crate) fn cause(error: &TerMenuError) -> &(dyn Error + Send + Sync + 'static) {
+    match error {
+        TerMenuError::Callback(cause) => cause.as_ref(),
+        e => e,
+    }
+}
+
+/// What the user wants done about a failed callback.
+pub(crate) enum AfterFailure {
+    Retry,
+    PickAnother,
+    Abort,
+}
+
+/// Asks the user whether to run the callback that failed with `error` again, go back to the menu
+/// set up with `config` to pick another item, or give up. Escape gives up.
+pub(crate) fn after_failure(
+    config: &MenuConfig,
+    error: &TerMenuError,
+) -> Result<AfterFailure, TerMenuError> {
+    let strings = &config.strings;
+    let cause = match error {
+        TerMenuError::Callback(e) => e.to_string(),
+        e => e.to_string(),
+    };
+    let answers = [
+        strings.retry.clone(),
+        strings.pick_another.clone(),
+        strings.abort.clone(),
+    ];
+    let config = MenuConfig {
+        prompt: fill(&strings.action_failed, &[("error", &cause)]),
+        confirm_message: String::new(),
+        cancel_message: String::new(),
+        keymap: config.keymap.clone(),
+        theme: config.theme.clone(),
+        plain: config.plain,
+        inline: config.inline,
+        alternate_screen: config.alternate_screen,
+        output: config.output.clone(),
+        events: config.events.clone(),
+        strings: strings.clone(),
+        ..MenuConfig::new(answers.len())
+    };
+    Ok(match Menu::new(&answers, config).run()? {
+        Some(0) => AfterFailure::Retry,
+        Some(1) => AfterFailure::PickAnother,
+        _ => AfterFailure::Abort,
+    })
+}
+
+/// Takes the items along `path` out of `options`, cloning those that sit inside submenus.
+fn take_path<T: MenuEntry + Clone>(mut options: Vec<T>, path: &[usize]) -> Vec<T> {
+    let nested: Vec<T> = match follow(&options, path).split_first() {
+        Some((_, nested)) => nested.iter().map(|&item| item.clone()).collect(),
+        None => Vec::new(),
+    };
+    let mut items = vec![options.swap_remove(path[0])];
+    items.extend(nested);
+    items
+}
+
+/// The confirmed item at the end of a selection path.
+fn leaf<T>(mut path: Vec<T>) -> T {
+    path.pop().expect("a selection path is never empty")
+}
+
+/// Turns a panic of the interaction thread into an error result.
+pub(crate) fn outcome<R>(joined: thread::Result<SelectionResult<R>>) -> SelectionResult<R> {
+    match joined {
+        Ok(result) => result,
+        Err(payload) => SelectionResult::Error(TerMenuError::from_panic(payload)),
+    }
+}
@@ -0,0 +1,83 @@
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Errors that can end a menu interaction.
+///
+/// # Variants
+/// * `RawMode` - The terminal could not be switched into raw mode (for example because stdin is
+///   not a terminal).
+/// * `EventRead` - Reading the next input event from the terminal failed.
+/// * `Io` - Writing the menu to the terminal failed.
+/// * `Poisoned` - The interaction thread or a selection callback panicked, so the menu state can
+///   no longer be trusted. Carries the panic message.
+/// * `Interrupted` - The user pressed Ctrl-C. The terminal has been restored; callers usually
+///   exit as a shell tool would.
+/// * `Callback` - The fallible callback of the confirmed item returned an error; see
+///   [`TerminalDropDown::use_fallible_drop_down`](crate::TerminalDropDown::use_fallible_drop_down).
+///   Carries that error.
+/// * `Unsupported` - A builder option was set that the way the menu is run cannot honour, e.g. a
+///   timeout on `DropDownBuilder::select_async`. Carries the name of the option.
+/// * `OutcomeTaken` - The outcome of a menu was asked for after `try_wait` or `wait_timeout`
+///   had already returned it.
+#[derive(Debug)]
+pub enum TerMenuError {
+    RawMode(io::Error),
+    EventRead(io::Error),
+    Io(io::Error),
+    Poisoned(String),
+    Interrupted,
+    Callback(Box<dyn Error + Send + Sync>),
+    Unsupported(&'static str),
+    OutcomeTaken,
+}
+
+impl TerMenuError {
+    /// Builds a `Poisoned` error from a panic payload caught by `catch_unwind` or
+    /// `JoinHandle::join`.
+    pub(crate) fn from_panic(payload: Box<dyn Any + Send>) -> Self {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "unknown panic".to_string()
+        };
+        TerMenuError::Poisoned(message)
+    }
+}
+
+impl fmt::Display for TerMenuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TerMenuError::RawMode(e) => write!(f, "Failed to enable raw mode: {}", e),
+            TerMenuError::EventRead(e) => write!(f, "Failed to read event: {}", e),
+            TerMenuError::Io(e) => write!(f, "Failed to write to the terminal: {}", e),
+            TerMenuError::Poisoned(message) => write!(f, "Menu panicked: {}", message),
+            TerMenuError::Interrupted => write!(f, "Interrupted by Ctrl-C"),
+            TerMenuError::Callback(e) => write!(f, "Selection callback failed: {}", e),
+            TerMenuError::Unsupported(option) => write!(f, "`{}` is not supported here", option),
+            TerMenuError::OutcomeTaken => write!(f, "The outcome was already returned"),
+        }
+    }
+}
+
+impl Error for TerMenuError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TerMenuError::RawMode(e) | TerMenuError::EventRead(e) | TerMenuError::Io(e) => Some(e),
+            TerMenuError::Callback(e) => Some(e.as_ref()),
+            TerMenuError::Poisoned(_)
+            | TerMenuError::Interrupted
+            | TerMenuError::Unsupported(_)
+            | TerMenuError::OutcomeTaken => None,
+        }
+    }
+}
+
+impl From<io::Error> for TerMenuError {
+    fn from(e: io::Error) -> Self {
+        TerMenuError::Io(e)
+    }
+}
@@ -0,0 +1,168 @@
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+/// Where a menu reads its input events from.
+///
+/// Menus read from the terminal by default. Another source, such as [`ScriptedEvents`], can be
+/// plugged in with [`DropDownBuilder::events`](crate::DropDownBuilder::events). The methods
+/// mirror crossterm's [`event::poll`] and [`event::read`].
+///
+/// A menu with its own event source leaves the local terminal alone: it neither enables raw mode
+/// nor asks for the terminal's size. To drive one over a remote PTY, e.g. an SSH session, decode
+/// the session's input into events, send an [`Event::Resize`] with the PTY's size first, and draw
+/// into the session with [`Output::writer`](crate::Output::writer).
+///
+/// # Example
+/// ```no_run
+/// use crossterm::event::Event;
+/// use std::io;
+/// use std::sync::mpsc::{Receiver, RecvTimeoutError};
+/// use std::time::Duration;
+/// use ter_menu::EventSource;
+///
+/// /// Events decoded from a remote session on another thread.
+/// struct Remote {
+///     events: Receiver<Event>,
+///     next: Option<Event>,
+/// }
+///
+/// impl EventSource for Remote {
+///     fn poll(&mut self, timeout: Duration) -> io::Result<bool> {
+///         if self.next.is_none() {
+///             match self.events.recv_timeout(timeout) {
+///                 Ok(event) => self.next = Some(event),
+///                 Err(RecvTimeoutError::Timeout) => return Ok(false),
+///                 // Let `read` report the closed session.
+///                 Err(RecvTimeoutError::Disconnected) => {}
+///             }
+///         }
+///         Ok(true)
+///     }
+///
+///     fn read(&mut self) -> io::Result<Event> {
+///         match self.next.take() {
+///             Some(event) => Ok(event),
+///             None => self
+///                 .events
+///                 .recv()
+///                 .map_err(|_| io::Error::new(io::ErrorKind::UnexpectedEof, "session closed")),
+///         }
+///     }
+/// }
+/// ```
+pub trait EventSource: Send {
+    /// Waits up to `timeout` for an event to become available.
+    ///
+    /// # Returns
+    /// `true` if [`EventSource::read`] will return without blocking.
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool>;
+
+    /// Returns the next event, blocking until one is available.
+    fn read(&mut self) -> io::Result<Event>;
+}
+
+/// The terminal's input, read through crossterm.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TerminalEvents;
+
+impl EventSource for TerminalEvents {
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool> {
+        event::poll(timeout)
+    }
+
+    fn read(&mut self) -> io::Result<Event> {
+        event::read()
+    }
+}
+
+/// A fixed list of events, for driving menus in tests without a terminal.
+///
+/// Once every event has been read, [`EventSource::read`] fails with
+/// [`io::ErrorKind::UnexpectedEof`], so a script that never confirms or cancels ends the menu
+/// with an error instead of hanging.
+///
+/// # Example
+/// ```
+/// use crossterm::event::KeyCode;
+/// use ter_menu::ScriptedEvents;
+///
+/// let script = ScriptedEvents::keys([KeyCode::Down, KeyCode::Down, KeyCode::Enter]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedEvents {
+    events: VecDeque<Event>,
+}
+
+impl ScriptedEvents {
+    /// A script of arbitrary events, including resizes and mouse events.
+    pub fn new(events: impl IntoIterator<Item = Event>) -> Self {
+        Self {
+            events: events.into_iter().collect(),
+        }
+    }
+
+    /// A script of key presses without modifiers.
+    pub fn keys(keys: impl IntoIterator<Item = KeyCode>) -> Self {
+        Self::new(
+            keys.into_iter()
+                .map(|code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE))),
+        )
+    }
+
+    /// A script typing `text` one character at a time.
+    pub fn text(text: &str) -> Self {
+        Self::keys(text.chars().map(KeyCode::Char))
+    }
+
+    /// Appends the events of `other`.
+    pub fn then(mut self, other: ScriptedEvents) -> Self {
+        self.events.extend(other.events);
+        self
+    }
+}
+
+impl EventSource for ScriptedEvents {
+    /// Always `true`: either an event is waiting, or the script is exhausted and
+    /// [`EventSource::read`] fails right away.
+    fn poll(&mut self, _timeout: Duration) -> io::Result<bool> {
+        Ok(true)
+    }
+
+    fn read(&mut self) -> io::Result<Event> {
+        self.events
+            .pop_front()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "no more scripted events"))
+    }
+}
+
+/// An [`EventSource`] shared between the builder and the menus it shows, so that a
+/// [`ReusableDropDown`](crate::ReusableDropDown) keeps reading from the same source.
+#[derive(Clone)]
+pub(crate) struct SharedEvents(Arc<Mutex<dyn EventSource>>);
+
+impl SharedEvents {
+    pub(crate) fn new(source: impl EventSource + 'static) -> Self {
+        Self(Arc::new(Mutex::new(source)))
+    }
+
+    pub(crate) fn poll(&self, timeout: Duration) -> io::Result<bool> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .poll(timeout)
+    }
+
+    pub(crate) fn read(&self) -> io::Result<Event> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).read()
+    }
+}
+
+impl fmt::Debug for SharedEvents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SharedEvents(..)")
+    }
+}
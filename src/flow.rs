@@ -0,0 +1,212 @@
+use crate::error::TerMenuError;
+use crate::events::{EventSource, SharedEvents};
+use crate::item::MenuEntry;
+use crate::menu::{Menu, MenuConfig};
+use crate::output::Output;
+use crate::strings::Strings;
+use crate::theme::Theme;
+
+/// One prompt of a [`Flow`]: shows a menu built from the answers so far and records its answer.
+///
+/// # Returns
+/// `Ok(false)` if the user cancelled the prompt.
+type Step<'a, S> = Box<dyn FnOnce(&mut S, &MenuConfig) -> Result<bool, TerMenuError> + 'a>;
+
+/// A sequence of prompts that fill in one result, for interactive setup wizards.
+///
+/// Each step shows its menu on the current thread once the previous one was answered. Options and
+/// questions are produced from the answers given so far, and each answer is stored in the
+/// caller's state `S`, which [`Flow::run`] returns once every step is answered.
+///
+/// # Type Parameters
+/// * `S` - The combined result, typically a struct with one field per step.
+///
+/// # Example
+/// ```no_run
+/// use ter_menu::Flow;
+///
+/// #[derive(Default)]
+/// struct Setup {
+///     environment: String,
+///     features: Vec<String>,
+///     deploy: bool,
+/// }
+///
+/// let setup = Flow::new(Setup::default())
+///     .select(
+///         "Environment:",
+///         |_| vec!["staging".to_string(), "production".to_string()],
+///         |setup, environment| setup.environment = environment,
+///     )
+///     .multi_select(
+///         "Features:",
+///         |setup| match setup.environment.as_str() {
+///             "staging" => vec!["metrics".to_string(), "debug-ui".to_string()],
+///             _ => vec!["metrics".to_string()],
+///         },
+///         |setup, features| setup.features = features,
+///     )
+///     .confirm(
+///         |setup| format!("Deploy to {}?", setup.environment),
+///         |setup, deploy| setup.deploy = deploy,
+///     )
+///     .run()?;
+/// # Ok::<(), ter_menu::TerMenuError>(())
+/// ```
+pub struct Flow<'a, S> {
+    state: S,
+    steps: Vec<Step<'a, S>>,
+    config: MenuConfig,
+}
+
+impl<'a, S> Flow<'a, S> {
+    /// Creates a flow without steps that starts from `state`.
+    pub fn new(state: S) -> Self {
+        Self {
+            state,
+            steps: Vec::new(),
+            config: MenuConfig {
+                confirm_message: String::new(),
+                cancel_message: String::new(),
+                ..MenuConfig::default()
+            },
+        }
+    }
+
+    /// Adds a step picking one item.
+    ///
+    /// # Parameters
+    /// * `prompt` - Title line shown above the items.
+    /// * `options` - Produces the items from the answers so far.
+    /// * `answer` - Stores the picked item in the state.
+    pub fn select<T: MenuEntry + 'a>(
+        mut self,
+        prompt: impl Into<String>,
+        options: impl FnOnce(&S) -> Vec<T> + 'a,
+        answer: impl FnOnce(&mut S, T) + 'a,
+    ) -> Self {
+        let prompt = prompt.into();
+        self.steps.push(Box::new(move |state, config| {
+            let mut options = options(state);
+            let config = MenuConfig {
+                prompt,
+                ..config.clone()
+            };
+            let mut menu = Menu::new(&options, config);
+            let Some(idx) = menu.run()? else {
+                return Ok(false);
+            };
+            answer(state, options.swap_remove(idx));
+            Ok(true)
+        }));
+        self
+    }
+
+    /// Adds a step checking any number of items, possibly none.
+    ///
+    /// # Parameters
+    /// * `prompt` - Title line shown above the items.
+    /// * `options` - Produces the items from the answers so far.
+    /// * `answer` - Stores the checked items, in display order, in the state.
+    pub fn multi_select<T: MenuEntry + 'a>(
+        mut self,
+        prompt: impl Into<String>,
+        options: impl FnOnce(&S) -> Vec<T> + 'a,
+        answer: impl FnOnce(&mut S, Vec<T>) + 'a,
+    ) -> Self {
+        let prompt = prompt.into();
+        self.steps.push(Box::new(move |state, config| {
+            let options = options(state);
+            let config = MenuConfig {
+                prompt,
+                ..config.clone()
+            };
+            let mut menu = Menu::multi(&options, config);
+            if menu.run()?.is_none() {
+                return Ok(false);
+            }
+            let checked = menu.checked_indices();
+            let mut options: Vec<Option<T>> = options.into_iter().map(Some).collect();
+            answer(
+                state,
+                checked
+                    .into_iter()
+                    .filter_map(|idx| options[idx].take())
+                    .collect(),
+            );
+            Ok(true)
+        }));
+        self
+    }
+
+    /// Adds a yes/no step.
+    ///
+    /// # Parameters
+    /// * `question` - Produces the question from the answers so far, e.g. "Deploy to staging?".
+    /// * `answer` - Stores `true` for Yes and `false` for No in the state.
+    pub fn confirm(
+        mut self,
+        question: impl FnOnce(&S) -> String + 'a,
+        answer: impl FnOnce(&mut S, bool) + 'a,
+    ) -> Self {
+        self.steps.push(Box::new(move |state, config| {
+            let config = MenuConfig {
+                prompt: question(state),
+                ..config.clone()
+            };
+            let answers = [config.strings.yes.clone(), config.strings.no.clone()];
+            let mut menu = Menu::new(&answers, config);
+            let Some(idx) = menu.run()? else {
+                return Ok(false);
+            };
+            answer(state, idx == 0);
+            Ok(true)
+        }));
+        self
+    }
+
+    /// Sets the colors and glyphs used by every step. Ignored when `NO_COLOR` is set or
+    /// `TERM=dumb`, where [`Theme::plain`] is always used.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.config.theme = theme;
+        self
+    }
+
+    /// Sets the texts every step shows on its own, such as the key instructions and the answers
+    /// of [`confirm`](Flow::confirm) steps; see [`Strings`].
+    pub fn strings(mut self, strings: Strings) -> Self {
+        self.config.strings = strings;
+        self
+    }
+
+    /// Sets where every step is drawn. Defaults to [`Output::Stdout`].
+    pub fn output(mut self, output: Output) -> Self {
+        self.config.output = output;
+        self
+    }
+
+    /// Reads the input of every step from `source` instead of the terminal.
+    pub fn events(mut self, source: impl EventSource + 'static) -> Self {
+        self.config.events = Some(SharedEvents::new(source));
+        self
+    }
+
+    /// Shows the steps one after another.
+    ///
+    /// # Returns
+    /// `Ok(Some(state))` once every step is answered, `Ok(None)` as soon as the user cancels a
+    /// step, or a [`TerMenuError`] if the terminal could not be driven or the user pressed Ctrl-C.
+    pub fn run(self) -> Result<Option<S>, TerMenuError> {
+        let Self {
+            mut state,
+            steps,
+            config,
+        } = self;
+        for step in steps {
+            if !step(&mut state, &config)? {
+                return Ok(None);
+            }
+        }
+        Ok(Some(state))
+    }
+}
@@ -0,0 +1,213 @@
+//! Fuzzy matching used by the filter-as-you-type mode, and the plain substring matching of
+//! search.
+//!
+//! The scoring follows the same ideas as skim/fzf: every pattern character must appear in the
+//! candidate in order, consecutive runs and matches at word boundaries are rewarded, and gaps
+//! between matched characters are penalised. Among all possible alignments the best scoring one
+//! is chosen, so the reported positions are the ones a user would expect to see highlighted.
+
+const SCORE_MATCH: i64 = 16;
+const PENALTY_GAP_START: i64 = 3;
+const PENALTY_GAP_EXTENSION: i64 = 1;
+const BONUS_BOUNDARY: i64 = 8;
+const BONUS_CAMEL: i64 = 7;
+const BONUS_CONSECUTIVE: i64 = 4;
+const BONUS_FIRST_CHAR_MULTIPLIER: i64 = 2;
+
+/// A successful fuzzy match of a pattern against a candidate string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FuzzyMatch {
+    /// Higher is better. Only meaningful relative to other matches of the same pattern.
+    pub(crate) score: i64,
+    /// Char indices (not byte offsets) of the matched characters within the candidate.
+    pub(crate) positions: Vec<usize>,
+}
+
+/// Matches `pattern` against `candidate`, ignoring case unless `case_sensitive`.
+///
+/// # Returns
+/// `None` if the pattern is not a subsequence of the candidate. An empty pattern matches
+/// everything with a score of zero and no highlighted positions.
+pub(crate) fn fuzzy_match(
+    pattern: &str,
+    candidate: &str,
+    case_sensitive: bool,
+) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    if pattern.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+    let text: Vec<char> = candidate.chars().collect();
+    let (m, n) = (pattern.len(), text.len());
+    if m > n || !is_subsequence(&pattern, &text, case_sensitive) {
+        return None;
+    }
+
+    let bonuses: Vec<i64> = (0..n)
+        .map(|j| char_bonus(j.checked_sub(1).map(|p| text[p]), text[j]))
+        .collect();
+
+    // scores[i][j]: best score with pattern[i] matched at text[j]; parents[i][j]: where
+    // pattern[i - 1] was matched on that best path.
+    let mut scores = vec![vec![None::<i64>; n]; m];
+    let mut parents = vec![vec![0usize; n]; m];
+
+    for j in 0..n {
+        if chars_eq(pattern[0], text[j], case_sensitive) {
+            scores[0][j] = Some(SCORE_MATCH + bonuses[j] * BONUS_FIRST_CHAR_MULTIPLIER);
+        }
+    }
+
+    for i in 1..m {
+        // Best score reachable at j after a gap, along with where that path came from.
+        let mut gap_best: Option<(i64, usize)> = None;
+        for j in i..n {
+            if j >= 2 {
+                let extended = gap_best.map(|(s, p)| (s - PENALTY_GAP_EXTENSION, p));
+                let opened = scores[i - 1][j - 2].map(|s| (s - PENALTY_GAP_START, j - 2));
+                gap_best = max_by_score(extended, opened);
+            }
+            if !chars_eq(pattern[i], text[j], case_sensitive) {
+                continue;
+            }
+            let consecutive = scores[i - 1][j - 1].map(|s| (s + BONUS_CONSECUTIVE, j - 1));
+            if let Some((score, parent)) = max_by_score(consecutive, gap_best) {
+                scores[i][j] = Some(score + SCORE_MATCH + bonuses[j]);
+                parents[i][j] = parent;
+            }
+        }
+    }
+
+    let (score, mut j) = (0..n)
+        .filter_map(|j| scores[m - 1][j].map(|s| (s, j)))
+        // Prefer the earliest end position among equal scores.
+        .max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)))?;
+    let mut positions = vec![0; m];
+    for i in (0..m).rev() {
+        positions[i] = j;
+        j = parents[i][j];
+    }
+    Some(FuzzyMatch { score, positions })
+}
+
+/// Finds the first occurrence of `pattern` in `candidate`, ignoring case unless
+/// `case_sensitive`.
+///
+/// # Returns
+/// Char indices of the occurrence within the candidate, or `None` if there is none or the pattern
+/// is empty.
+pub(crate) fn substring_match(
+    pattern: &str,
+    candidate: &str,
+    case_sensitive: bool,
+) -> Option<Vec<usize>> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = candidate.chars().collect();
+    if pattern.is_empty() || pattern.len() > text.len() {
+        return None;
+    }
+    (0..=text.len() - pattern.len())
+        .find(|&start| {
+            pattern
+                .iter()
+                .zip(&text[start..])
+                .all(|(&p, &t)| chars_eq(p, t, case_sensitive))
+        })
+        .map(|start| (start..start + pattern.len()).collect())
+}
+
+fn max_by_score(a: Option<(i64, usize)>, b: Option<(i64, usize)>) -> Option<(i64, usize)> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if b.0 > a.0 { b } else { a }),
+        (a, b) => a.or(b),
+    }
+}
+
+fn is_subsequence(pattern: &[char], text: &[char], case_sensitive: bool) -> bool {
+    let mut remaining = pattern.iter().peekable();
+    for &c in text {
+        if remaining
+            .peek()
+            .is_some_and(|&&p| chars_eq(p, c, case_sensitive))
+        {
+            remaining.next();
+        }
+    }
+    remaining.peek().is_none()
+}
+
+fn chars_eq(a: char, b: char, case_sensitive: bool) -> bool {
+    a == b || (!case_sensitive && a.to_lowercase().eq(b.to_lowercase()))
+}
+
+/// Bonus for matching `current`, given the character right before it.
+fn char_bonus(previous: Option<char>, current: char) -> i64 {
+    match previous {
+        None => BONUS_BOUNDARY,
+        Some(p) if !p.is_alphanumeric() && current.is_alphanumeric() => BONUS_BOUNDARY,
+        Some(p) if p.is_lowercase() && current.is_uppercase() => BONUS_CAMEL,
+        Some(p) if !p.is_numeric() && current.is_numeric() => BONUS_CAMEL,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(pattern: &str, candidate: &str) -> i64 {
+        fuzzy_match(pattern, candidate, false).unwrap().score
+    }
+
+    #[test]
+    fn a_prefix_scores_above_a_match_after_a_gap() {
+        assert!(score("bar", "barn") > score("bar", "foobar"));
+        assert!(score("fb", "fb_x") > score("fb", "f_x_b"));
+    }
+
+    #[test]
+    fn consecutive_matches_score_above_scattered_ones() {
+        assert!(score("abc", "abcxx") > score("abc", "axbxc"));
+    }
+
+    #[test]
+    fn the_best_alignment_is_the_one_highlighted() {
+        let found = fuzzy_match("ml", "my_small_list", false).unwrap();
+        assert_eq!(found.positions, vec![0, 9]);
+        let found = fuzzy_match("list", "my_small_list", false).unwrap();
+        assert_eq!(found.positions, vec![9, 10, 11, 12]);
+    }
+
+    #[test]
+    fn positions_count_chars_not_bytes() {
+        let found = fuzzy_match("本語", "日本語", false).unwrap();
+        assert_eq!(found.positions, vec![1, 2]);
+        assert_eq!(
+            fuzzy_match("Ñu", "año nuevo", false).unwrap().positions,
+            vec![1, 5]
+        );
+        assert!(fuzzy_match("Ñ", "año", true).is_none());
+        assert_eq!(substring_match("語", "日本語", false), Some(vec![2]));
+    }
+
+    #[test]
+    fn an_empty_query_matches_everything_without_highlights() {
+        assert_eq!(
+            fuzzy_match("", "anything", false),
+            Some(FuzzyMatch {
+                score: 0,
+                positions: Vec::new(),
+            })
+        );
+        assert_eq!(substring_match("", "anything", false), None);
+    }
+
+    #[test]
+    fn characters_out_of_order_do_not_match() {
+        assert!(fuzzy_match("ba", "ab", false).is_none());
+        assert!(fuzzy_match("abc", "ab", false).is_none());
+    }
+}
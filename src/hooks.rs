@@ -0,0 +1,81 @@
+use crate::intercept::KeyInterceptor;
+use crossterm::style::StyledContent;
+use std::cmp::Ordering;
+use std::error::Error;
+
+/// Called with an item, e.g. when it becomes highlighted.
+pub(crate) type ItemHook<T> = Box<dyn FnMut(&T) + Send>;
+
+/// Produces the text of the preview pane for an item.
+pub(crate) type PreviewHook<T> = Box<dyn Fn(&T) -> String + Send>;
+
+/// Produces the text shown for an item in place of its label.
+pub(crate) type FormatHook<T> = Box<dyn Fn(&T) -> String + Send>;
+
+/// Decides whether an item is shown while the predicate filter is switched on.
+pub(crate) type PredicateHook<T> = Box<dyn Fn(&T) -> bool + Send>;
+
+/// Orders two items for [`SortMode::Custom`](crate::SortMode::Custom).
+pub(crate) type CompareHook<T> = Box<dyn Fn(&T, &T) -> Ordering + Send>;
+
+/// Produces the status line printed once the confirmed item's callback has run, from the item
+/// and the callback's outcome.
+pub(crate) type FinishHook<T> =
+    Box<dyn Fn(&T, Result<(), &(dyn Error + Send + Sync)>) -> StyledContent<String> + Send>;
+
+/// A per-item callback that can fail, run once its item is confirmed and again for each retry;
+/// see [`DropDownBuilder::try_item`](crate::DropDownBuilder::try_item).
+pub(crate) type TryCallback<T> =
+    Box<dyn FnMut(&T) -> Result<(), Box<dyn Error + Send + Sync>> + Send>;
+
+/// Caller-supplied closures invoked while a menu is open, as opposed to the per-item callbacks
+/// that run once it has closed.
+///
+/// Owned by whoever builds the menu and lent to [`Menu`](crate::menu::Menu) for the duration of
+/// an interaction, so that a [`ReusableDropDown`](crate::ReusableDropDown) keeps its hooks
+/// between runs.
+pub(crate) struct Hooks<T> {
+    /// Called with the highlighted item when the menu opens and whenever the highlight moves to
+    /// a different item.
+    pub(crate) on_highlight: Option<ItemHook<T>>,
+    /// Produces the preview pane's text for the highlighted item on every redraw.
+    pub(crate) preview: Option<PreviewHook<T>>,
+    /// Produces the text shown for each item in place of its label.
+    pub(crate) format: Option<FormatHook<T>>,
+    /// Called once the user dismissed the menu without choosing, after the terminal is restored.
+    pub(crate) on_cancel: Option<Box<dyn FnMut() + Send>>,
+    /// Called with the confirmed item once the user chose one, or with the top-level item it
+    /// belongs to if it was chosen in a submenu. Not called by multi-select menus.
+    pub(crate) on_confirm: Option<ItemHook<T>>,
+    /// Called with the highlighted item on [`Action::ConfirmAndStay`](crate::Action::ConfirmAndStay),
+    /// while the menu stays open.
+    pub(crate) on_confirm_and_stay: Option<ItemHook<T>>,
+    /// Restricts the visible items to those it accepts while switched on with
+    /// [`Action::TogglePredicate`](crate::Action::TogglePredicate).
+    pub(crate) filter_predicate: Option<PredicateHook<T>>,
+    /// Orders the items while the menu is sorted with [`SortMode::Custom`](crate::SortMode::Custom).
+    pub(crate) compare: Option<CompareHook<T>>,
+    /// Produces the status line printed in place of the confirm message once the confirmed
+    /// item's callback has run. Not called by multi-select menus.
+    pub(crate) finish_message: Option<FinishHook<T>>,
+    /// Offered every key press before the key map, in the order they were added, until one
+    /// handles it.
+    pub(crate) key_interceptors: Vec<Box<dyn KeyInterceptor<T>>>,
+}
+
+impl<T> Default for Hooks<T> {
+    fn default() -> Self {
+        Self {
+            on_highlight: None,
+            preview: None,
+            format: None,
+            on_cancel: None,
+            on_confirm: None,
+            on_confirm_and_stay: None,
+            filter_predicate: None,
+            compare: None,
+            finish_message: None,
+            key_interceptors: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,436 @@
+use crate::error::TerMenuError;
+use crate::events::{EventSource, SharedEvents};
+use crate::menu::MenuConfig;
+use crate::output::Output;
+use crate::render::{Frame, Line};
+use crate::terminal::TerminalSession;
+use crate::theme::Theme;
+use crossterm::cursor::{MoveToColumn, MoveToPreviousLine};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::queue;
+use crossterm::style::{Color, ContentStyle, Print};
+use crossterm::terminal::{Clear, ClearType};
+use std::io::{self, BufWriter, IsTerminal, prelude::*};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Asks for a line of text on the current thread and blocks until the user answers.
+///
+/// A shorthand for [`Input::new`] without validation or initial text.
+///
+/// # Returns
+/// `Ok(Some(text))` once the user pressed Enter, `Ok(None)` if they pressed Escape, or a
+/// [`TerMenuError`] if the terminal could not be driven or the user pressed Ctrl-C.
+///
+/// # Example
+/// ```no_run
+/// if let Some(branch) = ter_menu::input("Branch name: ")? {
+///     println!("Creating {}", branch);
+/// }
+/// # Ok::<(), ter_menu::TerMenuError>(())
+/// ```
+pub fn input(prompt: &str) -> Result<Option<String>, TerMenuError> {
+    Input::new(prompt).run()
+}
+
+/// Checks submitted text, returning the message to show if it is rejected.
+type Validator<'a> = Box<dyn Fn(&str) -> Result<(), String> + 'a>;
+
+/// A single-line text prompt with cursor movement and optional validation.
+///
+/// Left/Right move the cursor, Home/End jump to either end, and Backspace/Delete remove the
+/// character before or under it. Enter submits the text once the validator accepts it, Escape
+/// cancels, and Ctrl-C interrupts like in the menus. When stdin or the output is not a terminal,
+/// or `NO_COLOR`/`TERM=dumb` ask for plain output, the text is read as an ordinary line instead.
+///
+/// # Example
+/// ```no_run
+/// use ter_menu::Input;
+///
+/// let port = Input::new("Port: ")
+///     .initial("8080")
+///     .validate(|text| match text.parse::<u16>() {
+///         Ok(_) => Ok(()),
+///         Err(_) => Err("Enter a number between 0 and 65535".to_string()),
+///     })
+///     .run()?;
+/// # Ok::<(), ter_menu::TerMenuError>(())
+/// ```
+pub struct Input<'a> {
+    prompt: String,
+    initial: String,
+    validator: Option<Validator<'a>>,
+    pub(crate) echo: Echo,
+    config: MenuConfig,
+}
+
+/// How the typed text is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Echo {
+    /// As typed.
+    Text,
+    /// One mask character per typed character.
+    Mask(char),
+    /// Not at all; the cursor stays after the prompt.
+    Hidden,
+}
+
+impl<'a> Input<'a> {
+    /// Creates a prompt showing `prompt` in front of the text.
+    pub fn new(prompt: impl Into<String>) -> Self {
+        Self {
+            prompt: prompt.into(),
+            initial: String::new(),
+            validator: None,
+            echo: Echo::Text,
+            config: MenuConfig::default(),
+        }
+    }
+
+    /// Sets the text the prompt starts with, e.g. the current value of a setting.
+    pub fn initial(mut self, text: impl Into<String>) -> Self {
+        self.initial = text.into();
+        self
+    }
+
+    /// Checks the text when the user presses Enter. An `Err` message is shown below the prompt
+    /// and the user keeps editing.
+    pub fn validate(mut self, validator: impl Fn(&str) -> Result<(), String> + 'a) -> Self {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// Sets the style of the prompt through [`Theme::prompt`]. Ignored when `NO_COLOR` is set
+    /// or `TERM=dumb`.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        if !self.config.plain {
+            self.config.theme = theme;
+        }
+        self
+    }
+
+    /// Sets where the prompt is drawn. Defaults to [`Output::Stdout`].
+    pub fn output(mut self, output: Output) -> Self {
+        self.config.output = output;
+        self
+    }
+
+    /// Reads the input from `source` instead of the terminal.
+    pub fn events(mut self, source: impl EventSource + 'static) -> Self {
+        self.config.events = Some(SharedEvents::new(source));
+        self
+    }
+
+    /// Shows the prompt and blocks until the user submits or cancels.
+    ///
+    /// # Returns
+    /// `Ok(Some(text))` with the accepted text, `Ok(None)` on Escape or at the end of input, or
+    /// a [`TerMenuError`] if the terminal could not be driven or the user pressed Ctrl-C.
+    pub fn run(mut self) -> Result<Option<String>, TerMenuError> {
+        let headless = self.config.events.is_some();
+        if !headless {
+            match self.editor_output(io::stdin().is_terminal(), self.config.output.is_terminal()) {
+                Some(output) => self.config.output = output,
+                None => return self.read_line(),
+            }
+        }
+        let _session = if headless {
+            None
+        } else {
+            Some(TerminalSession::start_with_cursor(&self.config)?)
+        };
+        let mut editor = LineEditor::new(self.initial.clone());
+        let mut error = None;
+        self.draw(&editor, error.as_deref())?;
+        loop {
+            let key = next_key(&self.config)?;
+            match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.finish(&editor)?;
+                    return Err(TerMenuError::Interrupted);
+                }
+                KeyCode::Esc => {
+                    self.finish(&editor)?;
+                    return Ok(None);
+                }
+                KeyCode::Enter => match self.check(editor.text()) {
+                    Ok(()) => {
+                        self.finish(&editor)?;
+                        return Ok(Some(editor.into_text()));
+                    }
+                    Err(message) => error = Some(message),
+                },
+                _ if editor.handle_key(&key) => error = None,
+                _ => continue,
+            }
+            self.draw(&editor, error.as_deref())?;
+        }
+    }
+
+    /// Where the prompt is drawn, given whether stdin and the configured output are terminals, or
+    /// `None` to read an ordinary line instead. A secret typed on a terminal is never read as a
+    /// line, since the terminal would echo it; it is drawn on stderr when the output is
+    /// redirected, e.g. into `tee`.
+    fn editor_output(&self, stdin_is_terminal: bool, output_is_terminal: bool) -> Option<Output> {
+        if !stdin_is_terminal {
+            return None;
+        }
+        match (self.echo, output_is_terminal) {
+            (Echo::Text, true) if !self.config.plain => Some(self.config.output.clone()),
+            (Echo::Text, _) => None,
+            (_, true) => Some(self.config.output.clone()),
+            (_, false) => Some(Output::Stderr),
+        }
+    }
+
+    /// Runs the validator, if there is one.
+    fn check(&self, text: &str) -> Result<(), String> {
+        self.validator
+            .as_ref()
+            .map_or(Ok(()), |validate| validate(text))
+    }
+
+    /// The prompt line for the current text.
+    fn line(&self, editor: &LineEditor) -> Line {
+        let mut line = Line::styled(self.prompt.as_str(), self.config.theme.prompt);
+        match self.echo {
+            Echo::Text => line.push(editor.text(), ContentStyle::new()),
+            Echo::Mask(mask) => line.push(
+                mask.to_string().repeat(editor.text().chars().count()),
+                ContentStyle::new(),
+            ),
+            Echo::Hidden => {}
+        }
+        line
+    }
+
+    /// Terminal column of the cursor within the prompt line.
+    fn cursor_column(&self, editor: &LineEditor) -> usize {
+        let typed = match self.echo {
+            Echo::Text => editor.cursor_width(),
+            Echo::Mask(mask) => editor.cursor() * mask.width().unwrap_or(1),
+            Echo::Hidden => 0,
+        };
+        self.prompt.width() + typed
+    }
+
+    /// Redraws the prompt line with `error` below it, and puts the terminal's cursor where the
+    /// next character will be inserted.
+    fn draw(&self, editor: &LineEditor, error: Option<&str>) -> io::Result<()> {
+        let line = self.line(editor);
+        let error_line = error.map(|message| {
+            Line::styled(
+                message,
+                ContentStyle {
+                    foreground_color: Some(Color::Red),
+                    ..ContentStyle::new()
+                },
+            )
+        });
+        let mut frame = Frame::default();
+        frame.push(line);
+        frame.lines.extend(error_line);
+        draw_prompt(&self.config.output, &frame, self.cursor_column(editor))
+    }
+
+    /// Leaves the prompt line with its final text on screen and moves below it.
+    fn finish(&self, editor: &LineEditor) -> io::Result<()> {
+        finish_prompt(&self.config.output, &self.line(editor))
+    }
+
+    /// Line-based fallback for when the prompt cannot be drawn, e.g. under a pipe. Invalid text
+    /// asks again; the end of input cancels.
+    fn read_line(&self) -> Result<Option<String>, TerMenuError> {
+        let mut stdout = self.config.output.clone();
+        loop {
+            write!(stdout, "{}", self.prompt)?;
+            stdout.flush()?;
+            // Wiped unless handed to the caller, since it may hold a secret.
+            let mut text = Zeroizing::new(String::new());
+            let read = io::stdin()
+                .lock()
+                .read_line(&mut text)
+                .map_err(TerMenuError::EventRead)?;
+            if read == 0 {
+                return Ok(None);
+            }
+            let len = text.trim_end_matches(['\r', '\n']).len();
+            text.truncate(len);
+            match self.check(&text) {
+                Ok(()) => return Ok(Some(std::mem::take(&mut *text))),
+                Err(message) => writeln!(stdout, "{}", message)?,
+            }
+        }
+    }
+}
+
+/// Blocks until the next key press, read from the configured event source or the terminal.
+/// Releases, which Windows consoles report besides the press, are skipped so that every key is
+/// typed once; repeats of a held key count as presses.
+pub(crate) fn next_key(config: &MenuConfig) -> Result<KeyEvent, TerMenuError> {
+    loop {
+        let event = match &config.events {
+            Some(events) => events.read(),
+            None => event::read(),
+        };
+        if let Event::Key(key) = event.map_err(TerMenuError::EventRead)?
+            && key.kind != KeyEventKind::Release
+        {
+            return Ok(key);
+        }
+    }
+}
+
+/// Draws a prompt's `frame` over the previous one, starting on the line the cursor is on, and
+/// puts the cursor back on the first line at `column`, where the user is typing.
+pub(crate) fn draw_prompt(output: &Output, frame: &Frame, column: usize) -> io::Result<()> {
+    if let Output::Frames(recorder) = output {
+        recorder.record(frame.plain_text());
+        return Ok(());
+    }
+    let mut output = output.clone();
+    let mut out = BufWriter::new(&mut output);
+    queue!(out, MoveToColumn(0), Clear(ClearType::FromCursorDown))?;
+    for (row, line) in frame.lines.iter().enumerate() {
+        if row > 0 {
+            queue!(out, Print("\r\n"))?;
+        }
+        line.queue(&mut out)?;
+    }
+    if frame.lines.len() > 1 {
+        queue!(out, MoveToPreviousLine(frame.lines.len() as u16 - 1))?;
+    }
+    queue!(out, MoveToColumn(column as u16))?;
+    out.flush()
+}
+
+/// Replaces a prompt drawn by [`draw_prompt`] with its final `line` and moves below it.
+pub(crate) fn finish_prompt(output: &Output, line: &Line) -> io::Result<()> {
+    if matches!(output, Output::Frames(_)) {
+        return Ok(());
+    }
+    let mut output = output.clone();
+    let mut out = BufWriter::new(&mut output);
+    queue!(out, MoveToColumn(0), Clear(ClearType::FromCursorDown))?;
+    line.queue(&mut out)?;
+    queue!(out, Print("\r\n"))?;
+    out.flush()
+}
+
+/// The text being typed into a prompt and the cursor position within it.
+///
+/// The text is wiped from memory when the editor is dropped, since it may be a password.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct LineEditor {
+    text: String,
+    /// Number of characters before the cursor.
+    cursor: usize,
+}
+
+impl LineEditor {
+    /// Starts with `text` and the cursor at its end.
+    pub(crate) fn new(mut text: String) -> Self {
+        // Room for typical input up front, so that growing the text does not leave copies of it
+        // behind in freed memory.
+        text.reserve(64);
+        Self {
+            cursor: text.chars().count(),
+            text,
+        }
+    }
+
+    /// Number of characters before the cursor.
+    pub(crate) fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub(crate) fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub(crate) fn into_text(mut self) -> String {
+        std::mem::take(&mut self.text)
+    }
+
+    /// Number of terminal columns taken up by the text before the cursor.
+    pub(crate) fn cursor_width(&self) -> usize {
+        self.text[..self.byte_offset(self.cursor)].width()
+    }
+
+    /// Applies an editing or cursor movement key.
+    ///
+    /// # Returns
+    /// `false` if the key is not an editing key, or had nothing to do.
+    pub(crate) fn handle_key(&mut self, key: &KeyEvent) -> bool {
+        let len = self.text.chars().count();
+        match key.code {
+            KeyCode::Char(c)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                self.text.insert(self.byte_offset(self.cursor), c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.text.remove(self.byte_offset(self.cursor));
+            }
+            KeyCode::Delete if self.cursor < len => {
+                self.text.remove(self.byte_offset(self.cursor));
+            }
+            KeyCode::Left if self.cursor > 0 => self.cursor -= 1,
+            KeyCode::Right if self.cursor < len => self.cursor += 1,
+            KeyCode::Home if self.cursor > 0 => self.cursor = 0,
+            KeyCode::End if self.cursor < len => self.cursor = len,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Byte offset of the character at `chars` characters into the text.
+    fn byte_offset(&self, chars: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(chars)
+            .map_or(self.text.len(), |(offset, _)| offset)
+    }
+}
+
+impl Drop for LineEditor {
+    fn drop(&mut self) {
+        self.text.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_secret_on_a_terminal_is_drawn_on_stderr_when_the_output_is_redirected() {
+        let mut password = Input::new("Token: ");
+        password.echo = Echo::Mask('*');
+        assert!(matches!(
+            password.editor_output(true, false),
+            Some(Output::Stderr)
+        ));
+        assert!(matches!(
+            password.editor_output(true, true),
+            Some(Output::Stdout)
+        ));
+        // Piped in, nothing is echoed, so an ordinary line is fine.
+        assert!(password.editor_output(false, true).is_none());
+    }
+
+    #[test]
+    fn text_is_read_as_a_line_when_the_output_is_redirected() {
+        let input = Input::new("Branch: ");
+        assert!(input.editor_output(true, false).is_none());
+        assert!(input.editor_output(false, true).is_none());
+        assert!(matches!(
+            input.editor_output(true, true),
+            Some(Output::Stdout)
+        ));
+    }
+}
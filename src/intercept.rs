@@ -0,0 +1,62 @@
+use crate::state::{KeyOutcome, MenuState};
+use crossterm::event::KeyEvent;
+
+/// Whether a [`KeyInterceptor`] dealt with a key press.
+///
+/// # Variants
+/// * `No` - The menu handles the key as usual, after any interceptors added later had their turn.
+/// * `Yes(outcome)` - The interceptor dealt with the key and the menu does nothing more with it.
+///   `outcome` says what happens next: [`KeyOutcome::Changed`] redraws the menu,
+///   [`KeyOutcome::Selected`] confirms the highlighted item, [`KeyOutcome::Cancelled`] and
+///   [`KeyOutcome::Interrupted`] close it, and [`KeyOutcome::Unchanged`] just swallows the key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Handled {
+    No,
+    Yes(KeyOutcome),
+}
+
+/// Sees every key press of a running menu before its key map does; see
+/// [`DropDownBuilder::key_interceptor`](crate::DropDownBuilder::key_interceptor).
+///
+/// An interceptor can look at the menu, e.g. to log the highlighted item, or act on it through
+/// [`MenuState::handle_key`], e.g. to add a shortcut that stands for other keys. Whatever it does
+/// to the menu, it reports what the menu should do next by returning [`Handled`].
+///
+/// Closures taking the key and the menu state implement the trait, so a struct is only needed
+/// for interceptors that keep state of their own between menus.
+///
+/// # Type Parameters
+/// * `T` - The type of items in the menu.
+///
+/// # Example
+/// ```no_run
+/// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+/// use ter_menu::{Handled, MenuState, TerminalDropDown};
+///
+/// // `j` and `k` move the highlight like the arrow keys.
+/// let result = TerminalDropDown::builder()
+///     .key_interceptor(|key: KeyEvent, menu: &mut MenuState<'_, &str>| {
+///         let arrow = match key.code {
+///             KeyCode::Char('j') => KeyCode::Down,
+///             KeyCode::Char('k') => KeyCode::Up,
+///             _ => return Handled::No,
+///         };
+///         Handled::Yes(menu.handle_key(KeyEvent::new(arrow, KeyModifiers::NONE)))
+///     })
+///     .items(["staging", "production"].map(|env| (env, |_: &&str| {})))
+///     .build()
+///     .wait();
+/// ```
+pub trait KeyInterceptor<T>: Send {
+    /// Called with each key press before the menu handles it.
+    fn on_key(&mut self, key: KeyEvent, menu: &mut MenuState<'_, T>) -> Handled;
+}
+
+impl<T, F> KeyInterceptor<T> for F
+where
+    F: FnMut(KeyEvent, &mut MenuState<'_, T>) -> Handled + Send,
+{
+    fn on_key(&mut self, key: KeyEvent, menu: &mut MenuState<'_, T>) -> Handled {
+        self(key, menu)
+    }
+}
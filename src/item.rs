@@ -0,0 +1,370 @@
+use crossterm::style::Color;
+use std::fmt::Display;
+
+/// Anything that can be listed in a menu.
+///
+/// Every [`Display`] type is an entry whose label is its `to_string()`, so plain strings, numbers
+/// and user types with a `Display` impl work as before. [`MenuItem`] is the entry to use when the
+/// text shown to the user should differ from the value handed back.
+pub trait MenuEntry {
+    /// Text shown for the entry, matched by the filter and used in confirm messages.
+    fn label(&self) -> String;
+
+    /// Optional secondary text rendered dimmed next to the label, or under it; see
+    /// [`DescriptionLayout`].
+    fn description(&self) -> Option<&str> {
+        None
+    }
+
+    /// Whether the entry is shown but cannot be chosen.
+    fn is_disabled(&self) -> bool {
+        false
+    }
+
+    /// Key that picks the entry straight away, shown underlined in the label. `None`, the
+    /// default, for entries without one.
+    fn hotkey(&self) -> Option<char> {
+        None
+    }
+
+    /// How the entry's label is drawn. Defaults to the terminal's normal style.
+    fn style(&self) -> ItemStyle {
+        ItemStyle::default()
+    }
+
+    /// State shown as a colored badge in front of the label. `None`, the default, for entries
+    /// without one.
+    fn status(&self) -> Option<Status> {
+        None
+    }
+
+    /// How choosing the entry must be confirmed a second time, for destructive actions. `None`,
+    /// the default, for entries chosen with a single Enter.
+    fn destructive(&self) -> Option<Confirmation> {
+        None
+    }
+
+    /// Entries of the submenu this entry opens. Empty, the default, for entries that are chosen
+    /// directly.
+    fn children(&self) -> &[Self]
+    where
+        Self: Sized,
+    {
+        &[]
+    }
+}
+
+impl<T: Display + ?Sized> MenuEntry for T {
+    fn label(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// A menu entry with a human-readable label and a machine value, such as an ID or a path.
+///
+/// `V` does not need to implement `Display`; only `label` is rendered. Use
+/// [`select_item`](crate::select_item) to get the value of the chosen entry back directly.
+///
+/// # Fields
+/// * `label` - Text shown in the menu.
+/// * `value` - Value the entry stands for.
+/// * `description` - Optional secondary text shown dimmed after or under the label.
+/// * `disabled` - Whether the entry is shown but cannot be confirmed.
+/// * `style` - Color, weight and icon of the label.
+/// * `hotkey` - Key that picks the entry straight away. See [`MenuItem::hotkey`].
+/// * `status` - State shown as a colored badge in front of the label.
+/// * `destructive` - How choosing the entry must be confirmed a second time. See
+///   [`MenuItem::destructive`].
+/// * `children` - Entries of the submenu this entry opens, empty for ordinary entries. See
+///   [`MenuItem::submenu`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MenuItem<V> {
+    pub label: String,
+    pub value: V,
+    pub description: Option<String>,
+    pub disabled: bool,
+    pub style: ItemStyle,
+    pub hotkey: Option<char>,
+    pub status: Option<Status>,
+    pub destructive: Option<Confirmation>,
+    pub children: Vec<MenuItem<V>>,
+}
+
+impl<V> MenuItem<V> {
+    /// Creates an enabled entry without a description.
+    pub fn new(label: impl Into<String>, value: V) -> Self {
+        Self {
+            label: label.into(),
+            value,
+            description: None,
+            disabled: false,
+            style: ItemStyle::default(),
+            hotkey: None,
+            status: None,
+            destructive: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Sets the secondary text shown after the label.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Marks the entry as shown but not selectable.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// Sets how the label is drawn, e.g. in red for destructive actions.
+    pub fn style(mut self, style: ItemStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Lets the user pick the entry by pressing `key`, ignoring case, without moving the
+    /// highlight there first. The first occurrence of `key` in the label is underlined, or the
+    /// key is shown after the label if it does not occur in it. Hotkeys take precedence over
+    /// other bindings of the same key, but not over typing in the filter line.
+    pub fn hotkey(mut self, key: char) -> Self {
+        self.hotkey = Some(key);
+        self
+    }
+
+    /// Shows `status` as a colored badge in front of the label, e.g. the health of a service.
+    pub fn status(mut self, status: Status) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Marks the entry as a destructive action that is only chosen once the user confirms it a
+    /// second time, as `confirmation` says, before the menu closes and the callback runs. A
+    /// timeout never chooses such an entry.
+    ///
+    /// # Example
+    /// ```
+    /// use ter_menu::{Confirmation, MenuItem};
+    ///
+    /// let actions = [
+    ///     MenuItem::new("Restart", "restart").destructive(Confirmation::EnterAgain),
+    ///     MenuItem::new("Drop database", "drop").destructive(Confirmation::TypeLabel),
+    /// ];
+    /// ```
+    pub fn destructive(mut self, confirmation: Confirmation) -> Self {
+        self.destructive = Some(confirmation);
+        self
+    }
+}
+
+/// An enabled entry labeled `label`, as made by [`MenuItem::new`].
+impl<L: Into<String>, V> From<(L, V)> for MenuItem<V> {
+    fn from((label, value): (L, V)) -> Self {
+        Self::new(label, value)
+    }
+}
+
+impl<V: Default> MenuItem<V> {
+    /// Creates an entry that opens another menu listing `children` instead of being chosen
+    /// itself.
+    ///
+    /// Enter opens the submenu, and Left, Backspace or Escape go back to the parent. A breadcrumb
+    /// line above the items shows the submenus opened so far, e.g. "Settings > Network > DNS".
+    /// The entry's value is never returned, so it is left at `V::default()`.
+    ///
+    /// # Example
+    /// ```
+    /// use ter_menu::MenuItem;
+    ///
+    /// let menu = [
+    ///     MenuItem::submenu(
+    ///         "Network",
+    ///         [
+    ///             MenuItem::new("DNS", "network.dns"),
+    ///             MenuItem::new("Proxy", "network.proxy"),
+    ///         ],
+    ///     ),
+    ///     MenuItem::new("Quit", "quit"),
+    /// ];
+    /// ```
+    pub fn submenu(label: impl Into<String>, children: impl IntoIterator<Item = Self>) -> Self {
+        Self {
+            children: children.into_iter().collect(),
+            ..Self::new(label, V::default())
+        }
+    }
+}
+
+impl<V> MenuEntry for MenuItem<V> {
+    fn label(&self) -> String {
+        self.label.clone()
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    fn hotkey(&self) -> Option<char> {
+        self.hotkey
+    }
+
+    fn style(&self) -> ItemStyle {
+        self.style.clone()
+    }
+
+    fn status(&self) -> Option<Status> {
+        self.status.clone()
+    }
+
+    fn destructive(&self) -> Option<Confirmation> {
+        self.destructive
+    }
+
+    fn children(&self) -> &[Self] {
+        &self.children
+    }
+}
+
+/// How a destructive entry must be confirmed after Enter; see [`MenuItem::destructive`]. The
+/// footer says what to do, and Escape goes back to the menu.
+///
+/// # Variants
+/// * `EnterAgain` - Press Enter a second time. Any other key goes back to the menu.
+/// * `TypeLabel` - Type the entry's label, then press Enter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Confirmation {
+    EnterAgain,
+    TypeLabel,
+}
+
+/// Where the [`description`](MenuEntry::description)s of entries are drawn; see
+/// [`DropDownBuilder::description_layout`](crate::DropDownBuilder::description_layout).
+///
+/// # Variants
+/// * `Inline` - Dimmed after the label, on the same line.
+/// * `Below` - On a dimmed line of its own under the label, for every entry.
+/// * `BelowHighlighted` - Under the highlighted entry only, so that the others keep to one line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DescriptionLayout {
+    #[default]
+    Inline,
+    Below,
+    BelowHighlighted,
+}
+
+/// The entries along `path`: the top-level entry at `path[0]`, then the entry at `path[1]` of
+/// its submenu, and so on.
+pub(crate) fn follow<'t, T: MenuEntry>(options: &'t [T], path: &[usize]) -> Vec<&'t T> {
+    let mut level = options;
+    path.iter()
+        .map(|&idx| {
+            let entry = &level[idx];
+            level = entry.children();
+            entry
+        })
+        .collect()
+}
+
+/// Presentation of a single entry, on top of the menu's own highlighting.
+///
+/// ```
+/// use crossterm::style::Color;
+/// use ter_menu::ItemStyle;
+///
+/// let destructive = ItemStyle::new().foreground(Color::Red).bold(true).icon("✗");
+/// ```
+///
+/// # Fields
+/// * `foreground` - Text color of the label. `None` keeps the terminal's color.
+/// * `bold` - Whether the label is drawn in bold.
+/// * `icon` - Text drawn before the label, such as an emoji or a Nerd Font glyph.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ItemStyle {
+    pub foreground: Option<Color>,
+    pub bold: bool,
+    pub icon: Option<String>,
+}
+
+impl ItemStyle {
+    /// The terminal's normal style without an icon.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the label's text color. Any crossterm color works, including 256-color and RGB values.
+    pub fn foreground(mut self, color: Color) -> Self {
+        self.foreground = Some(color);
+        self
+    }
+
+    /// Draws the label in bold.
+    pub fn bold(mut self, bold: bool) -> Self {
+        self.bold = bold;
+        self
+    }
+
+    /// Draws `icon` followed by a space before the label.
+    pub fn icon(mut self, icon: impl Into<String>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+}
+
+/// The state of what an entry stands for, drawn as a colored badge such as `[WARN]` in front of
+/// its label.
+///
+/// ```
+/// use crossterm::style::Color;
+/// use ter_menu::{MenuItem, Status};
+///
+/// let services = [
+///     MenuItem::new("api", 1).status(Status::Ok),
+///     MenuItem::new("worker", 2).status(Status::Error),
+///     MenuItem::new("cron", 3).status(Status::Custom("PAUSED".to_string(), Color::Blue)),
+/// ];
+/// ```
+///
+/// # Variants
+/// * `Ok` - A green `OK`.
+/// * `Warn` - A yellow `WARN`.
+/// * `Error` - A red `ERROR`.
+/// * `Custom` - Any other text, in the given color.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Status {
+    Ok,
+    Warn,
+    Error,
+    Custom(String, Color),
+}
+
+impl Status {
+    /// The text inside the badge.
+    pub fn text(&self) -> &str {
+        match self {
+            Status::Ok => "OK",
+            Status::Warn => "WARN",
+            Status::Error => "ERROR",
+            Status::Custom(text, _) => text,
+        }
+    }
+
+    /// The color the badge is drawn in.
+    pub fn color(&self) -> Color {
+        match self {
+            Status::Ok => Color::Green,
+            Status::Warn => Color::Yellow,
+            Status::Error => Color::Red,
+            Status::Custom(_, color) => *color,
+        }
+    }
+
+    /// The badge as drawn in front of a label, e.g. `[WARN] `.
+    pub(crate) fn badge(&self) -> String {
+        format!("[{}] ", self.text())
+    }
+}
@@ -0,0 +1,516 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long an incomplete key sequence waits for its next key before it is abandoned, like
+/// vim's `timeoutlen`.
+const SEQUENCE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Something the user can ask a menu to do, independent of which key triggers it.
+///
+/// # Variants
+/// * `Up` / `Down` - Move the highlight one row.
+/// * `PageUp` / `PageDown` - Move the highlight by a full page, stopping at the ends.
+/// * `HalfPageUp` / `HalfPageDown` - Move the highlight by half a page, stopping at the ends.
+/// * `First` / `Last` - Jump to the first or last item.
+/// * `Confirm` - Accept the highlighted item (or the checked items in multi-select mode).
+/// * `Cancel` - Clear a non-empty filter, otherwise go back from a submenu or close the menu
+///   without a selection.
+/// * `Back` - Go back from a submenu to its parent. Does nothing in the top-level menu.
+/// * `Left` / `Right` - Move the highlight one column in a grid layout. Elsewhere, and in the
+///   first column, `Left` does the same as `Back` and `Right` does nothing.
+/// * `ConfirmAndStay` - Hand the highlighted item to the
+///   [`on_confirm_and_stay`](crate::DropDownBuilder::on_confirm_and_stay) closure and keep the
+///   menu open, so that several items can be acted on one after another. A destructive item
+///   is confirmed a second time first. Does nothing on submenu entries, or without such a
+///   closure.
+/// * `Interrupt` - Close the menu at once, as Ctrl-C would outside of raw mode. The interaction
+///   ends with [`SelectionResult::Interrupted`](crate::SelectionResult::Interrupted).
+/// * `Toggle` - Check or uncheck the highlighted item in multi-select mode.
+/// * `SelectAll` / `SelectNone` - Check or uncheck every item in multi-select mode. Like
+///   `ToggleAll` and `Invert`, only changes the items the filter currently shows.
+/// * `ToggleAll` - Check every item in multi-select mode, or uncheck them all if they already
+///   are.
+/// * `Invert` - Check the unchecked items and uncheck the checked ones in multi-select mode.
+/// * `TogglePredicate` - Switch the menu's filter predicate on or off, showing only the items it
+///   accepts or all of them again. Does nothing without a predicate.
+/// * `MoveItemUp` / `MoveItemDown` - Move the highlighted item one place up or down in a
+///   [`Reorder`](crate::Reorder) list. Does nothing elsewhere.
+/// * `Help` - Show a screen listing the key bindings over the menu. Any key closes it again.
+/// * `Search` - Open a search line below the items. Typing jumps to the first item containing the
+///   text, ignoring case, and highlights it in every label; unlike the filter, no items are
+///   hidden. Enter closes the line and keeps the search, Escape drops it.
+/// * `NextMatch` / `PrevMatch` - Jump to the next or previous item matching the last search,
+///   wrapping around at the ends.
+/// * `CycleMatchMode` - Switch the filter to the next [`MatchMode`](crate::MatchMode). Does
+///   nothing without a filter line.
+/// * `ToggleCase` - Switch the filter between ignoring and respecting case. Does nothing without
+///   a filter line.
+/// * `CycleSort` - Switch to the next [`SortMode`](crate::SortMode). Does nothing in menus that
+///   were not given a sort mode, where the key stays available for type-ahead.
+/// * `Undo` - Take back a confirmed item while the undo window is open; see
+///   [`DropDownBuilder::undo_window`](crate::DropDownBuilder::undo_window). Does nothing
+///   otherwise, where the key stays available for type-ahead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    HalfPageUp,
+    HalfPageDown,
+    First,
+    Last,
+    Confirm,
+    ConfirmAndStay,
+    Cancel,
+    Back,
+    Left,
+    Right,
+    Interrupt,
+    Toggle,
+    SelectAll,
+    SelectNone,
+    ToggleAll,
+    Invert,
+    TogglePredicate,
+    CycleSort,
+    MoveItemUp,
+    MoveItemDown,
+    Help,
+    Search,
+    NextMatch,
+    PrevMatch,
+    CycleMatchMode,
+    ToggleCase,
+    Undo,
+}
+
+impl Action {
+    /// What the action does, as listed on the help screen.
+    pub(crate) fn description(self) -> &'static str {
+        match self {
+            Action::Up => "Move up",
+            Action::Down => "Move down",
+            Action::PageUp => "Previous page",
+            Action::PageDown => "Next page",
+            Action::HalfPageUp => "Up half a page",
+            Action::HalfPageDown => "Down half a page",
+            Action::First => "First item",
+            Action::Last => "Last item",
+            Action::Confirm => "Confirm",
+            Action::ConfirmAndStay => "Confirm and keep the menu open",
+            Action::Cancel => "Clear the filter, go back or cancel",
+            Action::Back => "Back to the parent menu",
+            Action::Left => "Left in a grid, or back",
+            Action::Right => "Right in a grid",
+            Action::Interrupt => "Quit",
+            Action::Toggle => "Check or uncheck the item",
+            Action::SelectAll => "Check all",
+            Action::SelectNone => "Uncheck all",
+            Action::ToggleAll => "Check all, or uncheck all",
+            Action::Invert => "Invert the checks",
+            Action::TogglePredicate => "Show only matching items, or all",
+            Action::CycleSort => "Change the sort order",
+            Action::MoveItemUp => "Move the item up",
+            Action::MoveItemDown => "Move the item down",
+            Action::Help => "Show this help",
+            Action::Search => "Search",
+            Action::NextMatch => "Next match",
+            Action::PrevMatch => "Previous match",
+            Action::CycleMatchMode => "Change how the filter matches",
+            Action::ToggleCase => "Ignore or respect case in the filter",
+            Action::Undo => "Undo the selection",
+        }
+    }
+}
+
+/// A key together with the modifiers held while pressing it.
+pub(crate) type Key = (KeyCode, KeyModifiers);
+
+/// The keys typed so far of an incomplete key sequence such as `gg`, and when the last of them
+/// was pressed.
+#[derive(Debug, Default)]
+pub(crate) struct PendingKeys {
+    keys: Vec<Key>,
+    last: Option<Instant>,
+}
+
+impl PendingKeys {
+    /// Whether a sequence is under way.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+/// Maps keys and key sequences to menu [`Action`]s.
+///
+/// [`KeyMap::default`] provides the standard bindings (arrows, PageUp/PageDown, Home/End, Enter,
+/// Shift+Enter for confirming without closing, Escape, Ctrl-C, Left/Right for grids, Left/Backspace for leaving submenus, Tab for the filter
+/// predicate, `s` for sorting, Space / `a` / `i` / `n` for multi-select, and Shift+Up/Down for
+/// reordering, `?` for the help screen, `/` / `n` / `N` for searching, and Ctrl-R / Alt-C for the
+/// filter's match mode and case sensitivity, and `u` for taking back a selection in the undo
+/// window); [`KeyMap::bind`] adds to or overrides them:
+///
+/// ```
+/// use crossterm::event::KeyCode;
+/// use ter_menu::{Action, KeyMap};
+///
+/// let keymap = KeyMap::default()
+///     .bind(KeyCode::Char('j'), Action::Down)
+///     .bind(KeyCode::Char('k'), Action::Up)
+///     .bind(KeyCode::Char('q'), Action::Cancel);
+/// ```
+///
+/// In filter mode, plain characters are typed into the filter before the key map is consulted,
+/// so letter bindings only apply to menus without a filter line.
+///
+/// A key can mean something else in multi-select menus, as `n` does: it jumps to the next search
+/// match elsewhere but unchecks every item there. [`KeyMap::bind_multi_select`] adds such
+/// bindings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyMap {
+    bindings: HashMap<Vec<Key>, Action>,
+    /// Bindings that take precedence over `bindings` in multi-select menus.
+    multi_select: HashMap<Key, Action>,
+}
+
+impl KeyMap {
+    /// A key map without any bindings.
+    pub fn empty() -> Self {
+        Self {
+            bindings: HashMap::new(),
+            multi_select: HashMap::new(),
+        }
+    }
+
+    /// The default bindings plus vi-style navigation: `j`/`k` to move, `gg`/`G` to jump to the
+    /// first/last item, and Ctrl-d/Ctrl-u to scroll by half a page.
+    pub fn vim() -> Self {
+        Self::default()
+            .bind(KeyCode::Char('j'), Action::Down)
+            .bind(KeyCode::Char('k'), Action::Up)
+            .bind_sequence([KeyCode::Char('g'), KeyCode::Char('g')], Action::First)
+            .bind(KeyCode::Char('G'), Action::Last)
+            .bind_with(
+                KeyCode::Char('d'),
+                KeyModifiers::CONTROL,
+                Action::HalfPageDown,
+            )
+            .bind_with(
+                KeyCode::Char('u'),
+                KeyModifiers::CONTROL,
+                Action::HalfPageUp,
+            )
+    }
+
+    /// Binds `code` pressed without modifiers to `action`, replacing any previous binding.
+    pub fn bind(self, code: KeyCode, action: Action) -> Self {
+        self.bind_with(code, KeyModifiers::NONE, action)
+    }
+
+    /// Binds `code` pressed together with `modifiers` (e.g. `KeyModifiers::CONTROL`) to `action`,
+    /// replacing any previous binding.
+    pub fn bind_with(mut self, code: KeyCode, modifiers: KeyModifiers, action: Action) -> Self {
+        let key = normalize(code, modifiers);
+        self.multi_select.remove(&key);
+        self.bindings.insert(vec![key], action);
+        self
+    }
+
+    /// Binds `code` pressed without modifiers to `action` in multi-select menus only, where it
+    /// takes precedence over the binding for other menus.
+    pub fn bind_multi_select(mut self, code: KeyCode, action: Action) -> Self {
+        self.multi_select
+            .insert(normalize(code, KeyModifiers::NONE), action);
+        self
+    }
+
+    /// Binds a sequence of keys pressed one after another without modifiers, such as `gg`, to
+    /// `action`.
+    ///
+    /// While a sequence is incomplete the menu waits for the next key, for up to a second. A key
+    /// that does not continue the sequence, or comes later, abandons it and is handled on its
+    /// own.
+    pub fn bind_sequence(
+        mut self,
+        codes: impl IntoIterator<Item = KeyCode>,
+        action: Action,
+    ) -> Self {
+        let keys: Vec<Key> = codes
+            .into_iter()
+            .map(|code| normalize(code, KeyModifiers::NONE))
+            .collect();
+        if !keys.is_empty() {
+            self.bindings.insert(keys, action);
+        }
+        self
+    }
+
+    /// Removes the bindings for `code` pressed without modifiers, in multi-select menus too.
+    pub fn unbind(mut self, code: KeyCode) -> Self {
+        self.bindings
+            .remove([(code, KeyModifiers::NONE)].as_slice());
+        self.multi_select.remove(&(code, KeyModifiers::NONE));
+        self
+    }
+
+    /// The action bound to `event` on its own outside multi-select menus, if any. Sequences are
+    /// not considered.
+    pub fn action_for(&self, event: &KeyEvent) -> Option<Action> {
+        self.bindings
+            .get([normalize(event.code, event.modifiers)].as_slice())
+            .copied()
+    }
+
+    /// Feeds `event`, pressed at `now`, into the partially typed sequence `pending`, using the
+    /// multi-select bindings if `multi_select` is set. A sequence whose last key came more than
+    /// [`SEQUENCE_TIMEOUT`] ago is abandoned first.
+    ///
+    /// # Returns
+    /// The action once `pending` plus `event` completes a binding, after which `pending` is
+    /// cleared. `None` if the keys so far are the start of a longer sequence (kept in `pending`)
+    /// or if nothing matches.
+    pub(crate) fn resolve(
+        &self,
+        pending: &mut PendingKeys,
+        event: &KeyEvent,
+        multi_select: bool,
+        now: Instant,
+    ) -> Option<Action> {
+        if pending
+            .last
+            .replace(now)
+            .is_some_and(|last| now.saturating_duration_since(last) > SEQUENCE_TIMEOUT)
+        {
+            pending.keys.clear();
+        }
+        let pending = &mut pending.keys;
+        let key = normalize(event.code, event.modifiers);
+        if multi_select
+            && pending.is_empty()
+            && let Some(action) = self.multi_select.get(&key)
+        {
+            return Some(*action);
+        }
+        // Retry the key on its own if it breaks off a pending sequence.
+        let attempts = if pending.is_empty() { 1 } else { 2 };
+        for _ in 0..attempts {
+            pending.push(key);
+            if let Some(action) = self.bindings.get(pending.as_slice()) {
+                pending.clear();
+                return Some(*action);
+            }
+            if self.is_prefix(pending) {
+                return None;
+            }
+            pending.clear();
+        }
+        None
+    }
+
+    /// The bindings as `(keys, action)` pairs for the help screen, one per action in declaration
+    /// order, with every key bound to the action listed, e.g. `("↓, j", Action::Down)`. With
+    /// `multi_select`, the multi-select bindings replace those of the same keys.
+    pub(crate) fn describe(&self, multi_select: bool) -> Vec<(String, Action)> {
+        let overrides: Vec<(Vec<Key>, Action)> = if multi_select {
+            self.multi_select
+                .iter()
+                .map(|(key, action)| (vec![*key], *action))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let mut bindings: Vec<(&Vec<Key>, Action)> = self
+            .bindings
+            .iter()
+            .filter(|(keys, _)| !overrides.iter().any(|(key, _)| key == *keys))
+            .chain(overrides.iter().map(|(keys, action)| (keys, action)))
+            .map(|(keys, action)| (keys, *action))
+            .collect();
+        // Arrows and other named keys before letters, as in "↓, j".
+        bindings.sort_by_key(|(keys, action)| {
+            let letters = keys
+                .iter()
+                .any(|(code, _)| matches!(code, KeyCode::Char(_)));
+            (*action as usize, keys.len(), letters, key_names(keys))
+        });
+        let mut described: Vec<(String, Action)> = Vec::new();
+        for (keys, action) in bindings {
+            match described.last_mut() {
+                Some((names, last)) if *last == action => {
+                    names.push_str(", ");
+                    names.push_str(&key_names(keys));
+                }
+                _ => described.push((key_names(keys), action)),
+            }
+        }
+        described
+    }
+
+    /// Whether some binding starts with, but is longer than, `keys`.
+    fn is_prefix(&self, keys: &[Key]) -> bool {
+        self.bindings
+            .keys()
+            .any(|binding| binding.len() > keys.len() && binding.starts_with(keys))
+    }
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self::empty()
+            .bind(KeyCode::Up, Action::Up)
+            .bind(KeyCode::Down, Action::Down)
+            .bind(KeyCode::PageUp, Action::PageUp)
+            .bind(KeyCode::PageDown, Action::PageDown)
+            .bind(KeyCode::Home, Action::First)
+            .bind(KeyCode::End, Action::Last)
+            .bind(KeyCode::Enter, Action::Confirm)
+            .bind_with(KeyCode::Enter, KeyModifiers::SHIFT, Action::ConfirmAndStay)
+            .bind(KeyCode::Esc, Action::Cancel)
+            .bind_with(KeyCode::Char('c'), KeyModifiers::CONTROL, Action::Interrupt)
+            .bind(KeyCode::Left, Action::Left)
+            .bind(KeyCode::Right, Action::Right)
+            .bind(KeyCode::Backspace, Action::Back)
+            .bind(KeyCode::Tab, Action::TogglePredicate)
+            .bind(KeyCode::Char('s'), Action::CycleSort)
+            .bind_with(KeyCode::Up, KeyModifiers::SHIFT, Action::MoveItemUp)
+            .bind_with(KeyCode::Down, KeyModifiers::SHIFT, Action::MoveItemDown)
+            .bind(KeyCode::Char('?'), Action::Help)
+            .bind(KeyCode::Char('/'), Action::Search)
+            .bind(KeyCode::Char('n'), Action::NextMatch)
+            .bind(KeyCode::Char('N'), Action::PrevMatch)
+            .bind_with(
+                KeyCode::Char('r'),
+                KeyModifiers::CONTROL,
+                Action::CycleMatchMode,
+            )
+            .bind_with(KeyCode::Char('c'), KeyModifiers::ALT, Action::ToggleCase)
+            .bind(KeyCode::Char(' '), Action::Toggle)
+            .bind(KeyCode::Char('a'), Action::ToggleAll)
+            .bind(KeyCode::Char('i'), Action::Invert)
+            .bind_multi_select(KeyCode::Char('n'), Action::SelectNone)
+            .bind(KeyCode::Char('u'), Action::Undo)
+            .bind(KeyCode::Char('U'), Action::Undo)
+    }
+}
+
+/// How a key or key sequence is written on the help screen, e.g. `Ctrl-d`, `Shift-↑` or `gg`.
+fn key_names(keys: &[Key]) -> String {
+    keys.iter()
+        .map(|&(code, modifiers)| {
+            let mut name = String::new();
+            if modifiers.contains(KeyModifiers::CONTROL) {
+                name.push_str("Ctrl-");
+            }
+            if modifiers.contains(KeyModifiers::ALT) {
+                name.push_str("Alt-");
+            }
+            if modifiers.contains(KeyModifiers::SHIFT) {
+                name.push_str("Shift-");
+            }
+            name.push_str(&match code {
+                KeyCode::Up => "↑".to_string(),
+                KeyCode::Down => "↓".to_string(),
+                KeyCode::Left => "←".to_string(),
+                KeyCode::Right => "→".to_string(),
+                KeyCode::Char(' ') => "Space".to_string(),
+                KeyCode::Char(c) => c.to_string(),
+                KeyCode::Esc => "Esc".to_string(),
+                KeyCode::PageUp => "PgUp".to_string(),
+                KeyCode::PageDown => "PgDn".to_string(),
+                KeyCode::F(n) => format!("F{}", n),
+                other => format!("{:?}", other),
+            });
+            name
+        })
+        .collect()
+}
+
+/// Shift is already reflected in the character itself ('J' vs 'j'), and terminals disagree about
+/// whether to report it, so it is ignored for character keys.
+fn normalize(code: KeyCode, modifiers: KeyModifiers) -> Key {
+    match code {
+        KeyCode::Char(_) => (code, modifiers - KeyModifiers::SHIFT),
+        _ => (code, modifiers),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn a_sequence_resolves_once_complete() {
+        let keymap = KeyMap::vim();
+        let mut pending = PendingKeys::default();
+        let now = Instant::now();
+        let g = press(KeyCode::Char('g'));
+
+        assert_eq!(keymap.resolve(&mut pending, &g, false, now), None);
+        assert!(!pending.is_empty());
+        assert_eq!(
+            keymap.resolve(&mut pending, &g, false, now),
+            Some(Action::First)
+        );
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn a_partial_sequence_times_out_before_a_complete_one() {
+        let keymap = KeyMap::vim();
+        let mut pending = PendingKeys::default();
+        let start = Instant::now();
+        let later = start + SEQUENCE_TIMEOUT * 2;
+        let g = press(KeyCode::Char('g'));
+
+        assert_eq!(keymap.resolve(&mut pending, &g, false, start), None);
+        // Too late to complete the first `g`, so this one starts over.
+        assert_eq!(keymap.resolve(&mut pending, &g, false, later), None);
+        assert_eq!(
+            keymap.resolve(&mut pending, &g, false, later),
+            Some(Action::First)
+        );
+    }
+
+    #[test]
+    fn a_key_breaking_off_a_sequence_is_handled_on_its_own() {
+        let keymap = KeyMap::vim();
+        let mut pending = PendingKeys::default();
+        let now = Instant::now();
+
+        let g = press(KeyCode::Char('g'));
+        assert_eq!(keymap.resolve(&mut pending, &g, false, now), None);
+        assert_eq!(
+            keymap.resolve(&mut pending, &press(KeyCode::Char('j')), false, now),
+            Some(Action::Down)
+        );
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn multi_select_bindings_apply_only_in_multi_select_menus() {
+        let keymap = KeyMap::default();
+        let mut pending = PendingKeys::default();
+        let now = Instant::now();
+        let n = press(KeyCode::Char('n'));
+
+        assert_eq!(
+            keymap.resolve(&mut pending, &n, false, now),
+            Some(Action::NextMatch)
+        );
+        assert_eq!(
+            keymap.resolve(&mut pending, &n, true, now),
+            Some(Action::SelectNone)
+        );
+        let rebound = keymap.bind(KeyCode::Char('n'), Action::Down);
+        assert_eq!(
+            rebound.resolve(&mut pending, &n, true, now),
+            Some(Action::Down)
+        );
+    }
+}
@@ -1,213 +1,96 @@
-use crossterm::{
-    event::{self, Event, KeyCode},
-    terminal::{disable_raw_mode, enable_raw_mode},
-};
-use crossterm::terminal::{Clear, ClearType};
-use crossterm::ExecutableCommand;
-use std::collections::HashMap;
-use std::fmt::Display;
-use std::hash::Hash;
-use std::sync::{Arc, Mutex};
-use std::thread::JoinHandle;
-use std::sync::mpsc;
-use std::time::Instant;
-use std::{
-    io::{self, prelude::*},
-    thread,
-};
-use std::sync::mpsc::Receiver;
-
-/// A terminal-based interactive dropdown selection component.
-///
-/// Allows users to navigate through options using keyboard arrows, confirm selections with Enter,
-/// and cancel with Escape. Uses raw terminal mode for input handling and provides visual feedback
-/// with highlighted selected items.
-///
-/// # Type Parameters
-/// * `T` - The type of items in the dropdown. Must implement necessary traits for display, hashing,
-///   cloning, thread safety, and equality checks.
-/// * `F` - The type of callback function triggered when an item is selected. Takes a reference to `T`
-///   as a parameter.
-#[derive(Debug)]
-pub struct TerminalDropDown<T, F>
-where
-    T: Display + Hash + Clone + Send + Eq + 'static,
-    F: FnOnce(&T) + Send + 'static,
-{
-    drop_down: Arc<Mutex<HashMap<T, F>>>,
-    handle: JoinHandle<()>,
-    item_n: usize,
-    receiver: Receiver<Option<usize>>,
-}
-
-impl<T, F> TerminalDropDown<T, F>
-where
-    T: Display + Hash + Clone + Send + Eq + 'static,
-    F: FnOnce(&T) + Send + 'static,
-{
-    /// Creates a new TerminalDropDown instance and starts the interaction thread.
-    ///
-    /// # Parameters
-    /// * `drop_down` - A HashMap containing items as keys and their corresponding callback functions
-    ///   as values.
-    /// * `item_n` - Maximum number of items to display in the terminal at once.
-    ///
-    /// # Returns
-    /// A new TerminalDropDown instance ready for user interaction.
-    ///
-    /// # Behavior
-    /// Spawns a new thread that handles user input, maintains selection state, and updates the display.
-    /// Enables raw terminal mode for low-level input handling and properly cleans up resources.
-    pub fn use_drop_down(drop_down: HashMap<T, F>, item_n: usize) -> Self {
-        let drop_down = Arc::new(Mutex::new(drop_down));
-        let cloned = drop_down.clone();
-        let (tx, rx) = mpsc::channel();
-
-        let handle = thread::spawn(move || {
-            let options: Vec<T> = cloned.lock().unwrap().keys().cloned().collect();
-            if options.is_empty() {
-                println!("\nNo options available.");
-                return;
-            }
-
-            // 处理可能的错误而不是忽略
-            if let Err(e) = enable_raw_mode() {
-                eprintln!("Failed to enable raw mode: {}", e);
-                return;
-            }
-
-            let mut current_idx = 0;
-            Self::display_menu(&options, current_idx, item_n);
-            let mut last_time = Instant::now();
-            loop {
-                // 处理事件读取错误
-                let event = match event::read() {
-                    Ok(Event::Key(key_event)) => key_event,
-                    Ok(_) => continue, // 忽略非键盘事件
-                    Err(e) => {
-                        eprintln!("Failed to read event: {}", e);
-                        break;
-                    }
-                };
-
-                if Instant::now().duration_since(last_time).as_millis() < 300 {
-                    continue;
-                }
-                last_time = Instant::now();
-
-                match event.code {
-                    KeyCode::Up => {
-                        current_idx = if current_idx == 0 {
-                            options.len() - 1
-                        } else {
-                            current_idx - 1
-                        };
-                        Self::display_menu(&options, current_idx, item_n);
-                    }
-                    KeyCode::Down => {
-                        current_idx = (current_idx + 1) % options.len();
-                        Self::display_menu(&options, current_idx, item_n);
-                    }
-                    KeyCode::Enter => {
-                        let selected_key = &options[current_idx];
-                        println!("\nConfirm delete: {}", selected_key);
-                        if let Some(func) = cloned.lock().unwrap().remove(selected_key) {
-                            tx.send(Some(current_idx)).unwrap();
-                            func(selected_key);
-                        }
-                        break;
-                    }
-                    KeyCode::Esc => {
-                        println!("\nDelete canceled.");
-                        break;
-                    }
-                    _ => {}
-                }
-            }
-
-            // 处理可能的错误而不是忽略
-            if let Err(e) = disable_raw_mode() {
-                eprintln!("Failed to disable raw mode: {}", e);
-            }
-        });
-
-        Self {
-            drop_down,
-            handle,
-            item_n,
-            receiver: rx,
-        }
-    }
-
-    /// Renders the current state of the dropdown menu in the terminal.
-    ///
-    /// # Parameters
-    /// * `options` - Slice of all available items in the dropdown.
-    /// * `current_idx` - Index of the currently selected item.
-    /// * `max_show` - Maximum number of items to display at once.
-    ///
-    /// # Behavior
-    /// Clears the terminal, displays a header with total/max items, renders visible items with
-    /// highlighting for the selected item, and shows navigation instructions. Implements a sliding
-    /// window for when there are more items than can be displayed at once.
-    pub fn display_menu(options: &[T], current_idx: usize, max_show: usize) {
-        // Clear screen and reset cursor position
-        let mut stdout = io::stdout();
-        stdout.execute(Clear(ClearType::All)).unwrap();
-        stdout.execute(crossterm::cursor::MoveTo(0, 0)).unwrap();
-        // 处理刷新错误
-        if let Err(e) = io::stdout().flush() {
-            eprintln!("Failed to flush stdout: {}", e);
-        }
-
-        if options.is_empty() {
-            println!("No options available.\nPress ESC to exit.");
-            return;
-        }
-
-        let total = options.len();
-        let start_idx = if total <= max_show {
-            0
-        } else {
-            current_idx
-                .saturating_sub(max_show / 2)
-                .min(total - max_show)
-        };
-        let end_idx = (start_idx + max_show).min(total);
-
-        println!("Please select.（ESC for canceling）:");
-        println!(
-            "Total: {} | Showing: {} - {}\n",
-            total,
-            start_idx + 1,
-            end_idx
-        );
-
-        for (i, option) in options
-            .iter()
-            .enumerate()
-            .skip(start_idx)
-            .take(end_idx - start_idx)
-        {
-            if i == current_idx {
-                println!("\x1B[7m> {}\x1B[0m", option);
-            } else {
-                println!("  {}", option);
-            }
-        }
-
-        println!("\n↑: Up | ↓: Down | Enter: Confirm | ESC: Cancel");
-    }
-
-    /// Blocks until the user interaction thread completes.
-    ///
-    /// # Returns
-    /// A Result indicating whether the thread joined successfully or encountered an error.
-    ///
-    /// # Usage
-    /// Call this method after creating the TerminalDropDown to wait for user input completion.
-    pub fn wait(self) -> thread::Result<Option<usize>> {
-        self.handle.join()?;
-        Ok(self.receiver.recv().unwrap())
-    }
-}
\ No newline at end of file
+//! Terminal-based interactive selection components.
+//!
+//! [`TerminalDropDown`] lets users pick one item, [`TerminalMultiSelect`] lets them toggle several,
+//! and [`select`] offers a blocking, callback-free front-end for simple scripts; [`select_scoped`]
+//! runs one on a thread of its own over items borrowed from the caller, and [`select_enum`]
+//! picks a variant of an enum. Filtered variants let users narrow long lists by typing a fuzzy
+//! query, and [`ReusableDropDown`] can be shown again and again without rebuilding its
+//! callbacks. [`Flow`] chains several prompts into a setup wizard, [`PathPicker`] browses the
+//! filesystem for a file, and [`Reorder`] lets users put a list in order.
+
+#[cfg(feature = "clap")]
+mod args;
+mod autocomplete;
+mod border;
+mod builder;
+mod cancel;
+mod drop_down;
+mod error;
+mod events;
+mod flow;
+mod fuzzy;
+mod hooks;
+mod input;
+mod intercept;
+mod item;
+mod keymap;
+mod live;
+mod matcher;
+mod menu;
+mod multi_select;
+mod output;
+mod password;
+mod path_picker;
+mod preview;
+mod render;
+mod reorder;
+mod result;
+mod reusable;
+mod scoped;
+mod selection;
+mod session;
+mod sort;
+#[cfg(feature = "serde")]
+mod spec;
+mod state;
+mod store;
+mod strings;
+mod table;
+mod terminal;
+mod theme;
+mod timeout;
+mod variants;
+#[cfg(feature = "ratatui")]
+mod widget;
+mod worker;
+
+#[cfg(feature = "clap")]
+pub use args::{get_matches_interactive, try_get_matches_from_interactive};
+pub use autocomplete::{Autocomplete, Completion};
+pub use border::Border;
+pub use builder::DropDownBuilder;
+pub use cancel::CancelHandle;
+pub use drop_down::TerminalDropDown;
+pub use error::TerMenuError;
+pub use events::{EventSource, ScriptedEvents, TerminalEvents};
+pub use flow::Flow;
+pub use input::{Input, input};
+pub use intercept::{Handled, KeyInterceptor};
+pub use item::{Confirmation, DescriptionLayout, ItemStyle, MenuEntry, MenuItem, Status};
+pub use keymap::{Action, KeyMap};
+pub use live::ItemsHandle;
+pub use matcher::MatchMode;
+pub use menu::{render_to_string, select, select_filtered, select_index, select_item};
+pub use multi_select::{MultiSelectBuilder, TerminalMultiSelect};
+pub use output::{FrameRecorder, Output};
+pub use password::{Password, Secret, password};
+pub use path_picker::PathPicker;
+pub use preview::PreviewLayout;
+pub use reorder::Reorder;
+pub use result::SelectionResult;
+pub use reusable::ReusableDropDown;
+pub use scoped::{ScopedDropDown, select_scoped};
+pub use selection::{CancelReason, Selection};
+pub use session::Replay;
+pub use sort::SortMode;
+#[cfg(feature = "serde")]
+pub use spec::{ItemSpec, MenuSpec, ThemeName};
+pub use state::{KeyOutcome, MenuState};
+pub use store::{FileStore, SelectionStore};
+pub use strings::Strings;
+pub use table::Column;
+pub use theme::Theme;
+pub use timeout::TimeoutAction;
+pub use variants::{Variants, select_enum};
+#[cfg(feature = "ratatui")]
+pub use widget::DropDownWidget;
@@ -0,0 +1,151 @@
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+
+/// Decides which items stay in the menu.
+type Keep<T> = Box<dyn FnMut(&T) -> bool + Send>;
+
+/// A change requested through an [`ItemsHandle`], applied by the menu the next time it polls.
+pub(crate) enum Change<T> {
+    Push(T),
+    Retain(Keep<T>),
+    /// A source passed to [`ItemsHandle::load`] started delivering items.
+    LoadStarted,
+    /// A source passed to [`ItemsHandle::load`] ran out of items.
+    LoadFinished,
+}
+
+/// Changes waiting for the menu.
+struct Pending<T> {
+    changes: Vec<Change<T>>,
+    /// Set once the menu has closed. Later changes are dropped.
+    closed: bool,
+}
+
+/// Adds and removes a running menu's items from another thread, e.g. hosts appearing in the list
+/// as they answer a network scan.
+///
+/// Obtained from [`TerminalDropDown::items_handle`](crate::TerminalDropDown::items_handle).
+/// Handles are cheap to clone and can be moved to any thread; they stay valid after the menu has
+/// closed, when changes are simply dropped.
+///
+/// # Behavior
+/// The menu picks up changes within a few milliseconds and redraws. The highlight stays on the
+/// same item; if that item was removed, it moves to the next remaining one. A submenu whose
+/// parent item was removed is closed. The filter and the checked items are kept.
+pub struct ItemsHandle<T> {
+    pending: Arc<Mutex<Pending<T>>>,
+}
+
+impl<T> ItemsHandle<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            pending: Arc::new(Mutex::new(Pending {
+                changes: Vec::new(),
+                closed: false,
+            })),
+        }
+    }
+
+    /// Appends `item` below the existing items.
+    pub fn push(&self, item: T) {
+        self.request(Change::Push(item));
+    }
+
+    /// Appends `items` below the existing items, in order.
+    pub fn extend(&self, items: impl IntoIterator<Item = T>) {
+        let mut pending = self.lock();
+        if !pending.closed {
+            pending.changes.extend(items.into_iter().map(Change::Push));
+        }
+    }
+
+    /// Removes every item for which `keep` returns `false`.
+    pub fn retain(&self, keep: impl FnMut(&T) -> bool + Send + 'static) {
+        self.request(Change::Retain(Box::new(keep)));
+    }
+
+    /// Removes every item equal to `item`.
+    pub fn remove(&self, item: T)
+    where
+        T: PartialEq + Send + 'static,
+    {
+        self.retain(move |existing| *existing != item);
+    }
+
+    /// Appends the items of a slow source, such as a paginated API or a directory scan, as they
+    /// arrive. `items` is read on a thread of its own, so the user can pick from the items loaded
+    /// so far; a channel's `Receiver` works as well as any iterator. Until it runs out, a spinner
+    /// and a "Loading…" row are shown below the items.
+    ///
+    /// Stops early once the menu has closed.
+    pub fn load<I>(&self, items: I)
+    where
+        I: IntoIterator<Item = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.request(Change::LoadStarted);
+        let handle = self.clone();
+        thread::spawn(move || {
+            // Ends the loading row even if the source panics.
+            let _finished = LoadGuard(handle.clone());
+            for item in items {
+                if handle.lock().closed {
+                    break;
+                }
+                handle.push(item);
+            }
+        });
+    }
+
+    fn request(&self, change: Change<T>) {
+        let mut pending = self.lock();
+        if !pending.closed {
+            pending.changes.push(change);
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Pending<T>> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Whether changes are waiting to be applied.
+    pub(crate) fn has_changes(&self) -> bool {
+        !self.lock().changes.is_empty()
+    }
+
+    /// The changes requested since the last call, oldest first.
+    pub(crate) fn take_changes(&self) -> Vec<Change<T>> {
+        std::mem::take(&mut self.lock().changes)
+    }
+
+    /// Drops the pending changes and any that are requested later, once the menu has closed.
+    pub(crate) fn close(&self) {
+        let mut pending = self.lock();
+        pending.closed = true;
+        pending.changes.clear();
+    }
+}
+
+impl<T> Clone for ItemsHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            pending: Arc::clone(&self.pending),
+        }
+    }
+}
+
+impl<T> fmt::Debug for ItemsHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ItemsHandle(..)")
+    }
+}
+
+/// Reports the end of a source passed to [`ItemsHandle::load`] when dropped.
+struct LoadGuard<T>(ItemsHandle<T>);
+
+impl<T> Drop for LoadGuard<T> {
+    fn drop(&mut self) {
+        self.0.request(Change::LoadFinished);
+    }
+}
@@ -0,0 +1,102 @@
+use crate::fuzzy::{FuzzyMatch, fuzzy_match, substring_match};
+
+/// How the text typed into the filter line is matched against the items.
+///
+/// Set with [`DropDownBuilder::match_mode`](crate::DropDownBuilder::match_mode). While
+/// filtering, Ctrl-R ([`Action::CycleMatchMode`](crate::Action::CycleMatchMode)) switches to the
+/// next mode, and Alt-C ([`Action::ToggleCase`](crate::Action::ToggleCase)) between ignoring and
+/// respecting case.
+///
+/// # Variants
+/// * `Fuzzy` - The typed characters appear in the label in order, not necessarily next to each
+///   other. The best matches are listed first.
+/// * `Substring` - The label contains the typed text as it is. Matches keep their order.
+/// * `Regex` - The label matches the typed text as a regular expression. Matches keep their
+///   order, and nothing matches while the expression is incomplete or invalid. Needs the `regex`
+///   feature.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MatchMode {
+    #[default]
+    Fuzzy,
+    Substring,
+    #[cfg(feature = "regex")]
+    Regex,
+}
+
+impl MatchMode {
+    /// Whether a longer text can only match fewer items, so that after typing a character only
+    /// the items matching so far need to be checked again. Not so for regular expressions, where
+    /// e.g. `a|` matches every item, also those `a` does not.
+    pub(crate) fn narrows(self) -> bool {
+        match self {
+            MatchMode::Fuzzy | MatchMode::Substring => true,
+            #[cfg(feature = "regex")]
+            MatchMode::Regex => false,
+        }
+    }
+
+    /// The mode [`Action::CycleMatchMode`](crate::Action::CycleMatchMode) switches to.
+    pub(crate) fn next(self) -> Self {
+        match self {
+            MatchMode::Fuzzy => MatchMode::Substring,
+            #[cfg(feature = "regex")]
+            MatchMode::Substring => MatchMode::Regex,
+            #[cfg(not(feature = "regex"))]
+            MatchMode::Substring => MatchMode::Fuzzy,
+            #[cfg(feature = "regex")]
+            MatchMode::Regex => MatchMode::Fuzzy,
+        }
+    }
+}
+
+/// A filter text prepared for matching many labels in one [`MatchMode`].
+pub(crate) enum Matcher<'p> {
+    Fuzzy(&'p str, bool),
+    Substring(&'p str, bool),
+    /// `None` if the text is not a valid expression.
+    #[cfg(feature = "regex")]
+    Regex(Option<regex::Regex>),
+}
+
+impl<'p> Matcher<'p> {
+    /// Prepares `pattern` for matching in `mode`, ignoring case unless `case_sensitive`.
+    pub(crate) fn new(mode: MatchMode, pattern: &'p str, case_sensitive: bool) -> Self {
+        match mode {
+            MatchMode::Fuzzy => Matcher::Fuzzy(pattern, case_sensitive),
+            MatchMode::Substring => Matcher::Substring(pattern, case_sensitive),
+            #[cfg(feature = "regex")]
+            MatchMode::Regex => Matcher::Regex(
+                regex::RegexBuilder::new(pattern)
+                    .case_insensitive(!case_sensitive)
+                    .build()
+                    .ok(),
+            ),
+        }
+    }
+
+    /// Matches `candidate`. Substring and regex matches all score the same.
+    pub(crate) fn find(&self, candidate: &str) -> Option<FuzzyMatch> {
+        match self {
+            Matcher::Fuzzy(pattern, case_sensitive) => {
+                fuzzy_match(pattern, candidate, *case_sensitive)
+            }
+            Matcher::Substring(pattern, case_sensitive) => {
+                substring_match(pattern, candidate, *case_sensitive).map(|positions| FuzzyMatch {
+                    score: 0,
+                    positions,
+                })
+            }
+            #[cfg(feature = "regex")]
+            Matcher::Regex(regex) => {
+                let found = regex.as_ref()?.find(candidate)?;
+                // Char indices, as for the other modes.
+                let start = candidate[..found.start()].chars().count();
+                let len = found.as_str().chars().count();
+                Some(FuzzyMatch {
+                    score: 0,
+                    positions: (start..start + len).collect(),
+                })
+            }
+        }
+    }
+}
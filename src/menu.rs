@@ -0,0 +1,3504 @@
+use crate::border::Border;
+use crate::cancel::{self, CancelHandle};
+use crate::error::TerMenuError;
+use crate::events::SharedEvents;
+use crate::fuzzy::substring_match;
+use crate::hooks::{CompareHook, Hooks, PredicateHook};
+use crate::intercept::Handled;
+use crate::item::{Confirmation, DescriptionLayout, MenuEntry, MenuItem, follow};
+use crate::keymap::{Action, Key, KeyMap, PendingKeys};
+use crate::live::{Change, ItemsHandle};
+use crate::matcher::{MatchMode, Matcher};
+use crate::output::Output;
+use crate::preview::{self, PreviewLayout};
+use crate::render::{Frame, InlineRenderer, Line, PopupRenderer, Renderer, ScreenRenderer};
+use crate::selection::{CancelReason, Interaction};
+use crate::session::SessionLog;
+use crate::sort::{self, SortMode};
+use crate::state::{KeyOutcome, MenuState};
+use crate::strings::{Strings, fill};
+use crate::terminal::TerminalSession;
+use crate::theme::Theme;
+use crate::timeout::TimeoutAction;
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
+    MouseEventKind,
+};
+use crossterm::style::{Attribute, ContentStyle, StyledContent};
+use crossterm::terminal;
+use std::env;
+use std::fmt::Display;
+use std::io::{self, BufWriter, IsTerminal, prelude::*};
+use std::ops::{ControlFlow, Deref};
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthStr;
+
+/// Presents a dropdown on the current thread and blocks until the user decides.
+///
+/// Unlike [`TerminalDropDown`](crate::TerminalDropDown), no callbacks or background thread are
+/// involved: the selected item is simply returned to the caller, which makes this convenient for
+/// sequential scripts. Nothing is printed once the menu closes.
+///
+/// # Parameters
+/// * `options` - Slice of items to choose from, rendered in the given order.
+/// * `item_n` - Maximum number of items to display in the terminal at once.
+///
+/// # Returns
+/// `Ok(Some(item))` with a clone of the confirmed item, `Ok(None)` if the user cancelled or there
+/// was nothing to select, or a [`TerMenuError`] if the terminal could not be driven.
+pub fn select<T: MenuEntry + Clone>(
+    options: &[T],
+    item_n: usize,
+) -> Result<Option<T>, TerMenuError> {
+    let mut menu = Menu::new(options, MenuConfig::returning(item_n));
+    Ok(menu.run()?.map(|idx| menu.entry(idx).clone()))
+}
+
+/// Like [`select`], but returns the position of the confirmed item in `options` instead of a
+/// clone of it, so `T` need not implement `Clone`. Suited to large or expensive items, e.g.
+/// rows of a table shared through an `Arc<[T]>`, which are then looked up by index.
+///
+/// # Returns
+/// `Ok(Some(index))` of the confirmed item, `Ok(None)` if the user cancelled or there was nothing
+/// to select, or a [`TerMenuError`] if the terminal could not be driven. Within a submenu, the
+/// index is that of the top-level item it was opened from.
+pub fn select_index<T: MenuEntry>(
+    options: &[T],
+    item_n: usize,
+) -> Result<Option<usize>, TerMenuError> {
+    let mut menu = Menu::new(options, MenuConfig::returning(item_n));
+    Ok(menu.run()?.map(|idx| menu.path_to(idx)[0]))
+}
+
+/// Like [`select`], but with a filter line: typing narrows the list using fuzzy matching.
+///
+/// # Parameters
+/// * `options` - Slice of items to choose from. With an empty filter they are rendered in the given
+///   order; otherwise the best matches are listed first.
+/// * `item_n` - Maximum number of items to display in the terminal at once.
+///
+/// # Behavior
+/// Printable characters are appended to the filter and Backspace removes the last one. Characters
+/// that matched are highlighted in each row. Escape clears a non-empty filter, and cancels the
+/// menu once the filter is empty.
+pub fn select_filtered<T: MenuEntry + Clone>(
+    options: &[T],
+    item_n: usize,
+) -> Result<Option<T>, TerMenuError> {
+    let config = MenuConfig {
+        filter: true,
+        ..MenuConfig::returning(item_n)
+    };
+    let mut menu = Menu::new(options, config);
+    Ok(menu.run()?.map(|idx| menu.entry(idx).clone()))
+}
+
+/// Like [`select`], but over [`MenuItem`]s: the labels are shown and the value of the confirmed
+/// item is returned. Entries made with [`MenuItem::submenu`] open their submenu instead of being
+/// returned.
+///
+/// # Returns
+/// `Ok(Some(value))` with a clone of the confirmed item's value, `Ok(None)` if the user cancelled
+/// or there was nothing to select, or a [`TerMenuError`] if the terminal could not be driven.
+pub fn select_item<V: Clone>(
+    items: &[MenuItem<V>],
+    item_n: usize,
+) -> Result<Option<V>, TerMenuError> {
+    let mut menu = Menu::new(items, MenuConfig::returning(item_n));
+    Ok(menu.run()?.map(|idx| menu.entry(idx).value.clone()))
+}
+
+/// Whole seconds shown in a countdown with `left` remaining, rounded up so that it never reads
+/// "0s" while the menu is still open.
+fn countdown_seconds(left: Duration) -> u64 {
+    left.as_secs() + u64::from(left.subsec_nanos() > 0)
+}
+
+/// Whether `code` moves the highlight or cursor, so that holding it scrolls.
+fn is_navigation(code: KeyCode) -> bool {
+    matches!(
+        code,
+        KeyCode::Up
+            | KeyCode::Down
+            | KeyCode::Left
+            | KeyCode::Right
+            | KeyCode::PageUp
+            | KeyCode::PageDown
+            | KeyCode::Home
+            | KeyCode::End
+            | KeyCode::Tab
+            | KeyCode::BackTab
+    )
+}
+
+/// Page size used when it should fit the terminal but the terminal's height is unknown.
+const FALLBACK_PAGE_SIZE: usize = 10;
+
+/// Terminal width assumed for laying out a preview pane when the real width is unknown.
+const FALLBACK_COLUMNS: usize = 80;
+
+/// How long the loading spinner shows each of its glyphs.
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Space between the cells of a grid.
+const GRID_GAP: &str = "  ";
+
+/// Two left clicks on the same row within this interval count as a double-click.
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Presentation and behaviour settings shared by every menu component.
+#[derive(Debug, Clone)]
+pub(crate) struct MenuConfig {
+    /// Maximum number of items to display in the terminal at once. `None` shows as many as fit.
+    pub(crate) page_size: Option<usize>,
+    /// Header line printed above the items.
+    pub(crate) prompt: String,
+    /// Index of the item highlighted when the menu opens.
+    pub(crate) default_index: usize,
+    /// Whether moving past the first/last item jumps to the other end.
+    pub(crate) wrap_navigation: bool,
+    /// Whether the terminal bell rings when Up or Down cannot move past the first or last item
+    /// because wrap-around navigation is off.
+    pub(crate) bell_at_edges: bool,
+    /// Whether typing narrows the list with a fuzzy filter.
+    pub(crate) filter: bool,
+    /// Printed after the user confirms. `{item}` is replaced with the selected item(s).
+    pub(crate) confirm_message: String,
+    /// Printed after the user cancels.
+    pub(crate) cancel_message: String,
+    /// Footer line with key instructions. `None` uses the default for the menu's mode.
+    pub(crate) help: Option<String>,
+    /// Repeats of the previously handled key arriving sooner than this after it are dropped, so
+    /// that a held key moves at most once per interval.
+    pub(crate) repeat_interval: Duration,
+    /// Whether mouse capture is enabled: wheel scrolling and clicking rows.
+    pub(crate) mouse: bool,
+    /// Whether keys are read with the kitty keyboard protocol where the terminal supports it.
+    pub(crate) keyboard_enhancement: bool,
+    /// Whether a single click selects a row instead of only highlighting it.
+    pub(crate) click_to_select: bool,
+    /// Whether the menu is drawn on the alternate screen, leaving the user's scrollback intact.
+    pub(crate) alternate_screen: bool,
+    /// Whether the menu is drawn below the cursor instead of clearing the screen, and erased
+    /// once the interaction is over.
+    pub(crate) inline: bool,
+    /// Which keys trigger which actions.
+    pub(crate) keymap: KeyMap,
+    /// Colors and glyphs used for drawing.
+    pub(crate) theme: Theme,
+    /// Whether to draw plain text without colors, cursor movement or other escape sequences.
+    /// Detected from `NO_COLOR` and `TERM=dumb`; implies [`Theme::plain`] and turns off the
+    /// alternate screen, inline mode and mouse capture.
+    pub(crate) plain: bool,
+    /// Where the preview pane goes, if the menu has one.
+    pub(crate) preview_layout: PreviewLayout,
+    /// Whether the first nine rows on the page are numbered and can be picked with keys 1-9.
+    pub(crate) numbered: bool,
+    /// Where the menu is drawn and its messages printed.
+    pub(crate) output: Output,
+    /// Where input comes from instead of the terminal, if anywhere. Such menus leave the
+    /// terminal's modes alone and never drop repeated keys.
+    pub(crate) events: Option<SharedEvents>,
+    /// Non-selectable rows, each drawn before the option with the given index. Hidden while a
+    /// filter is typed, since matches are no longer in their original order.
+    pub(crate) dividers: Vec<(usize, Divider)>,
+    /// How long the menu waits for the first key press, and what happens when nobody presses
+    /// one in time.
+    pub(crate) timeout: Option<(Duration, TimeoutAction)>,
+    /// The order the items are listed in when the menu opens. `None` keeps the given order and
+    /// leaves [`Action::CycleSort`] without effect.
+    pub(crate) sort: Option<SortMode>,
+    /// Column titles drawn above the items when they are shown as table rows.
+    pub(crate) table_header: Option<String>,
+    /// Number of items side by side in each row, for short labels. `None` lists one per row.
+    pub(crate) grid: Option<usize>,
+    /// Draw the items side by side on the prompt's line, moving with Left and Right.
+    pub(crate) horizontal: bool,
+    /// In multi-select mode, how many items must be checked before Enter confirms.
+    pub(crate) min_selected: usize,
+    /// In multi-select mode, how many items may be checked at most.
+    pub(crate) max_selected: Option<usize>,
+    /// In multi-select mode, number the checked items in the order they were checked and
+    /// return them in that order.
+    pub(crate) ordered: bool,
+    /// Let Shift+Up/Down move the highlighted item, for [`Reorder`](crate::Reorder).
+    pub(crate) reorder: bool,
+    /// Draw the prompt, breadcrumb and totals lines above the items.
+    pub(crate) show_header: bool,
+    /// Draw the key instructions below the items.
+    pub(crate) show_footer: bool,
+    /// Texts the menu shows on its own.
+    pub(crate) strings: Strings,
+    /// Row and column of the top-left corner of a popup drawn over the screen contents.
+    pub(crate) popup: Option<(u16, u16)>,
+    /// The text of the screen under a popup, one string per row, to restore once it closes.
+    pub(crate) backdrop: Vec<String>,
+    /// How the filter line matches items when the menu opens.
+    pub(crate) match_mode: MatchMode,
+    /// Whether the filter line respects case when the menu opens.
+    pub(crate) case_sensitive: bool,
+    /// Wrap items too long for the terminal onto further lines instead of cutting them off.
+    pub(crate) soft_wrap: bool,
+    /// Where item descriptions are drawn.
+    pub(crate) description_layout: DescriptionLayout,
+    /// How long a confirmed item can still be taken back before the menu closes.
+    pub(crate) undo_window: Option<Duration>,
+    /// The box drawn around the menu, if any.
+    pub(crate) border: Option<Border>,
+    /// Text in the top edge of the box.
+    pub(crate) border_title: Option<String>,
+    /// Ask whether to retry, pick another item or give up when the confirmed item's fallible
+    /// callback fails, instead of ending with the error right away.
+    pub(crate) retry_prompt: bool,
+    /// Where key presses, resizes and frames are recorded for replaying the session, if
+    /// anywhere.
+    pub(crate) session: Option<SessionLog>,
+}
+
+impl MenuConfig {
+    /// Default settings with the given page size.
+    pub(crate) fn new(page_size: usize) -> Self {
+        Self {
+            page_size: Some(page_size),
+            prompt: Strings::english().prompt,
+            default_index: 0,
+            wrap_navigation: true,
+            bell_at_edges: false,
+            filter: false,
+            confirm_message: Strings::english().confirm_message,
+            cancel_message: Strings::english().cancel_message,
+            help: None,
+            repeat_interval: Duration::from_millis(50),
+            keyboard_enhancement: false,
+            mouse: false,
+            click_to_select: false,
+            alternate_screen: false,
+            inline: false,
+            keymap: KeyMap::default(),
+            theme: Theme::default(),
+            plain: plain_terminal(),
+            preview_layout: PreviewLayout::default(),
+            numbered: false,
+            output: Output::default(),
+            events: None,
+            dividers: Vec::new(),
+            timeout: None,
+            sort: None,
+            table_header: None,
+            grid: None,
+            horizontal: false,
+            min_selected: 0,
+            max_selected: None,
+            ordered: false,
+            reorder: false,
+            show_header: true,
+            show_footer: true,
+            strings: Strings::english(),
+            match_mode: MatchMode::default(),
+            case_sensitive: false,
+            soft_wrap: false,
+            description_layout: DescriptionLayout::default(),
+            undo_window: None,
+            popup: None,
+            backdrop: Vec::new(),
+            border: None,
+            border_title: None,
+            retry_prompt: false,
+            session: None,
+        }
+    }
+
+    /// Default settings for the functions that return the chosen item, which print nothing once
+    /// the menu closes: the caller decides what the choice means.
+    pub(crate) fn returning(page_size: usize) -> Self {
+        Self {
+            confirm_message: String::new(),
+            cancel_message: String::new(),
+            ..Self::new(page_size)
+        }
+    }
+}
+
+/// The renderer that draws a menu set up with `config`.
+fn renderer_for(config: &MenuConfig) -> Renderer {
+    if let Output::Frames(recorder) = &config.output {
+        Renderer::Record(recorder.clone())
+    } else if config.plain {
+        Renderer::Plain
+    } else if let Some((row, column)) = config.popup {
+        Renderer::Popup(PopupRenderer::new(row, column, config.backdrop.clone()))
+    } else if (config.inline || config.horizontal) && !config.alternate_screen {
+        Renderer::Inline(InlineRenderer::new(config.mouse))
+    } else {
+        Renderer::FullScreen(ScreenRenderer::default())
+    }
+}
+
+/// A non-selectable row drawn between items.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Divider {
+    /// A horizontal rule grouping the items around it.
+    Separator,
+    /// A title for the items that follow, up to the next header.
+    Header(String),
+}
+
+/// Whether the environment asks for output without escape sequences: `NO_COLOR` set to a
+/// non-empty value (see <https://no-color.org>), or `TERM=dumb`.
+fn plain_terminal() -> bool {
+    let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    let dumb = env::var_os("TERM").is_some_and(|term| term == "dumb");
+    no_color || dumb
+}
+
+impl Default for MenuConfig {
+    /// Default settings with a page size that fits the terminal.
+    fn default() -> Self {
+        Self {
+            page_size: None,
+            ..Self::new(FALLBACK_PAGE_SIZE)
+        }
+    }
+}
+
+/// Selection state shared by every menu component.
+///
+/// Tracks the highlighted row, the rows visible under the current filter and, in multi-select
+/// mode, which rows are checked. The interaction loop and the renderer both operate on this struct
+/// so that single and multi-select menus behave identically apart from the checkbox handling.
+pub(crate) struct Menu<'a, T> {
+    /// The top-level options.
+    root: Items<'a, T>,
+    pub(crate) config: MenuConfig,
+    /// Position of the highlighted row within `visible`.
+    cursor: usize,
+    /// Indices into `options` of the rows currently shown, in display order.
+    visible: Vec<usize>,
+    /// Matched char positions for each entry of `visible`, used for highlighting. Empty while
+    /// no filter text is typed, so that huge lists do not carry a vector per option.
+    highlights: Vec<Vec<usize>>,
+    /// `Some` in filter mode, holding the text typed so far.
+    query: Option<String>,
+    /// `Some` in multi-select mode, holding one flag per option.
+    checked: Option<Vec<bool>>,
+    /// Row position and time of the previous left click, for double-click detection.
+    last_click: Option<(usize, Instant)>,
+    /// The previously handled key and when it was handled, for limiting the rate of repeats.
+    last_key: Option<(Key, Instant)>,
+    /// Whether the screen lags behind the state because drawing waits for queued input.
+    stale: bool,
+    /// Whether input already queued is handled before drawing, as for the terminal.
+    coalesce: bool,
+    /// Whether held keys arrive as [`KeyEventKind::Repeat`] rather than as more presses, as with
+    /// the kitty protocol and scripts.
+    reports_repeats: bool,
+    /// Keys typed so far of an incomplete key-map sequence such as `gg`.
+    pending_keys: PendingKeys,
+    /// Width and height of the terminal, once known. Limits how many items fit on a page and
+    /// how long a line may be.
+    terminal_size: Option<(u16, u16)>,
+    /// Caller-supplied closures, if any.
+    hooks: Option<&'a mut Hooks<T>>,
+    /// Index into `options` of the item `on_highlight` was last called with.
+    notified: Option<usize>,
+    /// Lets another thread close the menu, if the owner handed one out.
+    cancel: Option<CancelHandle>,
+    /// When the configured timeout runs out. Cleared by the first key press or click.
+    deadline: Option<Instant>,
+    /// The menus above the open submenu, outermost first. Empty in the top-level menu.
+    trail: Vec<Level>,
+    /// Whether the filter predicate hook is switched on and hides the items it rejects.
+    predicate_on: bool,
+    /// The order the options are listed in while no filter text is typed.
+    sort: SortMode,
+    /// Where changes to the top-level options come from, if they can change while the menu is
+    /// open.
+    live: Option<Live<T>>,
+    /// When the menu was created, for reporting how long the user took.
+    opened: Instant,
+    /// Number of key presses handled so far.
+    keystrokes: usize,
+    /// Why the menu closed without a choice, once it has.
+    cancelled_by: Option<CancelReason>,
+    /// Set when the cursor hit an end of the list, so that the next redraw rings the bell.
+    at_edge: bool,
+    /// Indices into `options` of the checked items, in the order they were checked.
+    order: Vec<usize>,
+    /// Whether the help screen is shown instead of the items.
+    show_help: bool,
+    /// The text of the last search, highlighted in the labels and jumped to with
+    /// [`Action::NextMatch`] and [`Action::PrevMatch`].
+    search: Option<String>,
+    /// Whether keys are typed into the search line, from [`Action::Search`] until Enter or
+    /// Escape.
+    search_open: bool,
+    /// Position of the highlight when the search line was opened, where typing searches from.
+    search_from: usize,
+    /// How the filter line currently matches items.
+    match_mode: MatchMode,
+    /// Whether the filter line currently respects case.
+    case_sensitive: bool,
+    /// When the undo window of the confirmed, highlighted item closes, while it is open.
+    undo_until: Option<Instant>,
+    /// While a destructive item waits to be confirmed a second time, the text typed to confirm
+    /// it.
+    confirming: Option<String>,
+    /// Whether the item waiting in `confirming` was chosen with
+    /// [`Action::ConfirmAndStay`], so that confirming it keeps the menu open.
+    confirming_stays: bool,
+    renderer: Renderer,
+}
+
+/// The options of a menu, borrowed from the caller or owned so that they can change while the
+/// menu is open.
+enum Items<'a, T> {
+    Borrowed(&'a [T]),
+    Owned(Vec<T>),
+}
+
+impl<T> Deref for Items<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match self {
+            Items::Borrowed(items) => items,
+            Items::Owned(items) => items,
+        }
+    }
+}
+
+/// A menu the user opened a submenu from, restored when they go back to it.
+struct Level {
+    /// Index into the menu's options of the entry whose submenu was opened.
+    entered: usize,
+    query: Option<String>,
+}
+
+/// The top-level options of a menu whose items change while it is open.
+struct Live<T> {
+    handle: ItemsHandle<T>,
+    /// A number per top-level option that stays the same while other options come and go, in
+    /// ascending order. The options the menu was created with are numbered from zero.
+    ids: Vec<usize>,
+    next_id: usize,
+    /// How many options the menu was created with.
+    initial: usize,
+    /// Number of sources passed to [`ItemsHandle::load`] that are still delivering items.
+    loading: usize,
+    /// When the menu was created, for animating the loading spinner.
+    since: Instant,
+}
+
+impl<T> Drop for Live<T> {
+    fn drop(&mut self) {
+        self.handle.close();
+    }
+}
+
+/// One row of the item area on the current page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Row<'d> {
+    /// The item at this position within `visible`.
+    Item(usize),
+    /// A further line of the item at this position, wrapped because it was too long.
+    Wrapped(usize),
+    /// The description of the item at this position, on a line of its own.
+    Description(usize),
+    Divider(&'d Divider),
+}
+
+/// Why the event loop woke up.
+#[derive(Debug)]
+enum Wake {
+    Input(Event),
+    /// The countdown's remaining seconds changed or the loading spinner moved on, and the menu
+    /// must be redrawn.
+    Tick,
+    /// The owner closed the menu through its [`CancelHandle`].
+    Cancelled,
+    /// The timeout ran out before the user pressed a key.
+    TimedOut,
+    /// The undo window closed without the confirmed item being taken back.
+    UndoOver,
+    /// Another thread changed the options through an [`ItemsHandle`].
+    ItemsChanged,
+}
+
+/// What the event loop should do after an input event was handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Step {
+    /// Nothing visible changed.
+    Ignore,
+    /// The state changed and the menu must be redrawn.
+    Redraw,
+    /// The user confirmed the highlighted item.
+    Confirm,
+    /// The user dismissed the menu.
+    Cancel,
+    /// The user pressed Ctrl-C.
+    Interrupt,
+}
+
+impl<'a, T: MenuEntry> Menu<'a, T> {
+    /// Creates a single-selection menu over `options`.
+    pub(crate) fn new(options: &'a [T], config: MenuConfig) -> Self {
+        Self::with_items(Items::Borrowed(options), config)
+    }
+
+    /// Creates a single-selection menu over `options` that picks up the items added and removed
+    /// through `handle` while it is open.
+    pub(crate) fn live(options: Vec<T>, handle: ItemsHandle<T>, config: MenuConfig) -> Self {
+        let initial = options.len();
+        let mut menu = Self {
+            live: Some(Live {
+                handle,
+                ids: (0..initial).collect(),
+                next_id: initial,
+                initial,
+                loading: 0,
+                since: Instant::now(),
+            }),
+            ..Self::with_items(Items::Owned(options), config)
+        };
+        // Items may have been requested before the menu was shown.
+        menu.apply_changes();
+        menu
+    }
+
+    fn with_items(options: Items<'a, T>, mut config: MenuConfig) -> Self {
+        if config.plain {
+            config.theme = Theme::plain();
+            config.border = config.border.map(|_| Border::ascii());
+        }
+        let mut menu = Self {
+            cursor: config.default_index.min(options.len().saturating_sub(1)),
+            visible: (0..options.len()).collect(),
+            highlights: Vec::new(),
+            query: config.filter.then(String::new),
+            checked: None,
+            last_click: None,
+            last_key: None,
+            stale: false,
+            coalesce: false,
+            reports_repeats: false,
+            pending_keys: PendingKeys::default(),
+            terminal_size: None,
+            hooks: None,
+            notified: None,
+            cancel: None,
+            deadline: None,
+            trail: Vec::new(),
+            predicate_on: false,
+            sort: config.sort.unwrap_or_default(),
+            live: None,
+            opened: Instant::now(),
+            keystrokes: 0,
+            cancelled_by: None,
+            at_edge: false,
+            order: Vec::new(),
+            show_help: false,
+            search: None,
+            search_open: false,
+            search_from: 0,
+            match_mode: config.match_mode,
+            case_sensitive: config.case_sensitive,
+            undo_until: None,
+            confirming: None,
+            confirming_stays: false,
+            renderer: renderer_for(&config),
+            root: options,
+            config,
+        };
+        menu.settle();
+        if menu.sort != SortMode::Unsorted {
+            // Start on the first row, unless the caller picked a default item to start on.
+            if menu.config.default_index == 0 {
+                menu.apply_filter();
+            } else {
+                menu.resort();
+            }
+        }
+        menu
+    }
+
+    /// Creates a multi-selection menu over `options` with nothing checked.
+    pub(crate) fn multi(options: &'a [T], config: MenuConfig) -> Self {
+        Self {
+            checked: Some(vec![false; options.len()]),
+            ..Self::new(options, config)
+        }
+    }
+
+    /// Gets the menu ready to [`run`](Menu::run) again after it was confirmed, e.g. because the
+    /// confirmed item's callback failed and the user wants to pick another. It opens on the same
+    /// item, in a fresh frame.
+    pub(crate) fn reopen(&mut self) {
+        self.renderer = renderer_for(&self.config);
+        self.cancelled_by = None;
+    }
+
+    /// Lends the menu closures to call while it runs.
+    pub(crate) fn with_hooks(self, hooks: &'a mut Hooks<T>) -> Self {
+        let mut menu = Self {
+            hooks: Some(hooks),
+            ..self
+        };
+        // The comparator was not known when the menu was created.
+        if menu.sort == SortMode::Custom {
+            menu.resort();
+        }
+        menu
+    }
+
+    /// Makes the menu close as if cancelled once `cancel` is triggered.
+    pub(crate) fn with_cancel(self, cancel: CancelHandle) -> Self {
+        Self {
+            cancel: Some(cancel),
+            ..self
+        }
+    }
+
+    /// Whether the owner asked the menu to close through its [`CancelHandle`].
+    fn cancel_requested(&self) -> bool {
+        self.cancel.as_ref().is_some_and(CancelHandle::is_cancelled)
+    }
+
+    /// Blocks until the next input event arrives, a cancellation is requested, the options change,
+    /// or the countdown needs attention. Without a [`CancelHandle`], an [`ItemsHandle`] or a
+    /// running countdown this simply waits for the next event.
+    fn next_event(&self) -> Result<Wake, TerMenuError> {
+        loop {
+            if self.cancel_requested() {
+                return Ok(Wake::Cancelled);
+            }
+            if let Some(live) = &self.live
+                && live.handle.has_changes()
+            {
+                return Ok(Wake::ItemsChanged);
+            }
+            let mut wait =
+                (self.cancel.is_some() || self.live.is_some()).then_some(cancel::POLL_INTERVAL);
+            let mut tick = None;
+            if let Some(live) = self.live.as_ref().filter(|_| self.spinning()) {
+                // Wake up when the spinner moves on to its next glyph.
+                let elapsed = live.since.elapsed().as_millis() % SPINNER_INTERVAL.as_millis();
+                let until_tick = SPINNER_INTERVAL - Duration::from_millis(elapsed as u64);
+                tick = Some(until_tick);
+                wait = Some(wait.map_or(until_tick, |wait| wait.min(until_tick)));
+            }
+            if let Some(until) = self.undo_until {
+                let left = until.saturating_duration_since(Instant::now());
+                if left.is_zero() {
+                    return Ok(Wake::UndoOver);
+                }
+                // Wake up when the whole number of seconds shown drops.
+                let seconds = countdown_seconds(left);
+                let until_tick = left - Duration::from_secs(seconds - 1);
+                if seconds > 1 {
+                    tick = Some(tick.map_or(until_tick, |tick| tick.min(until_tick)));
+                }
+                wait = Some(wait.map_or(until_tick, |wait| wait.min(until_tick)));
+            }
+            if let Some(deadline) = self.deadline {
+                let left = deadline.saturating_duration_since(Instant::now());
+                if left.is_zero() {
+                    return Ok(Wake::TimedOut);
+                }
+                // Wake up when the whole number of seconds shown in the footer drops, or when
+                // the last second is over.
+                let seconds = countdown_seconds(left);
+                let until_tick = left - Duration::from_secs(seconds - 1);
+                if seconds > 1 {
+                    tick = Some(tick.map_or(until_tick, |tick| tick.min(until_tick)));
+                }
+                wait = Some(wait.map_or(until_tick, |wait| wait.min(until_tick)));
+            }
+            let Some(wait) = wait else {
+                break;
+            };
+            let ready = match &self.config.events {
+                Some(events) => events.poll(wait),
+                None => event::poll(wait),
+            };
+            if ready.map_err(TerMenuError::EventRead)? {
+                break;
+            }
+            if tick == Some(wait) {
+                return Ok(Wake::Tick);
+            }
+        }
+        let event = match &self.config.events {
+            Some(events) => events.read(),
+            None => event::read(),
+        }
+        .map_err(TerMenuError::EventRead)?;
+        if let Some(session) = &self.config.session {
+            session.event(&event)?;
+        }
+        Ok(Wake::Input(event))
+    }
+
+    /// The outcome of a menu whose timeout ran out. A destructive item is never confirmed
+    /// without the user, and a submenu entry has no value to return, so the menu closes
+    /// instead.
+    fn timeout_outcome(&self) -> Option<usize> {
+        match self.config.timeout {
+            Some((_, TimeoutAction::SelectDefault)) => {
+                self.current().filter(|&idx| match &self.checked {
+                    Some(_) => self.within_limits(self.checked_indices().len()),
+                    None => {
+                        let item = &self.options()[idx];
+                        !item.is_disabled()
+                            && item.destructive().is_none()
+                            && item.children().is_empty()
+                    }
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// The countdown shown in the footer while the timeout is running, e.g.
+    /// "Auto-selecting 'prod' in 8s".
+    fn countdown(&self) -> Option<String> {
+        let left = self.deadline?.saturating_duration_since(Instant::now());
+        let seconds = countdown_seconds(left);
+        let strings = &self.config.strings;
+        let text = match (self.config.timeout?.1, self.timeout_outcome()) {
+            (TimeoutAction::SelectDefault, Some(_)) if self.checked.is_some() => {
+                fill(&strings.confirming_in, &[("seconds", &seconds)])
+            }
+            (TimeoutAction::SelectDefault, Some(idx)) => fill(
+                &strings.auto_selecting,
+                &[
+                    ("item", &self.label_of(&self.options()[idx])),
+                    ("seconds", &seconds),
+                ],
+            ),
+            _ => fill(&strings.closing_in, &[("seconds", &seconds)]),
+        };
+        Some(text)
+    }
+
+    /// The note shown during the undo window, e.g. "Selected prod — press U within 5s to undo".
+    fn undo_note(&self) -> Option<String> {
+        let left = self.undo_until?.saturating_duration_since(Instant::now());
+        let item = self.label_of(&self.options()[self.current()?]);
+        Some(fill(
+            &self.config.strings.undo,
+            &[("item", &item), ("seconds", &countdown_seconds(left))],
+        ))
+    }
+
+    /// The note shown while a destructive item waits to be confirmed a second time, e.g.
+    /// "Press Enter again to confirm Drop database, or ESC to go back".
+    fn confirming_note(&self) -> Option<String> {
+        let typed = self.confirming.as_deref()?;
+        let item = &self.options()[self.current()?];
+        let template = match item.destructive()? {
+            Confirmation::EnterAgain => &self.config.strings.confirm_again,
+            Confirmation::TypeLabel => &self.config.strings.confirm_by_label,
+        };
+        Some(fill(
+            template,
+            &[("item", &self.label_of(item)), ("typed", &typed)],
+        ))
+    }
+
+    /// Brings `order` up to date with `checked`: drops the items that were unchecked and appends
+    /// the newly checked ones, in display order if several were checked at once.
+    fn sync_order(&mut self) {
+        let Some(checked) = &self.checked else {
+            return;
+        };
+        self.order.retain(|&i| checked[i]);
+        let mut ranked = vec![false; checked.len()];
+        for &i in &self.order {
+            ranked[i] = true;
+        }
+        self.order
+            .extend((0..checked.len()).filter(|&i| checked[i] && !ranked[i]));
+    }
+
+    /// The checkbox drawn before item `i`: empty in single-selection mode, and the item's rank
+    /// instead of the checked glyph in an ordered list.
+    fn checkbox(&self, i: usize) -> String {
+        let theme = &self.config.theme;
+        let Some(checked) = &self.checked else {
+            return String::new();
+        };
+        if !self.config.ordered {
+            let glyph = if checked[i] {
+                &theme.checked
+            } else {
+                &theme.unchecked
+            };
+            return glyph.clone();
+        }
+        let text = match self.order.iter().position(|&j| j == i) {
+            Some(rank) => self.rank_text(rank + 1),
+            None => theme.unchecked.clone(),
+        };
+        let padding = self.checkbox_width().saturating_sub(text.width());
+        text + &" ".repeat(padding)
+    }
+
+    /// How `rank` is drawn in an ordered list: inside the unchecked glyph's brackets if it has
+    /// any, as in `[2]`, or on its own.
+    fn rank_text(&self, rank: usize) -> String {
+        let glyph = self.config.theme.unchecked.trim_end();
+        if glyph.contains(' ') {
+            format!("{} ", glyph.replacen(' ', &rank.to_string(), 1))
+        } else {
+            format!("{} ", rank)
+        }
+    }
+
+    /// Width of the checkboxes, or 0 in single-selection mode.
+    fn checkbox_width(&self) -> usize {
+        let theme = &self.config.theme;
+        match &self.checked {
+            Some(_) if self.config.ordered => {
+                let widest = self.rank_text(self.options().len().max(1)).width();
+                theme.unchecked.width().max(widest)
+            }
+            Some(_) => theme.checked.width().max(theme.unchecked.width()),
+            None => 0,
+        }
+    }
+
+    /// Returns `true` if checking `count` items satisfies the configured minimum and maximum.
+    fn within_limits(&self, count: usize) -> bool {
+        count >= self.config.min_selected && self.config.max_selected.is_none_or(|max| count <= max)
+    }
+
+    /// Returns `true` in multi-select mode once as many items are checked as allowed, so that
+    /// the unchecked ones can no longer be checked.
+    fn at_max_selected(&self) -> bool {
+        self.checked.is_some()
+            && self
+                .config
+                .max_selected
+                .is_some_and(|max| self.checked_indices().len() >= max)
+    }
+
+    /// The footer note on how many items may be checked, e.g. " | Select 1-3 (2 selected)", or
+    /// `None` without limits.
+    fn limits_note(&self) -> Option<String> {
+        let strings = &self.config.strings;
+        let (min, max) = (self.config.min_selected, self.config.max_selected);
+        let rule = match max {
+            None if min == 0 => return None,
+            None => &strings.select_at_least,
+            Some(_) if min == 0 => &strings.select_up_to,
+            Some(max) if min == max => &strings.select_exactly,
+            Some(_) => &strings.select_between,
+        };
+        let max = max.unwrap_or_default();
+        let rule = fill(rule, &[("min", &min), ("max", &max)]);
+        let note = if self.at_max_selected() {
+            &strings.limits_reached
+        } else {
+            &strings.limits
+        };
+        let selected = self.checked_indices().len();
+        Some(fill(note, &[("limits", &rule), ("selected", &selected)]))
+    }
+
+    /// Indices of the checked options, in display order, or in the order they were checked in
+    /// an ordered list. Empty in single-selection mode.
+    pub(crate) fn checked_indices(&self) -> Vec<usize> {
+        if self.config.ordered {
+            return self.order.clone();
+        }
+        self.checked
+            .iter()
+            .flatten()
+            .enumerate()
+            .filter(|(_, checked)| **checked)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Runs the interactive selection loop on the current thread.
+    ///
+    /// Enables raw mode, renders the menu, and processes key events until the user confirms or
+    /// cancels. Raw mode is disabled again before returning, even if rendering panics.
+    ///
+    /// # Returns
+    /// The index of the highlighted item when the user confirmed, or `None` if the interaction
+    /// was cancelled. In multi-select mode the checked items are available through
+    /// [`Menu::checked_indices`].
+    pub(crate) fn run(&mut self) -> Result<Option<usize>, TerMenuError> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!(
+            "menu",
+            options = self.options().len(),
+            multi = self.checked.is_some()
+        )
+        .entered();
+        #[cfg(feature = "tracing")]
+        tracing::debug!("menu opened");
+        if self.options().is_empty() && self.live.is_none() {
+            writeln!(self.config.output.clone(), "\nNo options available.")?;
+            self.cancelled_by = Some(CancelReason::NoOptions);
+            return Ok(None);
+        }
+        let headless = self.config.events.is_some();
+        if !headless && (!io::stdin().is_terminal() || !self.config.output.is_terminal()) {
+            let outcome = self.prompt_line()?;
+            return self.conclude(outcome);
+        }
+
+        let outcome = {
+            // Other event sources may claim input is waiting when it is not, as scripts do.
+            self.coalesce = !headless;
+            let session = if headless {
+                None
+            } else {
+                self.terminal_size = terminal::size().ok();
+                Some(TerminalSession::start(&self.config)?)
+            };
+            self.reports_repeats = session
+                .as_ref()
+                .is_none_or(TerminalSession::reports_repeats);
+            self.deadline = self
+                .config
+                .timeout
+                .map(|(timeout, _)| Instant::now() + timeout);
+            self.render()?;
+            self.notify_highlight();
+            loop {
+                // 处理事件读取错误
+                let event = match self.next_event()? {
+                    Wake::Input(event) => event,
+                    Wake::Tick => {
+                        self.render()?;
+                        continue;
+                    }
+                    Wake::Cancelled => {
+                        self.renderer.finish(&mut self.config.output)?;
+                        break None;
+                    }
+                    Wake::TimedOut => {
+                        self.renderer.finish(&mut self.config.output)?;
+                        let outcome = self.timeout_outcome();
+                        if outcome.is_none() {
+                            self.cancelled_by = Some(CancelReason::Timeout);
+                        }
+                        break outcome;
+                    }
+                    Wake::UndoOver => {
+                        self.undo_until = None;
+                        self.renderer.finish(&mut self.config.output)?;
+                        break self.current();
+                    }
+                    Wake::ItemsChanged => {
+                        self.apply_changes();
+                        self.render()?;
+                        self.notify_highlight();
+                        continue;
+                    }
+                };
+                if let ControlFlow::Break(outcome) = self.process(event)? {
+                    self.renderer.finish(&mut self.config.output)?;
+                    break outcome;
+                }
+            }
+        };
+        self.conclude(outcome)
+    }
+
+    /// Line-based fallback for when stdin or the output is not a terminal, e.g. under a pipe.
+    ///
+    /// Prints the items as a numbered list and reads the choice as a line of input, so scripts
+    /// can drive the menu with `echo 2 | my-tool`. In multi-select mode several numbers can be
+    /// given, separated by spaces or commas. Invalid input asks again; an empty line goes back
+    /// from a submenu, and cancels in the top-level menu or at the end of input. A destructive
+    /// item is asked about once more, as it would be with keys.
+    fn prompt_line(&mut self) -> Result<Option<usize>, TerMenuError> {
+        let mut stdout = self.config.output.clone();
+        'levels: loop {
+            writeln!(stdout, "{}", self.config.prompt)?;
+            if let Some(breadcrumb) = self.breadcrumb() {
+                writeln!(stdout, "{}", breadcrumb)?;
+            }
+            for (i, option) in self.options().iter().enumerate() {
+                let note = if option.is_disabled() {
+                    self.config.strings.unavailable.as_str()
+                } else if !option.children().is_empty() && self.checked.is_none() {
+                    " ›"
+                } else {
+                    ""
+                };
+                let badge = option.status().map(|status| status.badge());
+                writeln!(
+                    stdout,
+                    "  {}) {}{}{}",
+                    i + 1,
+                    badge.unwrap_or_default(),
+                    self.label_of(option),
+                    note
+                )?;
+            }
+            let total = self.options().len();
+            loop {
+                let question = if self.checked.is_some() {
+                    &self.config.strings.enter_choices
+                } else {
+                    &self.config.strings.enter_choice
+                };
+                write!(stdout, "{}", fill(question, &[("total", &total)]))?;
+                stdout.flush()?;
+
+                let Some(input) = self.read_answer()? else {
+                    return Ok(None);
+                };
+                let input = input.as_str();
+                if input.is_empty() {
+                    if self.back() {
+                        continue 'levels;
+                    }
+                    return Ok(None);
+                }
+                let choices: Option<Vec<usize>> = input
+                    .split([',', ' '])
+                    .filter(|part| !part.is_empty())
+                    .map(|part| {
+                        let n: usize = part.parse().ok()?;
+                        let idx = n.checked_sub(1).filter(|&idx| idx < total)?;
+                        (!self.options()[idx].is_disabled()).then_some(idx)
+                    })
+                    .collect();
+                let count = choices.as_ref().map(|choices| {
+                    let mut unique = choices.clone();
+                    unique.sort_unstable();
+                    unique.dedup();
+                    unique.len()
+                });
+                let allowed = count.is_some_and(|count| self.within_limits(count));
+                let limits = self.limits_note().unwrap_or_default();
+                let invalid = fill(&self.config.strings.invalid_choice, &[("input", &input)]);
+                match (choices, &mut self.checked) {
+                    (Some(choices), Some(checked)) if allowed => {
+                        for idx in &choices {
+                            checked[*idx] = true;
+                            if !self.order.contains(idx) {
+                                self.order.push(*idx);
+                            }
+                        }
+                        return Ok(choices.first().copied());
+                    }
+                    (Some(choices), None) if choices.len() == 1 => {
+                        if self.enter(choices[0]) {
+                            continue 'levels;
+                        }
+                        let item = &self.options()[choices[0]];
+                        if let Some(confirmation) = item.destructive() {
+                            let label = self.label_of(item);
+                            let question = match confirmation {
+                                Confirmation::EnterAgain => fill(
+                                    &self.config.strings.confirm_choice_again,
+                                    &[("item", &label), ("choice", &input)],
+                                ),
+                                Confirmation::TypeLabel => fill(
+                                    &self.config.strings.confirm_by_label,
+                                    &[("item", &label), ("typed", &"")],
+                                ),
+                            };
+                            write!(stdout, "{}", question)?;
+                            stdout.flush()?;
+                            let Some(answer) = self.read_answer()? else {
+                                return Ok(None);
+                            };
+                            let confirmed = match confirmation {
+                                Confirmation::EnterAgain => {
+                                    answer == input || answer.eq_ignore_ascii_case("y")
+                                }
+                                Confirmation::TypeLabel => answer == label,
+                            };
+                            if !confirmed {
+                                let invalid = &self.config.strings.invalid_choice;
+                                writeln!(stdout, "{}", fill(invalid, &[("input", &answer)]))?;
+                                continue;
+                            }
+                        }
+                        return Ok(Some(choices[0]));
+                    }
+                    (Some(_), Some(_)) => writeln!(stdout, "{}{}", invalid, limits)?,
+                    _ => writeln!(stdout, "{}", invalid)?,
+                }
+            }
+        }
+    }
+
+    /// Reads a line answering the line prompt, without surrounding whitespace.
+    ///
+    /// # Returns
+    /// `None` at the end of input or once the menu was cancelled.
+    fn read_answer(&self) -> Result<Option<String>, TerMenuError> {
+        let mut input = String::new();
+        let read = io::stdin()
+            .lock()
+            .read_line(&mut input)
+            .map_err(TerMenuError::EventRead)?;
+        if read == 0 || self.cancel_requested() {
+            return Ok(None);
+        }
+        Ok(Some(input.trim().to_string()))
+    }
+
+    /// Asynchronous counterpart of [`Menu::run`], reading input from crossterm's `EventStream`.
+    ///
+    /// The terminal is restored when the returned future completes or is dropped, so the
+    /// interaction can be raced against cancellation signals.
+    #[cfg(feature = "async")]
+    pub(crate) async fn run_async(&mut self) -> Result<Option<usize>, TerMenuError> {
+        use crossterm::event::EventStream;
+        use futures_core::Stream;
+        use std::pin::Pin;
+
+        // No span here: an entered span cannot be held across an await.
+        #[cfg(feature = "tracing")]
+        tracing::debug!(options = self.options().len(), "menu opened");
+
+        if self.options().is_empty() {
+            writeln!(self.config.output.clone(), "\nNo options available.")?;
+            self.cancelled_by = Some(CancelReason::NoOptions);
+            return Ok(None);
+        }
+        if !io::stdin().is_terminal() || !self.config.output.is_terminal() {
+            let outcome = self.prompt_line()?;
+            return self.conclude(outcome);
+        }
+
+        let outcome = {
+            let session = TerminalSession::start(&self.config)?;
+            self.reports_repeats = session.reports_repeats();
+            let mut events = EventStream::new();
+            self.terminal_size = terminal::size().ok();
+            self.render()?;
+            self.notify_highlight();
+            loop {
+                let Some(event) =
+                    std::future::poll_fn(|cx| Pin::new(&mut events).poll_next(cx)).await
+                else {
+                    break None;
+                };
+                let event = event.map_err(TerMenuError::EventRead)?;
+                if let Some(session) = &self.config.session {
+                    session.event(&event)?;
+                }
+                if let ControlFlow::Break(outcome) = self.process(event)? {
+                    self.renderer.finish(&mut self.config.output)?;
+                    break outcome;
+                }
+            }
+        };
+        self.conclude(outcome)
+    }
+
+    /// The text shown for `item`: what the `format` hook makes of it if there is one, otherwise
+    /// its label. Also what the filter and type-ahead match against.
+    fn label_of(&self, item: &T) -> String {
+        match self
+            .hooks
+            .as_deref()
+            .and_then(|hooks| hooks.format.as_ref())
+        {
+            Some(format) => format(item),
+            None => item.label(),
+        }
+    }
+
+    /// The filter text typed so far, in filter mode.
+    pub(crate) fn query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    /// Index into `options` of the highlighted row, if any row is visible.
+    pub(crate) fn current(&self) -> Option<usize> {
+        self.visible.get(self.cursor).copied()
+    }
+
+    /// The entries of the open submenu, or the top-level options outside submenus.
+    fn options(&self) -> &[T] {
+        level(&self.root, &self.trail)
+    }
+
+    /// The entry at `idx` of the open submenu, or of the top-level options outside submenus.
+    pub(crate) fn entry(&self, idx: usize) -> &T {
+        &self.options()[idx]
+    }
+
+    /// The item at the end of `path`, as returned by [`Menu::path_to`].
+    pub(crate) fn item_on(&self, path: &[usize]) -> &T {
+        follow(&self.root, path)
+            .pop()
+            .expect("a selection path is never empty")
+    }
+
+    /// The top-level options, including those added while the menu was open.
+    pub(crate) fn into_options(self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        match self.root {
+            Items::Borrowed(options) => options.to_vec(),
+            Items::Owned(options) => options,
+        }
+    }
+
+    /// How the interaction went so far, without the index of the confirmed item.
+    pub(crate) fn interaction(&self) -> Interaction {
+        Interaction {
+            index: None,
+            elapsed: self.opened.elapsed(),
+            keystrokes: self.keystrokes,
+            cancelled_by: self.cancelled_by,
+        }
+    }
+
+    /// Position of the top-level option at `idx` among the options the menu was created with, or
+    /// `None` for an option added through an [`ItemsHandle`].
+    pub(crate) fn origin(&self, idx: usize) -> Option<usize> {
+        match &self.live {
+            Some(live) => Some(live.ids[idx]).filter(|&id| id < live.initial),
+            None => Some(idx),
+        }
+    }
+
+    /// Applies the changes requested through the [`ItemsHandle`]. The highlight stays on the same
+    /// item, or moves to the next remaining one, and a submenu is closed when its entry goes.
+    fn apply_changes(&mut self) {
+        let (Some(live), Items::Owned(options)) = (&mut self.live, &mut self.root) else {
+            return;
+        };
+        // Indices shift as options come and go, so keep track of the ids instead.
+        let highlighted = match self.trail.first() {
+            Some(level) => Some(level.entered),
+            None => self.visible.get(self.cursor).copied(),
+        };
+        let highlighted = highlighted.map(|idx| live.ids[idx]);
+        let notified = self
+            .notified
+            .filter(|_| self.trail.is_empty())
+            .map(|idx| live.ids[idx]);
+        for change in live.handle.take_changes() {
+            match change {
+                Change::Push(item) => {
+                    options.push(item);
+                    live.ids.push(live.next_id);
+                    live.next_id += 1;
+                    if let Some(checked) = &mut self.checked {
+                        checked.push(false);
+                    }
+                }
+                Change::Retain(mut keep) => {
+                    let kept: Vec<bool> = options.iter().map(&mut keep).collect();
+                    retain_kept(options, &kept);
+                    retain_kept(&mut live.ids, &kept);
+                    if let Some(checked) = &mut self.checked {
+                        retain_kept(checked, &kept);
+                    }
+                }
+                Change::LoadStarted => live.loading += 1,
+                Change::LoadFinished => live.loading = live.loading.saturating_sub(1),
+            }
+        }
+        let highlighted = highlighted.map(|id| live.ids.binary_search(&id));
+        let notified = notified.and_then(|id| live.ids.binary_search(&id).ok());
+        match (self.trail.first_mut(), highlighted) {
+            (Some(level), Some(Ok(entered))) => {
+                level.entered = entered;
+                return;
+            }
+            (Some(_), _) => {
+                let top = self.trail.drain(..).next();
+                self.query = top.and_then(|level| level.query);
+            }
+            (None, _) => {}
+        }
+        self.notified = notified;
+        self.apply_filter();
+        if let Some(Ok(idx) | Err(idx)) = highlighted {
+            self.highlight_near(idx);
+        }
+    }
+
+    /// Whether a source passed to [`ItemsHandle::load`] is still delivering items.
+    fn loading(&self) -> bool {
+        self.live.as_ref().is_some_and(|live| live.loading > 0)
+    }
+
+    /// Whether the loading spinner is animated. Plain output shows it standing still, since every
+    /// redraw there prints the whole menu again.
+    fn spinning(&self) -> bool {
+        self.loading() && !self.config.plain && !self.config.theme.spinner.is_empty()
+    }
+
+    /// The row below the items while a source is still loading, e.g. "⠹ Loading…".
+    fn loading_line(&self) -> Option<Line> {
+        let live = self.live.as_ref().filter(|_| self.loading())?;
+        let glyphs: Vec<char> = self.config.theme.spinner.chars().collect();
+        let loading = &self.config.strings.loading;
+        let text = match glyphs.len() {
+            0 => format!("  {}", loading),
+            count => {
+                let step = live.since.elapsed().as_millis() / SPINNER_INTERVAL.as_millis();
+                let glyph = if self.spinning() {
+                    glyphs[(step % count as u128) as usize]
+                } else {
+                    glyphs[0]
+                };
+                format!("  {} {}", glyph, loading)
+            }
+        };
+        let mut dimmed = ContentStyle::new();
+        dimmed.attributes.set(Attribute::Dim);
+        Some(Line::styled(text, dimmed))
+    }
+
+    /// Indices of the entries whose submenus are open, outermost first.
+    pub(crate) fn submenu_path(&self) -> Vec<usize> {
+        self.trail.iter().map(|level| level.entered).collect()
+    }
+
+    /// Indices leading from the top-level options to the entry at `idx` of the open submenu.
+    pub(crate) fn path_to(&self, idx: usize) -> Vec<usize> {
+        let mut path = self.submenu_path();
+        path.push(idx);
+        path
+    }
+
+    /// Opens the submenu of the entry at `idx`, if it has one. Multi-select menus check entries
+    /// instead of opening them.
+    ///
+    /// # Returns
+    /// `true` if a submenu was opened.
+    fn enter(&mut self, idx: usize) -> bool {
+        if self.options()[idx].children().is_empty() || self.checked.is_some() {
+            return false;
+        }
+        self.trail.push(Level {
+            entered: idx,
+            query: self.query.as_mut().map(std::mem::take),
+        });
+        self.notified = None;
+        self.apply_filter();
+        true
+    }
+
+    /// Closes the open submenu and returns to its parent, as it was left.
+    ///
+    /// # Returns
+    /// `false` in the top-level menu.
+    fn back(&mut self) -> bool {
+        let Some(level) = self.trail.pop() else {
+            return false;
+        };
+        self.query = level.query;
+        self.notified = None;
+        self.apply_filter();
+        self.highlight_near(level.entered);
+        true
+    }
+
+    /// The labels of the submenus opened so far, e.g. "Settings > Network", or `None` in the
+    /// top-level menu.
+    fn breadcrumb(&self) -> Option<String> {
+        if self.trail.is_empty() {
+            return None;
+        }
+        let mut options: &[T] = &self.root;
+        let mut labels = Vec::new();
+        for level in &self.trail {
+            let entry = &options[level.entered];
+            labels.push(self.label_of(entry));
+            options = entry.children();
+        }
+        Some(labels.join(" > "))
+    }
+
+    /// The filter predicate hook, while it is switched on.
+    fn active_predicate(&self) -> Option<&PredicateHook<T>> {
+        let hooks = self.hooks.as_deref().filter(|_| self.predicate_on)?;
+        hooks.filter_predicate.as_ref()
+    }
+
+    /// Switches the filter predicate on or off, keeping the highlight on the same item if it is
+    /// still visible, or else on the next visible item in the caller's order.
+    ///
+    /// # Returns
+    /// `false` if the menu has no filter predicate.
+    fn toggle_predicate(&mut self) -> bool {
+        if self
+            .hooks
+            .as_deref()
+            .is_none_or(|hooks| hooks.filter_predicate.is_none())
+        {
+            return false;
+        }
+        let current = self.current();
+        self.predicate_on = !self.predicate_on;
+        self.apply_filter();
+        if let Some(idx) = current {
+            self.highlight_near(idx);
+        }
+        true
+    }
+
+    /// Switches to the next [`SortMode`], keeping the highlight on the same item.
+    ///
+    /// # Returns
+    /// `false` if the menu was not given a sort mode, so that the key can be used otherwise.
+    fn cycle_sort(&mut self) -> bool {
+        if self.config.sort.is_none() {
+            return false;
+        }
+        self.sort = self.sort.next(self.comparator().is_some());
+        self.resort();
+        true
+    }
+
+    /// The comparator for [`SortMode::Custom`], if the menu has one.
+    fn comparator(&self) -> Option<&CompareHook<T>> {
+        self.hooks.as_deref()?.compare.as_ref()
+    }
+
+    /// Lists the options in the current sort order, keeping the highlight on the same item.
+    fn resort(&mut self) {
+        let current = self.current();
+        self.apply_filter();
+        if let Some(idx) = current {
+            self.highlight_near(idx);
+        }
+    }
+
+    /// Moves the cursor to the option at `idx` if it is visible, or else to the next visible
+    /// option in the caller's order.
+    fn highlight_near(&mut self, idx: usize) {
+        let pos = self.visible.iter().position(|&i| i == idx).or_else(|| {
+            let next = self.visible.iter().position(|&i| i > idx);
+            next.or(self.visible.len().checked_sub(1))
+        });
+        self.cursor = pos.unwrap_or_default();
+        self.settle();
+    }
+
+    /// Recomputes the visible rows after the filter text or the filter predicate changed.
+    ///
+    /// Matches are ordered by score, then by their original position so that equally good matches
+    /// keep the caller's order. The cursor moves back to the best match.
+    fn apply_filter(&mut self) {
+        self.filter_among(None);
+    }
+
+    /// Like [`Menu::apply_filter`], but only considers the options at `candidates` if given.
+    /// Used when the filter text grew, since a longer text never matches an option the shorter
+    /// one did not.
+    fn filter_among(&mut self, candidates: Option<Vec<usize>>) {
+        let query = self.query.as_deref().unwrap_or_default();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(query, mode = ?self.match_mode, "filter changed");
+        let predicate = self.active_predicate();
+        let options = self.options();
+        let candidates = candidates.unwrap_or_else(|| (0..options.len()).collect());
+        let shown = candidates
+            .into_iter()
+            .filter(|&i| predicate.is_none_or(|predicate| predicate(&options[i])));
+        if query.is_empty() {
+            // Everything matches, in the caller's order or sorted; skip formatting every label
+            // unless the sort order needs it.
+            let mut visible: Vec<usize> = shown.collect();
+            sort::sort_rows(&mut visible, options, self.sort, self.comparator());
+            self.visible = visible;
+            self.highlights = Vec::new();
+            self.cursor = 0;
+            self.settle();
+            return;
+        }
+        let matcher = Matcher::new(self.match_mode, query, self.case_sensitive);
+        let mut matches: Vec<(usize, i64, Vec<usize>)> = shown
+            .filter_map(|i| {
+                matcher
+                    .find(&self.label_of(&options[i]))
+                    .map(|m| (i, m.score, m.positions))
+            })
+            .collect();
+        matches.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        self.visible = matches.iter().map(|(i, _, _)| *i).collect();
+        self.highlights = matches
+            .into_iter()
+            .map(|(_, _, positions)| positions)
+            .collect();
+        self.cursor = 0;
+        self.settle();
+    }
+
+    /// Whether the row at `pos` within `visible` can be highlighted.
+    fn selectable(&self, pos: usize) -> bool {
+        !self.options()[self.visible[pos]].is_disabled()
+    }
+
+    /// Indices into `options` of the rows shown that can be checked, in display order. Bulk
+    /// checks leave the items hidden by the filter alone.
+    fn shown_checkable(&self) -> Vec<usize> {
+        (0..self.visible.len())
+            .filter(|&pos| self.selectable(pos))
+            .map(|pos| self.visible[pos])
+            .collect()
+    }
+
+    /// The nearest selectable position at or after (`forward`) or at or before `from`, without
+    /// wrapping around.
+    fn seek(&self, from: usize, forward: bool) -> Option<usize> {
+        if forward {
+            (from..self.visible.len()).find(|&pos| self.selectable(pos))
+        } else {
+            let last = self.visible.len().checked_sub(1)?;
+            (0..=from.min(last)).rev().find(|&pos| self.selectable(pos))
+        }
+    }
+
+    /// Moves the cursor off a disabled row, preferring the nearest selectable row below it.
+    fn settle(&mut self) {
+        if let Some(pos) = self
+            .seek(self.cursor, true)
+            .or_else(|| self.seek(self.cursor, false))
+        {
+            self.cursor = pos;
+        }
+    }
+
+    fn move_up(&mut self) {
+        let previous = self
+            .cursor
+            .checked_sub(1)
+            .and_then(|pos| self.seek(pos, false));
+        let wrapped = || {
+            let last = self.visible.len().checked_sub(1)?;
+            self.seek(last, false)
+        };
+        match previous {
+            Some(pos) => self.cursor = pos,
+            None if self.config.wrap_navigation => {
+                if let Some(pos) = wrapped() {
+                    self.cursor = pos;
+                }
+            }
+            None => self.at_edge = true,
+        }
+    }
+
+    fn move_down(&mut self) {
+        match self.seek(self.cursor + 1, true) {
+            Some(pos) => self.cursor = pos,
+            None if self.config.wrap_navigation => {
+                if let Some(pos) = self.seek(0, true) {
+                    self.cursor = pos;
+                }
+            }
+            None => self.at_edge = true,
+        }
+    }
+
+    /// Moves the highlighted item one place up or down in a reorder list, taking the highlight
+    /// with it. Stops at the ends.
+    fn move_item(&mut self, up: bool) -> Step {
+        if !self.config.reorder {
+            return Step::Ignore;
+        }
+        let target = if up {
+            self.cursor.checked_sub(1)
+        } else {
+            Some(self.cursor + 1).filter(|&pos| pos < self.visible.len())
+        };
+        match target {
+            Some(target) => {
+                self.visible.swap(self.cursor, target);
+                self.cursor = target;
+            }
+            None => self.at_edge = true,
+        }
+        Step::Redraw
+    }
+
+    /// Indices of the options in the order they are listed, e.g. after the user moved them
+    /// around in a reorder list.
+    pub(crate) fn display_order(&self) -> &[usize] {
+        &self.visible
+    }
+
+    /// Moves the cursor by `delta` rows, stopping at the first and last row instead of wrapping.
+    /// Lands on the nearest selectable row in the direction of travel.
+    fn move_by(&mut self, delta: isize) {
+        let last = self.visible.len().saturating_sub(1);
+        let target = self.cursor.saturating_add_signed(delta).min(last);
+        let forward = delta > 0;
+        if let Some(pos) = self
+            .seek(target, forward)
+            .or_else(|| self.seek(target, !forward))
+        {
+            self.cursor = pos;
+        }
+    }
+
+    /// Feeds one terminal event into the menu and redraws it if anything changed.
+    ///
+    /// # Returns
+    /// `ControlFlow::Break` with the confirmed index (or `None` for a cancellation) once the
+    /// interaction is over, `ControlFlow::Continue` otherwise.
+    fn process(&mut self, event: Event) -> Result<ControlFlow<Option<usize>>, TerMenuError> {
+        let step = self.update(event);
+        if step == Step::Redraw {
+            self.stale = true;
+        }
+        match step {
+            Step::Ignore | Step::Redraw => {
+                // A burst of queued input, e.g. fast typing or a held key, is drawn once it has
+                // all been handled.
+                if self.stale && !self.input_pending()? {
+                    self.render()?;
+                    self.notify_highlight();
+                }
+            }
+            Step::Confirm => match self.config.undo_window {
+                // Hold on to the item for the undo window, unless it is already open and the
+                // user confirms once more.
+                Some(window) if self.undo_until.is_none() && self.checked.is_none() => {
+                    self.undo_until = Some(Instant::now() + window);
+                    self.render()?;
+                }
+                _ => return Ok(ControlFlow::Break(self.current())),
+            },
+            Step::Cancel => return Ok(ControlFlow::Break(None)),
+            Step::Interrupt => {
+                // Leave the screen tidy, but skip the cancel message and `on_cancel`: the caller
+                // is about to exit.
+                self.cancelled_by = Some(CancelReason::Interrupt);
+                self.renderer.finish(&mut self.config.output)?;
+                return Err(TerMenuError::Interrupted);
+            }
+        }
+        Ok(ControlFlow::Continue(()))
+    }
+
+    /// Whether another input event is already waiting, so that drawing can wait until it has
+    /// been handled. Always `false` unless the menu coalesces input.
+    fn input_pending(&self) -> Result<bool, TerMenuError> {
+        if !self.coalesce {
+            return Ok(false);
+        }
+        event::poll(Duration::ZERO).map_err(TerMenuError::EventRead)
+    }
+
+    /// Whether `key` is an auto-repeat of the key just handled that arrives sooner than the
+    /// repeat interval allows. Typed text is never limited. Where repeats cannot be told apart
+    /// from presses, navigation keys are taken for repeats, since holding those is what scrolls.
+    fn is_throttled(&self, key: KeyEvent) -> bool {
+        let typing = matches!(
+            key.code,
+            KeyCode::Char(_) | KeyCode::Backspace | KeyCode::Delete
+        ) && !key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+        let repeat = match key.kind {
+            KeyEventKind::Repeat => true,
+            _ => !self.reports_repeats && is_navigation(key.code),
+        };
+        repeat
+            && !typing
+            && !self.config.repeat_interval.is_zero()
+            && self.last_key.is_some_and(|(last, at)| {
+                last == (key.code, key.modifiers) && at.elapsed() < self.config.repeat_interval
+            })
+    }
+
+    /// Updates the state for one terminal event without drawing anything. A `Step::Confirm` is
+    /// only returned when the highlighted item may actually be confirmed.
+    pub(crate) fn update(&mut self, event: Event) -> Step {
+        // Somebody is at the keyboard, so the countdown no longer applies.
+        let countdown_stopped =
+            matches!(event, Event::Key(_) | Event::Mouse(_)) && self.deadline.take().is_some();
+        let mut step = match event {
+            // Only key interceptors hear of keys being let go: Windows consoles and the kitty
+            // protocol report those besides the press, which would otherwise move the highlight
+            // twice. Repeats of a held key act like presses.
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Release => {
+                self.intercept(key_event).unwrap_or(Step::Ignore)
+            }
+            Event::Key(key_event) if self.is_throttled(key_event) => Step::Ignore,
+            Event::Key(key_event) => {
+                self.keystrokes += 1;
+                if !self.config.repeat_interval.is_zero() {
+                    self.last_key = Some(((key_event.code, key_event.modifiers), Instant::now()));
+                }
+                match self.intercept(key_event) {
+                    Some(step) => step,
+                    None => self.handle_key(key_event),
+                }
+            }
+            Event::Mouse(mouse_event)
+                if self.config.mouse
+                    && !self.config.plain
+                    && self.undo_until.is_none()
+                    && self.confirming.is_none() =>
+            {
+                self.handle_mouse(mouse_event)
+            }
+            Event::Resize(columns, rows) => {
+                self.terminal_size = Some((columns, rows));
+                if let Renderer::FullScreen(screen) = &mut self.renderer {
+                    screen.invalidate();
+                }
+                Step::Redraw
+            }
+            _ => Step::Ignore, // 忽略其他事件
+        };
+        if countdown_stopped && step == Step::Ignore {
+            step = Step::Redraw;
+        }
+        if step == Step::Confirm
+            && !self
+                .current()
+                .is_some_and(|idx| self.checked.is_some() || !self.options()[idx].is_disabled())
+        {
+            step = Step::Ignore;
+        }
+        if step == Step::Confirm && self.current().is_some_and(|idx| self.enter(idx)) {
+            step = Step::Redraw;
+        }
+        // A destructive item takes a second confirmation, which ends waiting for it.
+        if step == Step::Confirm
+            && self.checked.is_none()
+            && self
+                .current()
+                .is_some_and(|idx| self.options()[idx].destructive().is_some())
+            && self.confirming.take().is_none()
+        {
+            self.confirming = Some(String::new());
+            self.confirming_stays = false;
+            step = Step::Redraw;
+        }
+        #[cfg(feature = "tracing")]
+        if let Event::Key(key) = event {
+            tracing::trace!(code = ?key.code, modifiers = ?key.modifiers, ?step, "key handled");
+        }
+        step
+    }
+
+    /// Calls the `on_highlight` hook if the highlighted item changed since it was last called.
+    fn notify_highlight(&mut self) {
+        let current = self.current();
+        if current == self.notified {
+            return;
+        }
+        self.notified = current;
+        if let (Some(idx), Some(hooks)) = (current, self.hooks.as_deref_mut())
+            && let Some(on_highlight) = &mut hooks.on_highlight
+        {
+            on_highlight(&level(&self.root, &self.trail)[idx]);
+        }
+    }
+
+    /// Wraps up a finished interaction: prints the confirm or cancel message and, if the user
+    /// cancelled, calls the `on_cancel` hook.
+    fn conclude(&mut self, outcome: Option<usize>) -> Result<Option<usize>, TerMenuError> {
+        if outcome.is_none() && self.cancelled_by.is_none() {
+            self.cancelled_by = Some(if self.cancel_requested() {
+                CancelReason::Handle
+            } else {
+                CancelReason::Escape
+            });
+        }
+        #[cfg(feature = "tracing")]
+        match outcome {
+            Some(_) if self.checked.is_some() => {
+                tracing::info!(checked = ?self.checked_indices(), "selection made")
+            }
+            Some(idx) => {
+                tracing::info!(idx, item = %self.label_of(&self.options()[idx]), "selection made")
+            }
+            None => tracing::info!(reason = ?self.cancelled_by, "menu cancelled"),
+        }
+        self.announce(outcome)?;
+        if let (Some(idx), None) = (outcome, &self.checked)
+            && let Some(on_confirm) = self
+                .hooks
+                .as_deref_mut()
+                .and_then(|hooks| hooks.on_confirm.as_mut())
+        {
+            let top = self.trail.first().map_or(idx, |level| level.entered);
+            on_confirm(&self.root[top]);
+        }
+        if outcome.is_none()
+            && let Some(on_cancel) = self
+                .hooks
+                .as_deref_mut()
+                .and_then(|hooks| hooks.on_cancel.as_mut())
+        {
+            on_cancel();
+        }
+        Ok(outcome)
+    }
+
+    /// Prints the confirm or cancel message for the finished interaction.
+    ///
+    /// Called once the terminal session has ended, so that the message lands on the restored
+    /// screen rather than on the alternate screen that is about to disappear.
+    fn announce(&self, outcome: Option<usize>) -> io::Result<()> {
+        let message = match outcome {
+            Some(current) => {
+                let item = if self.checked.is_some() {
+                    let selected: Vec<String> = self
+                        .checked_indices()
+                        .into_iter()
+                        .map(|i| self.label_of(&self.options()[i]))
+                        .collect();
+                    selected.join(", ")
+                } else {
+                    self.label_of(&self.options()[current])
+                };
+                self.config.confirm_message.replace("{item}", &item)
+            }
+            None => self.config.cancel_message.clone(),
+        };
+        if message.is_empty() {
+            return Ok(());
+        }
+        self.print_message(message)
+    }
+
+    /// Prints the status line of a [`finish_message`](crate::DropDownBuilder::finish_message)
+    /// hook in place of the confirm message, styled unless the menu is drawn as plain text.
+    pub(crate) fn print_status(&self, status: StyledContent<String>) -> io::Result<()> {
+        match self.renderer {
+            Renderer::Plain | Renderer::Record(_) => self.print_message(status.content()),
+            _ => self.print_message(status),
+        }
+    }
+
+    /// Prints `message` below the finished menu.
+    fn print_message(&self, message: impl Display) -> io::Result<()> {
+        let mut stdout = self.config.output.clone();
+        // An inline menu or popup collapses into the message; the others keep a gap below it.
+        if !matches!(self.renderer, Renderer::Inline(_) | Renderer::Popup(_)) {
+            writeln!(stdout)?;
+        }
+        writeln!(stdout, "{}", message)?;
+        stdout.flush()
+    }
+
+    /// Offers a key press to the key interceptors, in the order they were added, and returns the
+    /// step the first one to handle it asks for, or `None` if none did.
+    fn intercept(&mut self, event: KeyEvent) -> Option<Step> {
+        let mut interceptors = match self.hooks.as_deref_mut() {
+            Some(hooks) if !hooks.key_interceptors.is_empty() => {
+                std::mem::take(&mut hooks.key_interceptors)
+            }
+            _ => return None,
+        };
+        // Keys fed back in are neither dropped as repeats of this one nor counted as pressed.
+        let (keystrokes, last_key) = (self.keystrokes, self.last_key.take());
+        // Interceptors see the menu as a `MenuState`, which owns it, so an empty menu stands in
+        // while they run. Keys they feed back in meet no interceptors, as those are taken out.
+        let stand_in = Menu::new(&[], MenuConfig::new(0));
+        let mut state = MenuState::from_menu(std::mem::replace(self, stand_in));
+        let handled = interceptors.iter_mut().find_map(|interceptor| {
+            match interceptor.on_key(event, &mut state) {
+                Handled::Yes(outcome) => Some(outcome),
+                Handled::No => None,
+            }
+        });
+        *self = state.into_menu();
+        (self.keystrokes, self.last_key) = (keystrokes, last_key);
+        if let Some(hooks) = self.hooks.as_deref_mut() {
+            hooks.key_interceptors = interceptors;
+        }
+        handled.map(|outcome| match outcome {
+            KeyOutcome::Unchanged => Step::Ignore,
+            KeyOutcome::Changed => Step::Redraw,
+            KeyOutcome::Selected(_) => Step::Confirm,
+            KeyOutcome::Cancelled => Step::Cancel,
+            KeyOutcome::Interrupted => Step::Interrupt,
+        })
+    }
+
+    /// Handles a key press: filter editing takes precedence, then the configured key map, then
+    /// numbered quick-select and type-ahead.
+    fn handle_key(&mut self, event: KeyEvent) -> Step {
+        // Any key closes the help screen.
+        if std::mem::take(&mut self.show_help) {
+            return Step::Redraw;
+        }
+        if self.undo_until.is_some() {
+            return self.handle_undo_key(&event);
+        }
+        if self.confirming.is_some() {
+            return self.handle_confirming_key(&event);
+        }
+        if let Some(step) = self.edit_search(&event) {
+            return step;
+        }
+        if let Some(step) = self.edit_query(&event) {
+            return step;
+        }
+        if let KeyCode::Char(c) = event.code
+            && !event
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+            && let Some(pos) = self.hotkey_item(c)
+        {
+            return self.pick(pos);
+        }
+        if let Some(action) = self.config.keymap.resolve(
+            &mut self.pending_keys,
+            &event,
+            self.checked.is_some(),
+            Instant::now(),
+        ) {
+            let step = self.apply(action);
+            // Keys bound to actions that do nothing in this mode (`a`/`i`/`n` outside multi-select)
+            // stay available for type-ahead.
+            if step != Step::Ignore {
+                return step;
+            }
+        } else if !self.pending_keys.is_empty() {
+            // Part of a longer key sequence.
+            return Step::Ignore;
+        }
+        match event.code {
+            KeyCode::Char(c @ '1'..='9') if self.config.numbered && event.modifiers.is_empty() => {
+                self.quick_select(c as usize - '1' as usize)
+            }
+            KeyCode::Char(c)
+                if !c.is_whitespace()
+                    && !event
+                        .modifiers
+                        .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                self.type_ahead(c)
+            }
+            _ => Step::Ignore,
+        }
+    }
+
+    /// Handles a key press during the undo window: Undo or Cancel takes the confirmed item back,
+    /// Confirm closes the menu without waiting for the window to close, and other keys do
+    /// nothing.
+    fn handle_undo_key(&mut self, event: &KeyEvent) -> Step {
+        match self.config.keymap.resolve(
+            &mut self.pending_keys,
+            event,
+            self.checked.is_some(),
+            Instant::now(),
+        ) {
+            Some(Action::Undo | Action::Cancel) => {
+                self.undo_until = None;
+                Step::Redraw
+            }
+            Some(Action::Confirm) => Step::Confirm,
+            Some(Action::Interrupt) => Step::Interrupt,
+            _ => Step::Ignore,
+        }
+    }
+
+    /// Handles a key press while a destructive item waits to be confirmed a second time; see
+    /// [`Confirmation`]. Escape goes back to the menu.
+    fn handle_confirming_key(&mut self, event: &KeyEvent) -> Step {
+        let Some(item) = self.current().map(|idx| &self.options()[idx]) else {
+            self.confirming = None;
+            return Step::Redraw;
+        };
+        let by_label = item.destructive() == Some(Confirmation::TypeLabel);
+        let label = self.label_of(item);
+        let typed = self.confirming.get_or_insert_default();
+        match event.code {
+            KeyCode::Char(c)
+                if by_label
+                    && !event
+                        .modifiers
+                        .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                typed.push(c);
+                return Step::Redraw;
+            }
+            KeyCode::Backspace if by_label => {
+                typed.pop();
+                return Step::Redraw;
+            }
+            _ => {}
+        }
+        match self.config.keymap.resolve(
+            &mut self.pending_keys,
+            event,
+            self.checked.is_some(),
+            Instant::now(),
+        ) {
+            Some(Action::Confirm | Action::ConfirmAndStay) if by_label && *typed != label => {
+                Step::Ignore
+            }
+            Some(Action::Confirm | Action::ConfirmAndStay) if self.confirming_stays => {
+                self.confirming = None;
+                if let Some(idx) = self.current() {
+                    self.confirm_and_stay(idx);
+                }
+                Step::Redraw
+            }
+            Some(Action::Confirm | Action::ConfirmAndStay) => Step::Confirm,
+            Some(Action::Interrupt) => Step::Interrupt,
+            // Keys that are not part of the label do nothing while it is typed.
+            Some(action) if by_label && action != Action::Cancel => Step::Ignore,
+            None if by_label => Step::Ignore,
+            _ => {
+                self.confirming = None;
+                Step::Redraw
+            }
+        }
+    }
+
+    /// Hands the item at `idx` to the `on_confirm_and_stay` hook, if there is one.
+    fn confirm_and_stay(&mut self, idx: usize) {
+        if let Some(on_confirm) = self
+            .hooks
+            .as_deref_mut()
+            .and_then(|hooks| hooks.on_confirm_and_stay.as_mut())
+        {
+            on_confirm(&level(&self.root, &self.trail)[idx]);
+        }
+    }
+
+    /// The position of the selectable item whose hotkey is `c`, ignoring case.
+    fn hotkey_item(&self, c: char) -> Option<usize> {
+        (0..self.visible.len()).find(|&pos| {
+            self.selectable(pos)
+                && self.options()[self.visible[pos]]
+                    .hotkey()
+                    .is_some_and(|key| same_key(key, c))
+        })
+    }
+
+    /// Moves the highlight to the next row whose label starts with `c`, ignoring case. Starts
+    /// searching below the highlighted row, so repeated presses cycle through all such rows.
+    fn type_ahead(&mut self, c: char) -> Step {
+        let total = self.visible.len();
+        let found = (1..=total)
+            .map(|step| (self.cursor + step) % total)
+            .find(|&pos| {
+                self.selectable(pos)
+                    && self
+                        .label_of(&self.options()[self.visible[pos]])
+                        .chars()
+                        .next()
+                        .is_some_and(|first| first.to_lowercase().eq(c.to_lowercase()))
+            });
+        match found {
+            Some(pos) => {
+                self.cursor = pos;
+                Step::Redraw
+            }
+            None => Step::Ignore,
+        }
+    }
+
+    /// Picks the row numbered `offset + 1` on the current page: confirms it in single-selection
+    /// mode, toggles it in multi-select mode.
+    fn quick_select(&mut self, offset: usize) -> Step {
+        let (start_idx, end_idx) = self.window();
+        let pos = start_idx + offset;
+        if pos >= end_idx || !self.selectable(pos) {
+            return Step::Ignore;
+        }
+        self.pick(pos)
+    }
+
+    /// Highlights the item at `pos` and confirms it in single-selection mode, or toggles it in
+    /// multi-select mode.
+    fn pick(&mut self, pos: usize) -> Step {
+        self.cursor = pos;
+        if self.checked.is_some() {
+            self.apply(Action::Toggle)
+        } else {
+            Step::Confirm
+        }
+    }
+
+    /// Types into the open search line, jumping to the first match at or below where the search
+    /// started on every change. Enter closes the line, Escape (or Backspace on an empty line)
+    /// drops the search and returns to where it started.
+    ///
+    /// # Returns
+    /// `None` if the search line is closed or the key is not a search edit, so that it falls
+    /// through to filter editing and the key map.
+    fn edit_search(&mut self, event: &KeyEvent) -> Option<Step> {
+        if !self.search_open {
+            return None;
+        }
+        let search = self.search.get_or_insert_with(String::new);
+        match event.code {
+            KeyCode::Char(c)
+                if !event
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                search.push(c);
+            }
+            KeyCode::Backspace if !search.is_empty() => {
+                search.pop();
+            }
+            KeyCode::Enter => {
+                self.search_open = false;
+                self.search.take_if(|search| search.is_empty());
+                return Some(Step::Redraw);
+            }
+            KeyCode::Esc | KeyCode::Backspace => {
+                self.search_open = false;
+                self.search = None;
+                self.cursor = self.search_from;
+                return Some(Step::Redraw);
+            }
+            _ => return None,
+        }
+        self.cursor = self
+            .find_match(self.search_from, true, true)
+            .unwrap_or(self.search_from);
+        Some(Step::Redraw)
+    }
+
+    /// The position of the next selectable item after `from` (or from `from` itself if
+    /// `inclusive`) whose label contains the search text, going down if `forward` and up
+    /// otherwise, and wrapping around at the ends.
+    fn find_match(&self, from: usize, forward: bool, inclusive: bool) -> Option<usize> {
+        let search = self.search.as_deref()?;
+        let total = self.visible.len();
+        let from = from.min(total.checked_sub(1)?);
+        let steps = if inclusive { 0..total } else { 1..total + 1 };
+        steps
+            .map(|step| {
+                if forward {
+                    (from + step) % total
+                } else {
+                    (from + total * 2 - step) % total
+                }
+            })
+            .find(|&pos| {
+                self.selectable(pos)
+                    && substring_match(
+                        search,
+                        &self.label_of(&self.options()[self.visible[pos]]),
+                        false,
+                    )
+                    .is_some()
+            })
+    }
+
+    /// Types plain characters into the filter line and deletes them with Backspace.
+    ///
+    /// # Returns
+    /// `None` if the menu has no filter or the key is not a filter edit, so that it falls through
+    /// to the key map. Space stays a key-map key in multi-select mode, where it toggles items.
+    fn edit_query(&mut self, event: &KeyEvent) -> Option<Step> {
+        let multi = self.checked.is_some();
+        let query = self.query.as_mut()?;
+        match event.code {
+            KeyCode::Char(' ') if multi => return None,
+            KeyCode::Char(c)
+                if !event
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                query.push(c);
+                let candidates = self
+                    .match_mode
+                    .narrows()
+                    .then(|| std::mem::take(&mut self.visible));
+                self.filter_among(candidates);
+                return Some(Step::Redraw);
+            }
+            // With nothing left to delete, Backspace leaves a submenu.
+            KeyCode::Backspace if !query.is_empty() => {
+                query.pop();
+            }
+            _ => return None,
+        }
+        self.apply_filter();
+        Some(Step::Redraw)
+    }
+
+    /// Performs `action` on the menu state.
+    fn apply(&mut self, action: Action) -> Step {
+        match action {
+            Action::Up if self.grid_columns() > 1 => {
+                self.move_by(-(self.grid_columns() as isize));
+                Step::Redraw
+            }
+            Action::Down if self.grid_columns() > 1 => {
+                self.move_by(self.grid_columns() as isize);
+                Step::Redraw
+            }
+            Action::Up => {
+                self.move_up();
+                Step::Redraw
+            }
+            Action::Down => {
+                self.move_down();
+                Step::Redraw
+            }
+            Action::Left if self.config.horizontal => {
+                self.move_up();
+                Step::Redraw
+            }
+            Action::Right if self.config.horizontal => {
+                self.move_down();
+                Step::Redraw
+            }
+            Action::Left if !self.cursor.is_multiple_of(self.grid_columns()) => {
+                self.move_by(-1);
+                Step::Redraw
+            }
+            Action::Left => self.apply(Action::Back),
+            Action::Right
+                if self.cursor % self.grid_columns() + 1 < self.grid_columns()
+                    && self.cursor + 1 < self.visible.len() =>
+            {
+                self.move_by(1);
+                Step::Redraw
+            }
+            Action::Right => Step::Ignore,
+            Action::PageUp | Action::PageDown => {
+                let page = (self.page_size() * self.grid_columns()) as isize;
+                self.move_by(if action == Action::PageUp {
+                    -page
+                } else {
+                    page
+                });
+                Step::Redraw
+            }
+            Action::HalfPageUp | Action::HalfPageDown => {
+                let half = ((self.page_size() / 2).max(1) * self.grid_columns()) as isize;
+                self.move_by(if action == Action::HalfPageUp {
+                    -half
+                } else {
+                    half
+                });
+                Step::Redraw
+            }
+            Action::First => {
+                self.move_by(isize::MIN);
+                Step::Redraw
+            }
+            Action::Last => {
+                self.move_by(isize::MAX);
+                Step::Redraw
+            }
+            Action::Toggle => {
+                let Some(checked) = &mut self.checked else {
+                    return Step::Ignore;
+                };
+                // Multi-select menus never open submenus.
+                // Past the maximum, unchecked items stay unchecked.
+                let full = self
+                    .config
+                    .max_selected
+                    .is_some_and(|max| checked.iter().filter(|&&flag| flag).count() >= max);
+                if let Some(&idx) = self.visible.get(self.cursor)
+                    && !self.root[idx].is_disabled()
+                    && (checked[idx] || !full)
+                {
+                    checked[idx] = !checked[idx];
+                }
+                self.sync_order();
+                Step::Redraw
+            }
+            Action::SelectAll | Action::SelectNone => {
+                let shown = self.shown_checkable();
+                let Some(checked) = &mut self.checked else {
+                    return Step::Ignore;
+                };
+                for &i in &shown {
+                    checked[i] = false;
+                }
+                if action == Action::SelectAll {
+                    let kept = checked.iter().filter(|&&flag| flag).count();
+                    let room = self
+                        .config
+                        .max_selected
+                        .map_or(usize::MAX, |max| max - kept.min(max));
+                    for &i in shown.iter().take(room) {
+                        checked[i] = true;
+                    }
+                }
+                self.sync_order();
+                Step::Redraw
+            }
+            Action::ToggleAll => {
+                let shown = self.shown_checkable();
+                let Some(checked) = &self.checked else {
+                    return Step::Ignore;
+                };
+                if shown.iter().all(|&i| checked[i]) {
+                    self.apply(Action::SelectNone)
+                } else {
+                    self.apply(Action::SelectAll)
+                }
+            }
+            Action::Invert => {
+                let shown = self.shown_checkable();
+                let Some(checked) = &mut self.checked else {
+                    return Step::Ignore;
+                };
+                let mut inverted = checked.clone();
+                for &i in &shown {
+                    inverted[i] = !inverted[i];
+                }
+                let count = inverted.iter().filter(|&&flag| flag).count();
+                // An inversion that would check more than allowed is refused as a whole.
+                if self.config.max_selected.is_some_and(|max| count > max) {
+                    return Step::Ignore;
+                }
+                *checked = inverted;
+                self.sync_order();
+                Step::Redraw
+            }
+            Action::Help => {
+                self.show_help = true;
+                Step::Redraw
+            }
+            Action::Search => {
+                self.search = Some(String::new());
+                self.search_open = true;
+                self.search_from = self.cursor;
+                Step::Redraw
+            }
+            Action::NextMatch | Action::PrevMatch => {
+                match self.find_match(self.cursor, action == Action::NextMatch, false) {
+                    Some(pos) => {
+                        self.cursor = pos;
+                        Step::Redraw
+                    }
+                    None => Step::Ignore,
+                }
+            }
+            Action::MoveItemUp => self.move_item(true),
+            Action::MoveItemDown => self.move_item(false),
+            Action::Confirm
+                if self.checked.is_some() && !self.within_limits(self.checked_indices().len()) =>
+            {
+                Step::Ignore
+            }
+            Action::Confirm => Step::Confirm,
+            Action::ConfirmAndStay => {
+                if !self.action_applies(Action::ConfirmAndStay) {
+                    return Step::Ignore;
+                }
+                // Submenu parents have no value of their own to hand over.
+                let Some(idx) = self.current().filter(|&idx| {
+                    let item = &self.options()[idx];
+                    !item.is_disabled() && item.children().is_empty()
+                }) else {
+                    return Step::Ignore;
+                };
+                if self.options()[idx].destructive().is_some() {
+                    self.confirming = Some(String::new());
+                    self.confirming_stays = true;
+                } else {
+                    self.confirm_and_stay(idx);
+                }
+                Step::Redraw
+            }
+            Action::Cancel if self.query.as_ref().is_some_and(|q| !q.is_empty()) => {
+                self.query = Some(String::new());
+                self.apply_filter();
+                Step::Redraw
+            }
+            Action::Cancel | Action::Back if self.back() => Step::Redraw,
+            Action::Cancel => Step::Cancel,
+            Action::Back => Step::Ignore,
+            Action::Interrupt => Step::Interrupt,
+            Action::TogglePredicate if self.toggle_predicate() => Step::Redraw,
+            Action::TogglePredicate => Step::Ignore,
+            Action::CycleMatchMode | Action::ToggleCase if self.query.is_some() => {
+                if action == Action::CycleMatchMode {
+                    self.match_mode = self.match_mode.next();
+                } else {
+                    self.case_sensitive = !self.case_sensitive;
+                }
+                self.apply_filter();
+                Step::Redraw
+            }
+            Action::CycleMatchMode | Action::ToggleCase => Step::Ignore,
+            Action::CycleSort if self.cycle_sort() => Step::Redraw,
+            Action::CycleSort => Step::Ignore,
+            // Handled by `handle_undo_key` while the undo window is open.
+            Action::Undo => Step::Ignore,
+        }
+    }
+
+    /// Scrolls with the wheel and highlights (or selects) the row under a left click.
+    fn handle_mouse(&mut self, event: MouseEvent) -> Step {
+        match event.kind {
+            MouseEventKind::ScrollUp => self.apply(Action::Up),
+            MouseEventKind::ScrollDown => self.apply(Action::Down),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let Some(pos) = self
+                    .item_at(event.row, event.column)
+                    .filter(|&pos| self.selectable(pos))
+                else {
+                    return Step::Ignore;
+                };
+                let double_click = self
+                    .last_click
+                    .is_some_and(|(last, at)| last == pos && at.elapsed() <= DOUBLE_CLICK_INTERVAL);
+                self.cursor = pos;
+                if self.config.click_to_select || double_click {
+                    Step::Confirm
+                } else {
+                    self.last_click = Some((pos, Instant::now()));
+                    Step::Redraw
+                }
+            }
+            _ => Step::Ignore,
+        }
+    }
+
+    /// Maps a terminal cell to a position within `visible`, if an item is rendered there.
+    fn item_at(&self, row: u16, column: u16) -> Option<usize> {
+        let columns = self.grid_columns();
+        if self.config.horizontal {
+            return None;
+        }
+        if columns == 1 {
+            return self.row_at(row);
+        }
+        let first_row = usize::from(self.renderer.origin_row()) + self.first_item_row();
+        let offset = usize::from(row).checked_sub(first_row)?;
+        let stride = self.grid_cell_width() + GRID_GAP.len();
+        // The left edge of a box and the space after it.
+        let inset = usize::from(self.renderer.origin_column())
+            + if self.config.border.is_some() { 2 } else { 0 };
+        let column = usize::from(column).checked_sub(inset)? / stride;
+        let pos = self.window().0 + offset * columns + column;
+        (column < columns && pos < self.window().1).then_some(pos)
+    }
+
+    /// Maps a terminal row to a position within `visible`, if an item is rendered there.
+    fn row_at(&self, row: u16) -> Option<usize> {
+        let first_row = usize::from(self.renderer.origin_row()) + self.first_item_row();
+        let offset = usize::from(row).checked_sub(first_row)?;
+        match self.page_rows().get(offset)? {
+            Row::Item(pos) | Row::Wrapped(pos) | Row::Description(pos) => Some(*pos),
+            Row::Divider(_) => None,
+        }
+    }
+
+    /// Number of items per page: as many as fit below the header and above the footer, limited
+    /// to the configured page size if there is one. Always at least one.
+    fn page_size(&self) -> usize {
+        if self.config.horizontal {
+            return self.config.page_size.unwrap_or(FALLBACK_PAGE_SIZE).max(1);
+        }
+        let fits = self
+            .fitting_rows()
+            // Every item may take a second line for its description.
+            .map(|fits| match self.config.description_layout {
+                DescriptionLayout::Below => fits / 2,
+                _ => fits,
+            });
+        let page_size = match (self.config.page_size, fits) {
+            (Some(page_size), Some(fits)) => page_size.min(fits),
+            (Some(page_size), None) => page_size,
+            (None, Some(fits)) => fits,
+            (None, None) => FALLBACK_PAGE_SIZE,
+        };
+        page_size.max(1)
+    }
+
+    /// Columns and rows the menu may draw in: the whole terminal, or the part right of and below
+    /// the corner of a popup. `None` until the terminal size is known.
+    fn area(&self) -> Option<(u16, u16)> {
+        let (columns, rows) = self.terminal_size?;
+        let (row, column) = self.config.popup.unwrap_or_default();
+        Some((columns.saturating_sub(column), rows.saturating_sub(row)))
+    }
+
+    /// Number of terminal rows left for the items below the header and above the footer, once
+    /// the terminal size is known.
+    fn fitting_rows(&self) -> Option<usize> {
+        // Header rows, the loading row, the blank line and help line below the items, the final
+        // line break, a preview pane below the items, the bottom edge of a box, and the
+        // description under the highlighted item.
+        let chrome = self.first_item_row()
+            + usize::from(self.config.border.is_some())
+            + usize::from(self.config.description_layout == DescriptionLayout::BelowHighlighted)
+            + usize::from(self.loading())
+            + 2
+            + usize::from(self.config.show_footer || self.search_open)
+            + self.preview_height();
+        self.area()
+            .map(|(_, rows)| rows)
+            .filter(|&rows| rows > 0)
+            .map(|rows| usize::from(rows).saturating_sub(chrome))
+    }
+
+    /// Number of columns the lines of the menu may take up, inside the box if there is one.
+    fn content_width(&self) -> usize {
+        let columns = self
+            .area()
+            .map(|(columns, _)| usize::from(columns))
+            .filter(|&columns| columns > 0)
+            .unwrap_or(FALLBACK_COLUMNS);
+        if self.config.border.is_some() {
+            columns.saturating_sub(4)
+        } else {
+            columns
+        }
+    }
+
+    /// The preview pane's text for the highlighted item, if a preview was requested.
+    fn preview_text(&self) -> Option<String> {
+        let preview = self.hooks.as_deref()?.preview.as_ref()?;
+        Some(preview(&self.options()[self.current()?]))
+    }
+
+    /// Number of lines a preview pane takes up below the items, including its rule.
+    fn preview_height(&self) -> usize {
+        let has_preview = self
+            .hooks
+            .as_deref()
+            .is_some_and(|hooks| hooks.preview.is_some());
+        match self.config.preview_layout {
+            PreviewLayout::Bottom(height) if has_preview => height + 1,
+            _ => 0,
+        }
+    }
+
+    /// The range of positions within `visible` that fit on the current page.
+    fn window(&self) -> (usize, usize) {
+        let columns = self.grid_columns();
+        let total = self.visible.len();
+        let total_rows = total.div_ceil(columns);
+        let max_rows = self.page_size();
+        let start_row = if total_rows <= max_rows {
+            0
+        } else {
+            (self.cursor / columns)
+                .saturating_sub(max_rows / 2)
+                .min(total_rows - max_rows)
+        };
+        let (mut start, mut end) = (
+            start_row * columns,
+            ((start_row + max_rows) * columns).min(total),
+        );
+        // Wrapped items take several lines; drop items from the end farther from the highlight
+        // until the page fits the terminal.
+        if let Some(budget) = self
+            .fitting_rows()
+            .filter(|_| self.config.soft_wrap && columns == 1)
+        {
+            let heights: Vec<usize> = (start..end)
+                .map(|pos| self.item_height(pos, start))
+                .collect();
+            let mut lines: usize = heights.iter().sum();
+            while end - start > 1 && lines > budget {
+                if self.cursor - start > end - 1 - self.cursor {
+                    lines -= heights[start - start_row * columns];
+                    start += 1;
+                } else {
+                    end -= 1;
+                    lines -= heights[end - start_row * columns];
+                }
+            }
+        }
+        (start, end)
+    }
+
+    /// Number of lines the item at `pos` takes up on a page starting at `start_idx`, including
+    /// a description on its own line.
+    fn item_height(&self, pos: usize, start_idx: usize) -> usize {
+        let description = self.config.description_layout == DescriptionLayout::Below
+            && self.options()[self.visible[pos]].description().is_some();
+        self.item_lines(pos, start_idx).len() + usize::from(description)
+    }
+
+    /// The line counting the items above the list, e.g. "Total: 12 | Showing: 1 - 5".
+    fn totals_line(&self, start_idx: usize, end_idx: usize) -> Line {
+        let strings = &self.config.strings;
+        let total = self.visible.len();
+        let mut line = if self.query.is_some() || self.active_predicate().is_some() {
+            Line::plain(fill(
+                &strings.totals_matched,
+                &[
+                    ("total", &self.options().len()),
+                    ("matched", &total),
+                    ("first", &(start_idx + 1).min(total)),
+                    ("last", &end_idx),
+                ],
+            ))
+        } else {
+            Line::plain(fill(
+                &strings.totals,
+                &[
+                    ("total", &total),
+                    ("first", &(start_idx + 1)),
+                    ("last", &end_idx),
+                ],
+            ))
+        };
+        if self.checked.is_some() {
+            let count = fill(
+                &strings.selected_count,
+                &[
+                    ("selected", &self.checked_indices().len()),
+                    ("total", &self.options().len()),
+                ],
+            );
+            line.push(count, ContentStyle::new());
+        }
+        line
+    }
+
+    /// The footer text given with [`DropDownBuilder::help_line`](crate::DropDownBuilder::help_line)
+    /// with its placeholders filled in: `{total}`, `{matched}`, `{position}`, `{item}`,
+    /// `{selected}` and `{filter}`.
+    fn custom_help(&self, template: &str) -> String {
+        let item = self
+            .current()
+            .map(|idx| self.label_of(&self.options()[idx]))
+            .unwrap_or_default();
+        let position = if self.visible.is_empty() {
+            0
+        } else {
+            self.cursor + 1
+        };
+        template
+            .replace("{total}", &self.options().len().to_string())
+            .replace("{matched}", &self.visible.len().to_string())
+            .replace("{position}", &position.to_string())
+            .replace("{item}", &item)
+            .replace("{selected}", &self.checked_indices().len().to_string())
+            .replace("{filter}", self.query.as_deref().unwrap_or_default())
+    }
+
+    /// Whether `action` does anything in this menu, so that the help screen can leave out
+    /// bindings that do not apply, such as the checkbox keys outside multi-select mode.
+    fn action_applies(&self, action: Action) -> bool {
+        match action {
+            Action::Toggle
+            | Action::SelectAll
+            | Action::SelectNone
+            | Action::ToggleAll
+            | Action::Invert => self.checked.is_some(),
+            Action::TogglePredicate => self
+                .hooks
+                .as_deref()
+                .is_some_and(|hooks| hooks.filter_predicate.is_some()),
+            Action::CycleSort => self.config.sort.is_some(),
+            Action::ConfirmAndStay => self
+                .hooks
+                .as_deref()
+                .is_some_and(|hooks| hooks.on_confirm_and_stay.is_some()),
+            Action::CycleMatchMode | Action::ToggleCase => self.query.is_some(),
+            Action::Undo => self.config.undo_window.is_some(),
+            Action::MoveItemUp | Action::MoveItemDown => self.config.reorder,
+            Action::Right => self.grid_columns() > 1 || self.config.horizontal,
+            _ => true,
+        }
+    }
+
+    /// The help screen: every key binding that applies to this menu and the hotkeys of the
+    /// items listed, each with what it does.
+    fn help_frame(&self) -> Frame {
+        let theme = &self.config.theme;
+        let mut entries: Vec<(String, String)> = self
+            .config
+            .keymap
+            .describe(self.checked.is_some())
+            .into_iter()
+            .filter(|&(_, action)| self.action_applies(action))
+            .map(|(keys, action)| (keys, self.config.strings.action(action).to_string()))
+            .collect();
+        entries.extend(self.visible.iter().filter_map(|&i| {
+            let option = &self.options()[i];
+            let key = option.hotkey()?;
+            Some((key.to_string(), self.label_of(option)))
+        }));
+        let keys_width = entries
+            .iter()
+            .map(|(keys, _)| keys.width())
+            .max()
+            .unwrap_or_default();
+
+        let mut frame = Frame::default();
+        frame.push(Line::styled(
+            self.config.strings.keys_title.as_str(),
+            theme.prompt,
+        ));
+        frame.push(Line::default());
+        for (keys, description) in entries {
+            let padding = " ".repeat(keys_width - keys.width());
+            frame.push(Line::plain(format!(
+                "  {}{}  {}",
+                keys, padding, description
+            )));
+        }
+        frame.push(Line::default());
+        frame.push(Line::styled(
+            self.config.strings.keys_footer.as_str(),
+            theme.help,
+        ));
+        frame
+    }
+
+    /// The single line of a horizontal menu: the prompt, then the items on the current page
+    /// between arrows, the highlighted one in brackets, then the filter if one is typed.
+    fn horizontal_line(&self) -> Line {
+        let theme = &self.config.theme;
+        let (start_idx, end_idx) = self.window();
+        let mut dimmed = ContentStyle::new();
+        dimmed.attributes.set(Attribute::Dim);
+        let mut line = Line::styled(self.config.prompt.as_str(), theme.prompt);
+        line.push(" < ", ContentStyle::new());
+        if start_idx > 0 {
+            line.push("… | ", dimmed);
+        }
+        for pos in start_idx..end_idx {
+            if pos > start_idx {
+                line.push(" | ", ContentStyle::new());
+            }
+            let i = self.visible[pos];
+            let option = &self.options()[i];
+            let style = if pos == self.cursor {
+                theme.highlight
+            } else if option.is_disabled() {
+                dimmed
+            } else {
+                ContentStyle::new()
+            };
+            let highlighted = pos == self.cursor;
+            let open = if highlighted { "[" } else { "" };
+            line.push(format!("{}{}", open, self.checkbox(i)), style);
+            push_highlighted(
+                &mut line,
+                &self.label_of(option),
+                &self.match_positions(pos),
+                style,
+                theme.matched,
+            );
+            if highlighted {
+                line.push("]", style);
+            }
+        }
+        if end_idx < self.visible.len() {
+            line.push(" | …", dimmed);
+        }
+        line.push(" >", ContentStyle::new());
+        if let Some(query) = &self.query {
+            line.push(
+                format!(
+                    "  {}",
+                    fill(&self.config.strings.filter, &[("filter", query)])
+                ),
+                theme.help,
+            );
+        }
+        line
+    }
+
+    /// Number of items side by side in each row: more than one in a grid layout.
+    fn grid_columns(&self) -> usize {
+        self.config.grid.unwrap_or(1).max(1)
+    }
+
+    /// Width of every cell of a grid on the current page: room for the marker, the checkbox and
+    /// the widest label on the page.
+    fn grid_cell_width(&self) -> usize {
+        let (start_idx, end_idx) = self.window();
+        let theme = &self.config.theme;
+        let checkbox = self.checkbox_width();
+        let widest = (start_idx..end_idx)
+            .map(|pos| {
+                let (before, label, arrow) = self.grid_label(&self.options()[self.visible[pos]]);
+                before.width() + label.width() + arrow.width()
+            })
+            .max()
+            .unwrap_or_default();
+        theme.marker.width() + checkbox + widest
+    }
+
+    /// The text of an item in a grid cell, in three parts so that matches in the label can be
+    /// highlighted: its icon and status badge, its label, and its submenu arrow.
+    fn grid_label(&self, option: &T) -> (String, String, &'static str) {
+        let icon = option
+            .style()
+            .icon
+            .map(|icon| format!("{} ", icon))
+            .unwrap_or_default();
+        let badge = option
+            .status()
+            .map(|status| status.badge())
+            .unwrap_or_default();
+        let arrow = if !option.children().is_empty() && self.checked.is_none() {
+            " ›"
+        } else {
+            ""
+        };
+        (format!("{}{}", icon, badge), self.label_of(option), arrow)
+    }
+
+    /// The lines of a grid page: the items from `start_idx` to `end_idx`, row by row, each in a
+    /// cell of the same width.
+    fn grid_lines(&self, start_idx: usize, end_idx: usize) -> Vec<Line> {
+        let theme = &self.config.theme;
+        let cell_width = self.grid_cell_width();
+        let positions: Vec<usize> = (start_idx..end_idx).collect();
+        positions
+            .chunks(self.grid_columns())
+            .map(|row| {
+                let mut line = Line::default();
+                for (column, &pos) in row.iter().enumerate() {
+                    if column > 0 {
+                        line.push(GRID_GAP, ContentStyle::new());
+                    }
+                    let i = self.visible[pos];
+                    let option = &self.options()[i];
+                    let highlighted = pos == self.cursor;
+                    let mut style = if highlighted {
+                        theme.highlight
+                    } else {
+                        ContentStyle::new()
+                    };
+                    let blocked = self.at_max_selected()
+                        && self.checked.as_ref().is_some_and(|checked| !checked[i]);
+                    if option.is_disabled() || blocked {
+                        style.attributes.set(Attribute::Dim);
+                    }
+                    if let Some(color) = option.style().foreground {
+                        style.foreground_color = Some(color);
+                    }
+                    let marker = if highlighted {
+                        theme.marker.clone()
+                    } else {
+                        " ".repeat(theme.marker.width())
+                    };
+                    let checkbox = self.checkbox(i);
+                    let (before, label, arrow) = self.grid_label(option);
+                    let before = format!("{}{}{}", marker, checkbox, before);
+                    let width = before.width() + label.width() + arrow.width();
+                    line.push(before, style);
+                    push_highlighted(
+                        &mut line,
+                        &label,
+                        &self.match_positions(pos),
+                        style,
+                        theme.matched,
+                    );
+                    let mut after = arrow.to_string();
+                    let last = column + 1 == row.len();
+                    if !last || highlighted {
+                        after.push_str(&" ".repeat(cell_width.saturating_sub(width)));
+                    }
+                    if !after.is_empty() {
+                        line.push(after, style);
+                    }
+                }
+                line
+            })
+            .collect()
+    }
+
+    /// The rows of the current page: the items in the window, each preceded by its dividers.
+    ///
+    /// When the page starts in the middle of a group, the group's header is repeated at the top so
+    /// that the visible items are never shown without their heading.
+    fn page_rows(&self) -> Vec<Row<'_>> {
+        let (start_idx, end_idx) = self.window();
+        // Dividers are placed by index into the top-level options.
+        let show_dividers = self.trail.is_empty()
+            && self.sort == SortMode::Unsorted
+            && self.query.as_deref().is_none_or(str::is_empty);
+        let mut rows = Vec::new();
+        if show_dividers && let Some(&first) = self.visible.get(start_idx) {
+            let group_header = self.config.dividers.iter().rev().find(|(before, divider)| {
+                *before <= first && matches!(divider, Divider::Header(_))
+            });
+            if let Some((before, header)) = group_header
+                && *before < first
+            {
+                rows.push(Row::Divider(header));
+            }
+        }
+        for pos in start_idx..end_idx {
+            if show_dividers {
+                let i = self.visible[pos];
+                rows.extend(
+                    self.config
+                        .dividers
+                        .iter()
+                        .filter(|(before, _)| *before == i)
+                        .map(|(_, divider)| Row::Divider(divider)),
+                );
+            }
+            rows.push(Row::Item(pos));
+            if self.config.soft_wrap {
+                let continued = self.item_lines(pos, start_idx).len() - 1;
+                rows.extend(std::iter::repeat_n(Row::Wrapped(pos), continued));
+            }
+            let below = match self.config.description_layout {
+                DescriptionLayout::Inline => false,
+                DescriptionLayout::Below => true,
+                DescriptionLayout::BelowHighlighted => pos == self.cursor,
+            };
+            if below && self.options()[self.visible[pos]].description().is_some() {
+                rows.push(Row::Description(pos));
+            }
+        }
+        rows
+    }
+
+    /// The character positions in the label of the item at `pos` within `visible` that match the
+    /// filter, or else the search. Empty if neither matches.
+    fn match_positions(&self, pos: usize) -> Vec<usize> {
+        match self.highlights.get(pos) {
+            Some(positions) if !positions.is_empty() => positions.clone(),
+            // Search matches are highlighted where no filter match is.
+            _ => self
+                .search
+                .as_deref()
+                .and_then(|search| {
+                    substring_match(
+                        search,
+                        &self.label_of(&self.options()[self.visible[pos]]),
+                        false,
+                    )
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    /// The line of the item at `pos` within `visible`, on a page starting at `start_idx`:
+    /// marker, number, checkbox, icon, badge, label and inline description.
+    fn item_line(&self, pos: usize, start_idx: usize) -> Line {
+        let theme = &self.config.theme;
+        let i = self.visible[pos];
+        let checkbox = self.checkbox(i);
+        let option = &self.options()[i];
+        let highlighted = pos == self.cursor;
+        let mut style = if highlighted {
+            theme.highlight
+        } else {
+            ContentStyle::new()
+        };
+        // Once the maximum is checked, the items that can no longer be checked are dimmed.
+        let blocked =
+            self.at_max_selected() && self.checked.as_ref().is_some_and(|checked| !checked[i]);
+        if option.is_disabled() || blocked {
+            style.attributes.set(Attribute::Dim);
+        }
+        let marker = if highlighted {
+            theme.marker.clone()
+        } else {
+            " ".repeat(theme.marker.width())
+        };
+        let number = match pos - start_idx {
+            _ if !self.config.numbered => String::new(),
+            offset @ 0..9 => format!("{}. ", offset + 1),
+            _ => "   ".to_string(),
+        };
+        let mut line = Line::default();
+        line.push(format!("{}{}{}", marker, number, checkbox), style);
+        let item_style = option.style();
+        let mut label_style = style;
+        if let Some(color) = item_style.foreground {
+            label_style.foreground_color = Some(color);
+        }
+        if item_style.bold {
+            label_style.attributes.set(Attribute::Bold);
+        }
+        if let Some(icon) = &item_style.icon {
+            line.push(format!("{} ", icon), label_style);
+        }
+        if let Some(status) = option.status() {
+            let mut badge_style = label_style;
+            badge_style.foreground_color = Some(status.color());
+            badge_style.attributes.set(Attribute::Bold);
+            line.push(status.badge(), badge_style);
+        }
+        let positions = self.match_positions(pos);
+        match option.hotkey() {
+            Some(key) if positions.is_empty() => {
+                push_hotkey(&mut line, &self.label_of(option), key, label_style)
+            }
+            _ => push_highlighted(
+                &mut line,
+                &self.label_of(option),
+                &positions,
+                label_style,
+                self.config.theme.matched,
+            ),
+        }
+        if !option.children().is_empty() && self.checked.is_none() {
+            line.push(" ›", label_style);
+        }
+        if let Some(description) = option.description()
+            && self.config.description_layout == DescriptionLayout::Inline
+        {
+            let mut dimmed = style;
+            dimmed.attributes.set(Attribute::Dim);
+            line.push(format!("  {}", description), dimmed);
+        }
+        line
+    }
+
+    /// The lines the item at `pos` takes up: its [`item_line`](Menu::item_line), wrapped to the
+    /// width of the menu if wrapping is on, with the continuation lines lined up with the label.
+    fn item_lines(&self, pos: usize, start_idx: usize) -> Vec<Line> {
+        let line = self.item_line(pos, start_idx);
+        if !self.config.soft_wrap {
+            return vec![line];
+        }
+        let checkbox = self.checkbox_width();
+        let indent =
+            self.config.theme.marker.width() + 3 * usize::from(self.config.numbered) + checkbox;
+        line.wrap(self.content_width(), indent)
+    }
+
+    /// Screen row of the first rendered item: below the top edge of a box, the prompt, the
+    /// breadcrumb inside submenus, the optional filter line, the totals line, a blank separator
+    /// and the column titles of a table. The prompt, breadcrumb and totals are left out without a
+    /// header.
+    fn first_item_row(&self) -> usize {
+        let header = if self.config.show_header {
+            2 + usize::from(!self.trail.is_empty())
+        } else {
+            0
+        };
+        usize::from(self.config.border.is_some())
+            + header
+            + 1
+            + usize::from(self.query.is_some())
+            + usize::from(self.config.table_header.is_some())
+    }
+
+    /// Renders the current state of the menu in the terminal.
+    ///
+    /// Replaces the previous frame (by clearing the screen, or by rewinding in inline mode),
+    /// displays a header with total/max items, renders visible items with highlighting for the
+    /// selected item, and shows navigation instructions. Implements a sliding window for when
+    /// there are more items than can be displayed at once.
+    pub(crate) fn render(&mut self) -> io::Result<()> {
+        self.stale = false;
+        let frame = self.frame();
+        if let Some(session) = &self.config.session {
+            let text = if self.config.plain {
+                frame.plain_text()
+            } else {
+                frame.ansi_text()
+            };
+            session.frame(&text)?;
+        }
+        // Stderr and custom writers are unbuffered; send each frame in one go.
+        let mut out = BufWriter::new(&mut self.config.output);
+        self.renderer.draw(&mut out, &frame)?;
+        if std::mem::take(&mut self.at_edge) && self.config.bell_at_edges {
+            write!(out, "\x07")?;
+        }
+        out.flush()
+    }
+
+    /// Builds the lines for the current state without drawing them.
+    ///
+    /// Only the options on the current page are formatted, so a frame costs the same for a
+    /// hundred options as for hundreds of thousands.
+    pub(crate) fn frame(&self) -> Frame {
+        let frame = self.content();
+        let Some(border) = &self.config.border else {
+            return frame;
+        };
+        let columns = self
+            .area()
+            .map(|(columns, _)| usize::from(columns))
+            .filter(|&columns| columns > 0);
+        border.enclose(
+            frame,
+            self.config.border_title.as_deref(),
+            columns,
+            self.config.theme.help,
+            self.config.theme.prompt,
+        )
+    }
+
+    /// The lines of the current state, inside the box if the menu has one.
+    fn content(&self) -> Frame {
+        let mut frame = Frame::default();
+        if self.options().is_empty() && self.live.is_none() {
+            frame.push(Line::plain(self.config.strings.no_options.as_str()));
+            frame.push(Line::plain(self.config.strings.exit_hint.as_str()));
+            return frame;
+        }
+        if self.show_help {
+            return self.help_frame();
+        }
+        if self.config.horizontal {
+            frame.push(self.horizontal_line());
+            return frame;
+        }
+
+        let total = self.visible.len();
+        let (start_idx, end_idx) = self.window();
+
+        let theme = &self.config.theme;
+        if self.config.show_header {
+            frame.push(Line::styled(self.config.prompt.as_str(), theme.prompt));
+            if let Some(breadcrumb) = self.breadcrumb() {
+                frame.push(Line::styled(breadcrumb, theme.help));
+            }
+        }
+        // The filter line stays even without a header, so that the typed text can be seen.
+        if let Some(query) = &self.query {
+            frame.push(Line::plain(fill(
+                &self.config.strings.filter,
+                &[("filter", query)],
+            )));
+        }
+        if self.config.show_header {
+            frame.push(self.totals_line(start_idx, end_idx));
+        }
+        frame.push(indicator(&theme.scroll_up, start_idx > 0));
+        if let Some(header) = &self.config.table_header {
+            // Line the titles up with the labels, past the marker, number and checkbox.
+            let checkbox = self.checkbox_width();
+            let indent = theme.marker.width() + 3 * usize::from(self.config.numbered) + checkbox;
+            let mut bold = ContentStyle::new();
+            bold.attributes.set(Attribute::Bold);
+            frame.push(Line::styled(
+                format!("{}{}", " ".repeat(indent), header),
+                bold,
+            ));
+        }
+
+        let items_start = frame.lines.len();
+        if self.options().is_empty() {
+            if !self.loading() {
+                frame.push(Line::plain(format!("  {}", self.config.strings.no_items)));
+            }
+        } else if total == 0 {
+            frame.push(Line::plain(format!("  {}", self.config.strings.no_matches)));
+        }
+        if self.grid_columns() > 1 {
+            for line in self.grid_lines(start_idx, end_idx) {
+                frame.push(line);
+            }
+        }
+        // A grid has no dividers, and draws its items above.
+        let rows = if self.grid_columns() > 1 {
+            Vec::new()
+        } else {
+            self.page_rows()
+        };
+        // Separators span the widest label on the page.
+        let rule_width = rows
+            .iter()
+            .filter_map(|row| match row {
+                Row::Item(pos) => Some(self.label_of(&self.options()[self.visible[*pos]]).width()),
+                Row::Wrapped(_) | Row::Description(_) | Row::Divider(_) => None,
+            })
+            .max()
+            .unwrap_or_default()
+            .max(3);
+        // The highlight bar is padded to the widest row so that its length does not jump around.
+        let mut highlighted_lines = 0..0;
+        let mut row_width = 0;
+        for row in rows {
+            let pos = match row {
+                Row::Item(pos) => pos,
+                // Drawn together with the item's first line.
+                Row::Wrapped(_) => continue,
+                Row::Description(pos) => {
+                    // Lined up with the label, past the marker, number and checkbox.
+                    let indent = theme.marker.width()
+                        + 3 * usize::from(self.config.numbered)
+                        + self.checkbox_width();
+                    let description = self.options()[self.visible[pos]]
+                        .description()
+                        .unwrap_or_default();
+                    let mut dimmed = theme.help;
+                    dimmed.attributes.set(Attribute::Dim);
+                    let mut line = Line::plain(" ".repeat(indent));
+                    line.push(description, dimmed);
+                    frame.push(line);
+                    continue;
+                }
+                Row::Divider(Divider::Separator) => {
+                    let mut dimmed = ContentStyle::new();
+                    dimmed.attributes.set(Attribute::Dim);
+                    let mut line = Line::default();
+                    line.push(format!("  {}", "─".repeat(rule_width)), dimmed);
+                    frame.push(line);
+                    continue;
+                }
+                Row::Divider(Divider::Header(title)) => {
+                    let mut bold = ContentStyle::new();
+                    bold.attributes.set(Attribute::Bold);
+                    let mut line = Line::default();
+                    line.push(title.as_str(), bold);
+                    frame.push(line);
+                    continue;
+                }
+            };
+            let lines = self.item_lines(pos, start_idx);
+            if pos == self.cursor {
+                highlighted_lines = frame.lines.len()..frame.lines.len() + lines.len();
+            }
+            for line in lines {
+                row_width = row_width.max(line.width());
+                frame.push(line);
+            }
+        }
+        for line in &mut frame.lines[highlighted_lines] {
+            line.pad(row_width, theme.highlight);
+        }
+        if let Some(line) = self.loading_line() {
+            frame.push(line);
+        }
+
+        let preview = self.preview_text();
+        let columns = self
+            .area()
+            .map(|(columns, _)| usize::from(columns))
+            .filter(|&columns| columns > 0)
+            .unwrap_or(FALLBACK_COLUMNS);
+        if let (Some(text), PreviewLayout::Right) = (&preview, self.config.preview_layout) {
+            preview::attach_right(&mut frame.lines[items_start..], text, columns / 2);
+        }
+        frame.push(indicator(&theme.scroll_down, end_idx < total));
+        if let (Some(text), PreviewLayout::Bottom(height)) = (&preview, self.config.preview_layout)
+        {
+            for line in preview::below(text, height, columns) {
+                frame.push(line);
+            }
+        }
+        let strings = &self.config.strings;
+        let custom_help = self
+            .config
+            .help
+            .as_deref()
+            .map(|help| self.custom_help(help));
+        let help = match &custom_help {
+            Some(help) => help,
+            None if self.checked.is_some() => &strings.help_multi,
+            None if self.config.reorder => &strings.help_reorder,
+            None if self.query.is_some() && !self.trail.is_empty() => &strings.help_filter_submenu,
+            None if self.query.is_some() => &strings.help_filter,
+            None if !self.trail.is_empty() => &strings.help_submenu,
+            None => &strings.help,
+        };
+        let mut footer = Line::styled(help.as_str(), theme.help);
+        let has_predicate = self
+            .hooks
+            .as_deref()
+            .is_some_and(|hooks| hooks.filter_predicate.is_some());
+        if has_predicate && self.config.help.is_none() {
+            let toggle = if self.predicate_on {
+                &strings.show_all
+            } else {
+                &strings.only_matching
+            };
+            footer.push(toggle.as_str(), theme.help);
+        }
+        if let Some(note) = self.limits_note() {
+            footer.push(note, theme.help);
+        }
+        if self.grid_columns() > 1 && self.config.help.is_none() {
+            footer.push(strings.help_grid.as_str(), theme.help);
+        }
+        if self.config.sort.is_some() && self.config.help.is_none() && self.query.is_none() {
+            let mode = strings.sort_mode(self.sort);
+            footer.push(fill(&strings.sort, &[("mode", &mode)]), theme.help);
+        }
+        if self.query.is_some() && self.config.help.is_none() {
+            let mode = strings.match_mode(self.match_mode);
+            let case = if self.case_sensitive {
+                &strings.case_sensitive
+            } else {
+                &strings.ignore_case
+            };
+            footer.push(
+                fill(&strings.match_mode, &[("mode", &mode), ("case", case)]),
+                theme.help,
+            );
+        }
+        if self.search.is_some() && self.config.help.is_none() {
+            footer.push(strings.search_keys.as_str(), theme.help);
+        }
+        if let Some(countdown) = self.countdown() {
+            footer.push(format!(" | {}", countdown), theme.help);
+        }
+        // The undo note, then the search line, takes the footer's place while it is needed.
+        if let Some(note) = self.undo_note().or_else(|| self.confirming_note()) {
+            frame.push(Line::styled(note, theme.help));
+        } else if self.search_open {
+            let search = self.search.as_deref().unwrap_or_default();
+            frame.push(Line::plain(fill(&strings.search, &[("search", &search)])));
+        } else if self.config.show_footer {
+            frame.push(footer);
+        }
+
+        // Lines that wrapped would push the rest of the menu down and break the redraw.
+        if let Some((columns, _)) = self.area().filter(|&(columns, _)| columns > 0) {
+            for line in &mut frame.lines {
+                line.truncate(usize::from(columns));
+            }
+        }
+        frame
+    }
+}
+
+/// Keeps the elements of `items` whose flag in `kept` is set.
+fn retain_kept<U>(items: &mut Vec<U>, kept: &[bool]) {
+    let mut flags = kept.iter();
+    items.retain(|_| flags.next().copied().unwrap_or(true));
+}
+
+/// The entries of the submenu opened by following `trail` from `root`, or `root` itself for an
+/// empty trail.
+fn level<'r, T: MenuEntry>(root: &'r [T], trail: &[Level]) -> &'r [T] {
+    trail
+        .iter()
+        .fold(root, |options, level| options[level.entered].children())
+}
+
+/// The line above or below the items: the scroll indicator if the list continues in that
+/// direction, blank otherwise.
+fn indicator(glyph: &str, more: bool) -> Line {
+    if more && !glyph.is_empty() {
+        let mut dimmed = ContentStyle::new();
+        dimmed.attributes.set(Attribute::Dim);
+        Line::styled(glyph, dimmed)
+    } else {
+        Line::default()
+    }
+}
+
+/// Renders a single-selection menu for `options` with `current_idx` highlighted.
+pub(crate) fn display_menu<T: MenuEntry>(options: &[T], current_idx: usize, max_show: usize) {
+    if let Err(e) = display_menu_to(&mut io::stdout(), options, current_idx, max_show) {
+        eprintln!("Failed to render menu: {}", e);
+    }
+}
+
+/// Produces the frame of a single-selection menu as a string, for snapshot tests of menu layouts.
+///
+/// The result only depends on the arguments: the terminal's size and the `NO_COLOR`/`TERM`
+/// environment are not consulted, and lines are never cut.
+///
+/// # Parameters
+/// * `options` - Items of the menu, in display order.
+/// * `current_idx` - Index of the highlighted item.
+/// * `page_size` - Maximum number of items shown at once.
+/// * `ansi` - Whether to include colors and attributes as ANSI escape sequences. Without them,
+///   the highlighted row is still recognisable by its `> ` marker.
+///
+/// # Returns
+/// The lines of the frame separated by `\n`, without a trailing line break.
+///
+/// # Example
+/// ```
+/// let frame = ter_menu::render_to_string(&["a", "b", "c"], 1, 5, false);
+/// assert!(frame.contains("  a\n> b\n  c"));
+/// ```
+pub fn render_to_string<T: MenuEntry>(
+    options: &[T],
+    current_idx: usize,
+    page_size: usize,
+    ansi: bool,
+) -> String {
+    let config = MenuConfig {
+        default_index: current_idx,
+        plain: false,
+        ..MenuConfig::new(page_size)
+    };
+    let frame = Menu::new(options, config).frame();
+    if ansi {
+        frame.ansi_text()
+    } else {
+        frame.plain_text()
+    }
+}
+
+/// Like [`display_menu`], but draws into `out`.
+pub(crate) fn display_menu_to<T: MenuEntry, W: Write>(
+    out: &mut W,
+    options: &[T],
+    current_idx: usize,
+    max_show: usize,
+) -> io::Result<()> {
+    let config = MenuConfig {
+        default_index: current_idx,
+        ..MenuConfig::new(max_show)
+    };
+    let mut menu = Menu::new(options, config);
+    let frame = menu.frame();
+    menu.renderer.draw(out, &frame)
+}
+
+/// Appends `label` with its first `key` underlined, or with `(key)` after it if there is none.
+fn push_hotkey(line: &mut Line, label: &str, key: char, base: ContentStyle) {
+    let Some((at, c)) = label.char_indices().find(|&(_, c)| same_key(c, key)) else {
+        line.push(label, base);
+        let mut dimmed = base;
+        dimmed.attributes.set(Attribute::Dim);
+        line.push(format!(" ({})", key), dimmed);
+        return;
+    };
+    let mut underlined = base;
+    underlined.attributes.set(Attribute::Underlined);
+    let end = at + c.len_utf8();
+    line.push(&label[..at], base);
+    line.push(&label[at..end], underlined);
+    line.push(&label[end..], base);
+}
+
+/// Whether `a` and `b` are the same key, ignoring case.
+fn same_key(a: char, b: char) -> bool {
+    a.to_lowercase().eq(b.to_lowercase())
+}
+
+/// Appends `label` to `line` in `base`, drawing the characters at the given char positions with
+/// `style` on top: its colors where it has them, and its attributes added to those of `base`.
+///
+/// Adding to the row's base style keeps the highlight composed with the reverse-video bar of the
+/// selected row.
+fn push_highlighted(
+    line: &mut Line,
+    label: &str,
+    positions: &[usize],
+    base: ContentStyle,
+    style: ContentStyle,
+) {
+    if positions.is_empty() {
+        line.push(label, base);
+        return;
+    }
+    let mut matched = base;
+    matched.foreground_color = style.foreground_color.or(base.foreground_color);
+    matched.background_color = style.background_color.or(base.background_color);
+    matched.attributes.extend(style.attributes);
+
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (i, c) in label.chars().enumerate() {
+        let is_match = positions.contains(&i);
+        if is_match != run_matched && !run.is_empty() {
+            line.push(
+                std::mem::take(&mut run),
+                if run_matched { matched } else { base },
+            );
+        }
+        run_matched = is_match;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        line.push(run, if run_matched { matched } else { base });
+    }
+}
@@ -0,0 +1,282 @@
+use crate::border::Border;
+use crate::cancel::CancelHandle;
+use crate::drop_down::outcome;
+use crate::error::TerMenuError;
+use crate::events::{EventSource, SharedEvents};
+use crate::item::MenuEntry;
+use crate::keymap::KeyMap;
+use crate::menu::{Menu, MenuConfig};
+use crate::output::Output;
+use crate::result::SelectionResult;
+use crate::strings::Strings;
+use crate::theme::Theme;
+use crate::worker::Worker;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+/// A terminal-based interactive checkbox list for picking several items at once.
+///
+/// Items are navigated with the arrow keys like [`TerminalDropDown`](crate::TerminalDropDown),
+/// but Space toggles the highlighted item, `a` checks every item (or clears them all if they
+/// already are), `i` inverts the checks, `n` clears every check, and Enter confirms the whole
+/// set.
+///
+/// # Type Parameters
+/// * `T` - The type of items in the list. Must be displayable and transferable to the
+///   interaction thread.
+#[derive(Debug)]
+pub struct TerminalMultiSelect<T>
+where
+    T: MenuEntry + Send + 'static,
+{
+    worker: Worker<SelectionResult<Vec<T>>>,
+    cancel: CancelHandle,
+}
+
+impl<T> TerminalMultiSelect<T>
+where
+    T: MenuEntry + Send + 'static,
+{
+    /// Creates a new TerminalMultiSelect instance and starts the interaction thread.
+    ///
+    /// # Parameters
+    /// * `options` - Items to choose from, rendered in the given order.
+    /// * `item_n` - Maximum number of items to display in the terminal at once.
+    ///
+    /// # Returns
+    /// A new TerminalMultiSelect instance ready for user interaction.
+    pub fn use_multi_select(options: Vec<T>, item_n: usize) -> Self {
+        Self::spawn(options, MenuConfig::new(item_n))
+    }
+
+    /// Returns a [`MultiSelectBuilder`] for configuring a checkbox list before it is shown.
+    pub fn builder() -> MultiSelectBuilder<T> {
+        MultiSelectBuilder::new()
+    }
+
+    /// Starts the interaction thread for a list configured by `config`.
+    fn spawn(options: Vec<T>, config: MenuConfig) -> Self {
+        let cancel = CancelHandle::default();
+        let worker_cancel = cancel.clone();
+        let worker = Worker::spawn(move || {
+            let mut menu = Menu::multi(&options, config).with_cancel(worker_cancel);
+            match panic::catch_unwind(AssertUnwindSafe(|| menu.run())) {
+                Ok(Ok(Some(_))) => {
+                    let checked = menu.checked_indices();
+                    let mut options: Vec<Option<T>> = options.into_iter().map(Some).collect();
+                    let selected = checked
+                        .into_iter()
+                        .filter_map(|idx| options[idx].take())
+                        .collect();
+                    SelectionResult::Selected(selected)
+                }
+                Ok(Ok(None)) => SelectionResult::Cancelled,
+                Ok(Err(e)) => e.into(),
+                Err(payload) => SelectionResult::Error(TerMenuError::from_panic(payload)),
+            }
+        });
+
+        Self { worker, cancel }
+    }
+
+    /// Closes the list from the calling thread as if the user had pressed Escape; see
+    /// [`TerminalDropDown::cancel`](crate::TerminalDropDown::cancel).
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    /// Returns a [`CancelHandle`] for closing the list from another thread.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+
+    /// Returns `true` once the interaction is over and its outcome is ready.
+    pub fn is_finished(&self) -> bool {
+        self.worker.is_finished()
+    }
+
+    /// Returns the outcome if the interaction is over, without blocking; see
+    /// [`TerminalDropDown::try_wait`](crate::TerminalDropDown::try_wait).
+    pub fn try_wait(&mut self) -> Option<SelectionResult<Vec<T>>> {
+        self.worker.try_join().map(outcome)
+    }
+
+    /// Like [`try_wait`](TerminalMultiSelect::try_wait), but blocks for up to `timeout` for the
+    /// interaction to end.
+    pub fn wait_timeout(&mut self, timeout: Duration) -> Option<SelectionResult<Vec<T>>> {
+        self.worker.join_timeout(timeout).map(outcome)
+    }
+
+    /// Blocks until the user interaction thread completes.
+    ///
+    /// # Returns
+    /// `SelectionResult::Selected` with the checked items in display order, or in the order they
+    /// were checked for an [`ordered`](MultiSelectBuilder::ordered) list (possibly empty if the
+    /// user confirmed without checking anything), `Cancelled` on Escape, `Interrupted` on Ctrl-C,
+    /// or `Error` if the terminal could not be driven. Once
+    /// [`try_wait`](TerminalMultiSelect::try_wait) or
+    /// [`wait_timeout`](TerminalMultiSelect::wait_timeout) returned the outcome, the error is
+    /// [`TerMenuError::OutcomeTaken`].
+    pub fn wait(self) -> SelectionResult<Vec<T>> {
+        match self.worker.join() {
+            Some(joined) => outcome(joined),
+            None => SelectionResult::Error(TerMenuError::OutcomeTaken),
+        }
+    }
+}
+
+/// Step-by-step configuration for a [`TerminalMultiSelect`], like
+/// [`DropDownBuilder`](crate::DropDownBuilder) for single-selection dropdowns. Nothing is shown
+/// until [`MultiSelectBuilder::build`] is called.
+///
+/// # Example
+/// ```no_run
+/// use ter_menu::TerminalMultiSelect;
+///
+/// let reviewers = TerminalMultiSelect::builder()
+///     .items(["alice", "bob", "carol", "dave"])
+///     .prompt("Reviewers:")
+///     .min_selected(1)
+///     .max_selected(3)
+///     .build()
+///     .wait();
+/// ```
+pub struct MultiSelectBuilder<T>
+where
+    T: MenuEntry + Send + 'static,
+{
+    items: Vec<T>,
+    config: MenuConfig,
+}
+
+impl<T> MultiSelectBuilder<T>
+where
+    T: MenuEntry + Send + 'static,
+{
+    /// Creates a builder with no items, a page size that fits the terminal and no limit on how
+    /// many items are checked.
+    pub fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            config: MenuConfig::default(),
+        }
+    }
+
+    /// Appends items. Items are displayed in the order they are added.
+    pub fn items(mut self, items: impl IntoIterator<Item = T>) -> Self {
+        self.items.extend(items);
+        self
+    }
+
+    /// Appends a single item.
+    pub fn item(mut self, item: T) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    /// Sets the maximum number of items to display in the terminal at once; see
+    /// [`DropDownBuilder::page_size`](crate::DropDownBuilder::page_size).
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.config.page_size = Some(page_size);
+        self
+    }
+
+    /// Sets the title line printed above the items.
+    pub fn prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.config.prompt = prompt.into();
+        self
+    }
+
+    /// Enables the fuzzy filter line, so that typing narrows the visible items.
+    pub fn filter(mut self, filter: bool) -> Self {
+        self.config.filter = filter;
+        self
+    }
+
+    /// Requires at least `min` checked items: Enter does nothing until enough are checked. The
+    /// footer shows the requirement and how many items are checked so far.
+    pub fn min_selected(mut self, min: usize) -> Self {
+        self.config.min_selected = min;
+        self
+    }
+
+    /// Allows at most `max` checked items. Once that many are checked, the unchecked items are
+    /// dimmed and Space no longer checks them, and the footer says the maximum is reached.
+    pub fn max_selected(mut self, max: usize) -> Self {
+        self.config.max_selected = Some(max);
+        self
+    }
+
+    /// Ranks the items in the order they are checked: each checked item shows its rank instead
+    /// of a check mark, unchecking one moves those after it up, and
+    /// [`wait`](TerminalMultiSelect::wait) returns the items in that order rather than in
+    /// display order, e.g. to pick and order a patch series.
+    pub fn ordered(mut self, ordered: bool) -> Self {
+        self.config.ordered = ordered;
+        self
+    }
+
+    /// Sets the texts the list shows on its own; see
+    /// [`DropDownBuilder::strings`](crate::DropDownBuilder::strings).
+    pub fn strings(mut self, strings: Strings) -> Self {
+        self.config.prompt = strings.prompt.clone();
+        self.config.confirm_message = strings.confirm_message.clone();
+        self.config.cancel_message = strings.cancel_message.clone();
+        self.config.strings = strings;
+        self
+    }
+
+    /// Replaces the key bindings. Defaults to [`KeyMap::default`].
+    pub fn keymap(mut self, keymap: KeyMap) -> Self {
+        self.config.keymap = keymap;
+        self
+    }
+
+    /// Sets the colors and glyphs used for drawing, e.g. [`Theme::dark`].
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.config.theme = theme;
+        self
+    }
+
+    /// Draws the list inside a box; see
+    /// [`DropDownBuilder::border`](crate::DropDownBuilder::border).
+    pub fn border(mut self, border: Border) -> Self {
+        self.config.border = Some(border);
+        self
+    }
+
+    /// Shows `title` in the top edge of the box; see
+    /// [`DropDownBuilder::border_title`](crate::DropDownBuilder::border_title).
+    pub fn border_title(mut self, title: impl Into<String>) -> Self {
+        self.config.border.get_or_insert_with(Border::default);
+        self.config.border_title = Some(title.into());
+        self
+    }
+
+    /// Sets where the list is drawn and its messages printed. Defaults to [`Output::Stdout`].
+    pub fn output(mut self, output: Output) -> Self {
+        self.config.output = output;
+        self
+    }
+
+    /// Reads input from `events` instead of the terminal; see
+    /// [`DropDownBuilder::events`](crate::DropDownBuilder::events).
+    pub fn events(mut self, events: impl EventSource + 'static) -> Self {
+        self.config.events = Some(SharedEvents::new(events));
+        self
+    }
+
+    /// Shows the checkbox list and starts the interaction thread.
+    pub fn build(self) -> TerminalMultiSelect<T> {
+        TerminalMultiSelect::spawn(self.items, self.config)
+    }
+}
+
+impl<T> Default for MultiSelectBuilder<T>
+where
+    T: MenuEntry + Send + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
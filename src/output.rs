@@ -0,0 +1,149 @@
+use std::fmt;
+use std::io::{self, IsTerminal, Write};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// Where a menu draws itself and prints its messages.
+///
+/// # Variants
+/// * `Stdout` - Standard output, the default.
+/// * `Stderr` - Standard error, so that standard output stays clean for piped results, as in
+///   `my-tool | xargs rm`.
+/// * `Writer` - Any writer, such as a PTY the application manages itself or an in-memory buffer.
+///   Built with [`Output::writer`].
+/// * `Frames` - Records every frame as plain text instead of drawing it, for tests; see
+///   [`FrameRecorder`].
+///
+/// Input is still read from the terminal the process is attached to, unless another
+/// [`EventSource`](crate::EventSource) is set.
+#[derive(Clone, Default)]
+pub enum Output {
+    #[default]
+    Stdout,
+    Stderr,
+    Writer(Arc<Mutex<dyn Write + Send>>),
+    Frames(FrameRecorder),
+}
+
+impl Output {
+    /// Draws into `writer`.
+    pub fn writer(writer: impl Write + Send + 'static) -> Self {
+        Output::Writer(Arc::new(Mutex::new(writer)))
+    }
+
+    /// Whether the output is a terminal that understands cursor movement. Writers are assumed
+    /// to be one, since the caller chose them for drawing the menu.
+    pub(crate) fn is_terminal(&self) -> bool {
+        match self {
+            Output::Stdout => io::stdout().is_terminal(),
+            Output::Stderr => io::stderr().is_terminal(),
+            Output::Writer(_) | Output::Frames(_) => true,
+        }
+    }
+}
+
+impl fmt::Debug for Output {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Output::Stdout => f.write_str("Stdout"),
+            Output::Stderr => f.write_str("Stderr"),
+            Output::Writer(_) => f.write_str("Writer(..)"),
+            Output::Frames(recorder) => f.debug_tuple("Frames").field(recorder).finish(),
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Output::Stdout => io::stdout().write(buf),
+            Output::Stderr => io::stderr().write(buf),
+            Output::Writer(writer) => writer
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .write(buf),
+            Output::Frames(recorder) => {
+                recorder
+                    .lock()
+                    .printed
+                    .push_str(&String::from_utf8_lossy(buf));
+                Ok(buf.len())
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Output::Stdout => io::stdout().flush(),
+            Output::Stderr => io::stderr().flush(),
+            Output::Writer(writer) => writer
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .flush(),
+            Output::Frames(_) => Ok(()),
+        }
+    }
+}
+
+/// Collects what a menu draws, one plain-text string per frame, so that tests can assert on the
+/// rendered layout without a terminal.
+///
+/// Clones share the same recording: keep one and pass another to the menu as
+/// [`Output::Frames`]. Lines within a frame are separated by `\n` and carry no styling.
+///
+/// # Example
+/// ```
+/// use crossterm::event::KeyCode;
+/// use ter_menu::{FrameRecorder, Output, ScriptedEvents, TerminalDropDown};
+///
+/// let recorder = FrameRecorder::new();
+/// let result = TerminalDropDown::builder()
+///     .items(["a", "b", "c"].map(|item| (item, |_: &&str| {})))
+///     .events(ScriptedEvents::keys([KeyCode::Down, KeyCode::Enter]))
+///     .output(Output::Frames(recorder.clone()))
+///     .build()
+///     .wait();
+/// assert_eq!(result.selected(), Some("b"));
+/// assert!(recorder.last_frame().unwrap().contains("> b"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FrameRecorder {
+    recording: Arc<Mutex<Recording>>,
+}
+
+#[derive(Debug, Default)]
+struct Recording {
+    frames: Vec<String>,
+    printed: String,
+}
+
+impl FrameRecorder {
+    /// Creates an empty recording.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every frame drawn so far, oldest first.
+    pub fn frames(&self) -> Vec<String> {
+        self.lock().frames.clone()
+    }
+
+    /// The most recently drawn frame, if any.
+    pub fn last_frame(&self) -> Option<String> {
+        self.lock().frames.last().cloned()
+    }
+
+    /// Everything printed outside of frames, such as the confirm or cancel message.
+    pub fn printed(&self) -> String {
+        self.lock().printed.clone()
+    }
+
+    pub(crate) fn record(&self, frame: String) {
+        self.lock().frames.push(frame);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Recording> {
+        self.recording
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
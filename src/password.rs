@@ -0,0 +1,116 @@
+use crate::error::TerMenuError;
+use crate::events::EventSource;
+use crate::input::{Echo, Input};
+use crate::output::Output;
+use crate::theme::Theme;
+use std::fmt;
+use zeroize::Zeroize;
+
+/// Asks for a password or token on the current thread, echoing `*` for each typed character.
+///
+/// A shorthand for [`Password::new`] with the default settings.
+///
+/// # Returns
+/// `Ok(Some(secret))` once the user pressed Enter, `Ok(None)` if they pressed Escape, or a
+/// [`TerMenuError`] if the terminal could not be driven or the user pressed Ctrl-C.
+///
+/// # Example
+/// ```no_run
+/// if let Some(token) = ter_menu::password("Token: ")? {
+///     println!("Read a token of {} characters", token.expose().len());
+/// }
+/// # Ok::<(), ter_menu::TerMenuError>(())
+/// ```
+pub fn password(prompt: &str) -> Result<Option<Secret>, TerMenuError> {
+    Password::new(prompt).run()
+}
+
+/// A single-line prompt for secrets, with the same editing keys as [`Input`].
+///
+/// The terminal is in raw mode while the user types, so the terminal itself never echoes the
+/// text; the prompt draws a mask character per typed character, or nothing at all with
+/// [`Password::hidden`]. If the output is not a terminal, e.g. under `app | tee log`, the prompt
+/// is drawn on stderr instead; only when stdin is not a terminal is the secret read as an
+/// ordinary line. The text being edited and the returned [`Secret`] are wiped from memory when
+/// dropped.
+pub struct Password<'a> {
+    input: Input<'a>,
+}
+
+impl<'a> Password<'a> {
+    /// Creates a prompt showing `prompt` in front of a `*` per typed character.
+    pub fn new(prompt: impl Into<String>) -> Self {
+        let mut input = Input::new(prompt);
+        input.echo = Echo::Mask('*');
+        Self { input }
+    }
+
+    /// Shows `mask` instead of each typed character.
+    pub fn mask(mut self, mask: char) -> Self {
+        self.input.echo = Echo::Mask(mask);
+        self
+    }
+
+    /// Shows nothing while the user types, not even how long the secret is.
+    pub fn hidden(mut self) -> Self {
+        self.input.echo = Echo::Hidden;
+        self
+    }
+
+    /// Checks the secret when the user presses Enter; see [`Input::validate`].
+    pub fn validate(mut self, validator: impl Fn(&str) -> Result<(), String> + 'a) -> Self {
+        self.input = self.input.validate(validator);
+        self
+    }
+
+    /// Sets the style of the prompt; see [`Input::theme`].
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.input = self.input.theme(theme);
+        self
+    }
+
+    /// Sets where the prompt is drawn. Defaults to [`Output::Stdout`].
+    pub fn output(mut self, output: Output) -> Self {
+        self.input = self.input.output(output);
+        self
+    }
+
+    /// Reads the input from `source` instead of the terminal.
+    pub fn events(mut self, source: impl EventSource + 'static) -> Self {
+        self.input = self.input.events(source);
+        self
+    }
+
+    /// Shows the prompt and blocks until the user submits or cancels.
+    ///
+    /// # Returns
+    /// `Ok(Some(secret))` with the accepted text, `Ok(None)` on Escape or at the end of input,
+    /// or a [`TerMenuError`] if the terminal could not be driven or the user pressed Ctrl-C.
+    pub fn run(self) -> Result<Option<Secret>, TerMenuError> {
+        Ok(self.input.run()?.map(Secret))
+    }
+}
+
+/// Text that is wiped from memory when dropped, such as a password read by [`password`].
+///
+/// Its `Debug` output never shows the text.
+pub struct Secret(String);
+
+impl Secret {
+    /// The secret text.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(..)")
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
@@ -0,0 +1,343 @@
+use crate::error::TerMenuError;
+use crate::events::{EventSource, SharedEvents};
+use crate::item::{ItemStyle, MenuEntry};
+use crate::menu::{Menu, MenuConfig};
+use crate::output::Output;
+use crate::theme::Theme;
+use crossterm::style::Color;
+use std::cell::OnceCell;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Browses the filesystem below a directory and returns the chosen file.
+///
+/// Each directory is a submenu: Enter opens it and Backspace (or Left, or Escape) goes back up,
+/// with the breadcrumb line showing the directories opened so far. Directories are listed first
+/// and read only when opened. Typing filters the listing of the open directory.
+///
+/// # Example
+/// ```no_run
+/// use ter_menu::PathPicker;
+///
+/// if let Some(path) = PathPicker::new(".").extensions(["toml", "yaml"]).run()? {
+///     println!("Loading {}", path.display());
+/// }
+/// # Ok::<(), ter_menu::TerMenuError>(())
+/// ```
+pub struct PathPicker {
+    root: PathBuf,
+    filter: FileFilter,
+    config: MenuConfig,
+}
+
+/// Which entries of a directory are listed.
+#[derive(Debug, Clone, Default)]
+struct FileFilter {
+    /// Extensions without the dot. Empty lists every file.
+    extensions: Vec<String>,
+    /// Pattern file names must match, with `*` and `?` wildcards.
+    glob: Option<String>,
+    /// Whether to list entries whose name starts with a dot.
+    show_hidden: bool,
+}
+
+impl PathPicker {
+    /// Creates a picker listing the files and directories in `root`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        Self {
+            config: MenuConfig {
+                prompt: format!("Select a file in {}:", root.display()),
+                filter: true,
+                confirm_message: String::new(),
+                cancel_message: String::new(),
+                ..MenuConfig::default()
+            },
+            root,
+            filter: FileFilter::default(),
+        }
+    }
+
+    /// Lists only files with one of `extensions`, given without the dot, e.g. `["rs", "toml"]`.
+    /// Extensions are compared ignoring ASCII case, so `"jpg"` also lists `PHOTO.JPG`.
+    /// Directories are always listed.
+    pub fn extensions<I>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.filter.extensions = extensions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Lists only files whose name matches `pattern`, where `*` stands for any run of characters
+    /// and `?` for a single one, e.g. `"*.test.*"`. Directories are always listed.
+    pub fn glob(mut self, pattern: impl Into<String>) -> Self {
+        self.filter.glob = Some(pattern.into());
+        self
+    }
+
+    /// Whether files and directories whose name starts with a dot are listed. Off by default.
+    pub fn show_hidden(mut self, show_hidden: bool) -> Self {
+        self.filter.show_hidden = show_hidden;
+        self
+    }
+
+    /// Sets the title line printed above the listing.
+    pub fn prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.config.prompt = prompt.into();
+        self
+    }
+
+    /// Sets the maximum number of entries to display at once. Without it, as many as fit in the
+    /// terminal are shown.
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.config.page_size = Some(page_size);
+        self
+    }
+
+    /// Sets the colors and glyphs used for drawing.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.config.theme = theme;
+        self
+    }
+
+    /// Sets where the picker is drawn. Defaults to [`Output::Stdout`].
+    pub fn output(mut self, output: Output) -> Self {
+        self.config.output = output;
+        self
+    }
+
+    /// Reads the input from `source` instead of the terminal.
+    pub fn events(mut self, source: impl EventSource + 'static) -> Self {
+        self.config.events = Some(SharedEvents::new(source));
+        self
+    }
+
+    /// Shows the picker on the current thread and blocks until the user decides.
+    ///
+    /// # Returns
+    /// `Ok(Some(path))` with the path of the chosen file, `Ok(None)` if the user cancelled, or a
+    /// [`TerMenuError`] if the terminal could not be driven or `root` could not be read.
+    pub fn run(self) -> Result<Option<PathBuf>, TerMenuError> {
+        let entries = list(&self.root, &Rc::new(self.filter))?;
+        let mut menu = Menu::new(&entries, self.config);
+        Ok(menu.run()?.map(|idx| menu.entry(idx).path.clone()))
+    }
+}
+
+/// A row of the listing.
+#[derive(Debug)]
+struct PathEntry {
+    path: PathBuf,
+    name: String,
+    kind: Kind,
+    filter: Rc<FileFilter>,
+    /// The directory's listing, read when it is first opened.
+    children: OnceCell<Vec<PathEntry>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    File,
+    Directory,
+    /// Stands in for the listing of a directory that is empty or could not be read, so that it
+    /// can still be opened and left again.
+    Note,
+}
+
+impl PathEntry {
+    fn new(path: PathBuf, name: String, kind: Kind, filter: &Rc<FileFilter>) -> Self {
+        Self {
+            path,
+            name,
+            kind,
+            filter: Rc::clone(filter),
+            children: OnceCell::new(),
+        }
+    }
+}
+
+impl MenuEntry for PathEntry {
+    fn label(&self) -> String {
+        match self.kind {
+            Kind::Directory => format!("{}/", self.name),
+            Kind::File | Kind::Note => self.name.clone(),
+        }
+    }
+
+    fn is_disabled(&self) -> bool {
+        self.kind == Kind::Note
+    }
+
+    fn style(&self) -> ItemStyle {
+        match self.kind {
+            Kind::Directory => ItemStyle::new().foreground(Color::Blue).bold(true),
+            Kind::File | Kind::Note => ItemStyle::new(),
+        }
+    }
+
+    fn children(&self) -> &[Self] {
+        if self.kind != Kind::Directory {
+            return &[];
+        }
+        self.children.get_or_init(|| {
+            let note = match list(&self.path, &self.filter) {
+                Ok(entries) if !entries.is_empty() => return entries,
+                Ok(_) => "(empty)".to_string(),
+                Err(e) => format!("(cannot read: {})", e),
+            };
+            vec![PathEntry::new(
+                self.path.clone(),
+                note,
+                Kind::Note,
+                &self.filter,
+            )]
+        })
+    }
+}
+
+/// The entries of `dir` that pass `filter`, directories first, each group sorted by name.
+fn list(dir: &Path, filter: &Rc<FileFilter>) -> io::Result<Vec<PathEntry>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with('.') && !filter.show_hidden {
+            continue;
+        }
+        let path = entry.path();
+        // Follows symlinks, so that links to directories can be opened.
+        let kind = if path.is_dir() {
+            Kind::Directory
+        } else if filter.accepts(&name) {
+            Kind::File
+        } else {
+            continue;
+        };
+        entries.push(PathEntry::new(path, name, kind, filter));
+    }
+    entries.sort_by(|a, b| {
+        (a.kind != Kind::Directory)
+            .cmp(&(b.kind != Kind::Directory))
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    Ok(entries)
+}
+
+impl FileFilter {
+    /// Whether a file called `name` is listed.
+    fn accepts(&self, name: &str) -> bool {
+        let extension_ok = self.extensions.is_empty()
+            || Path::new(name)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| {
+                    self.extensions
+                        .iter()
+                        .any(|allowed| ext.eq_ignore_ascii_case(allowed))
+                });
+        let glob_ok = self.glob.as_deref().is_none_or(|pattern| {
+            let pattern: Vec<char> = pattern.chars().collect();
+            let name: Vec<char> = name.chars().collect();
+            glob_match(&pattern, &name)
+        });
+        extension_ok && glob_ok
+    }
+}
+
+/// Whether `name` matches `pattern`, where `*` matches any run of characters and `?` any single
+/// character.
+///
+/// Only the most recent `*` is ever backtracked to, so matching takes at most
+/// `pattern.len() * name.len()` steps however many stars the pattern has.
+fn glob_match(pattern: &[char], name: &[char]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // The last `*` seen, and the position in `name` it currently stretches to.
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                // Let the star swallow one more character and try again after it.
+                Some((star_p, star_n)) => {
+                    star = Some((star_p, star_n + 1));
+                    p = star_p + 1;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glob(pattern: &str, name: &str) -> bool {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let name: Vec<char> = name.chars().collect();
+        glob_match(&pattern, &name)
+    }
+
+    #[test]
+    fn glob_wildcards() {
+        assert!(glob("*.rs", "main.rs"));
+        assert!(glob("*.rs", ".rs"));
+        assert!(!glob("*.rs", "main.rsx"));
+        assert!(glob("*.test.*", "parser.test.ts"));
+        assert!(!glob("*.test.*", "parser.ts"));
+        assert!(glob("?.txt", "a.txt"));
+        assert!(!glob("?.txt", ".txt"));
+        assert!(glob("日?.md", "日本.md"));
+        assert!(glob("**", ""));
+        assert!(!glob("", "a"));
+        assert!(glob("a*b*c", "a_b_b_c"));
+        assert!(!glob("a*b*c", "a_c_b"));
+    }
+
+    #[test]
+    fn glob_with_many_stars_fails_quickly() {
+        let name = "a".repeat(200);
+        let pattern = format!("{}b", "*a".repeat(20));
+        assert!(!glob(&pattern, &name));
+        assert!(glob(&pattern, &format!("{}b", name)));
+    }
+
+    #[test]
+    fn extensions_ignore_case() {
+        let filter = FileFilter {
+            extensions: vec!["jpg".to_string(), "PNG".to_string()],
+            ..FileFilter::default()
+        };
+        assert!(filter.accepts("photo.jpg"));
+        assert!(filter.accepts("PHOTO.JPG"));
+        assert!(filter.accepts("icon.png"));
+        assert!(!filter.accepts("photo.jpeg"));
+        assert!(!filter.accepts("jpg"));
+    }
+
+    #[test]
+    fn extensions_and_glob_must_both_pass() {
+        let filter = FileFilter {
+            extensions: vec!["rs".to_string()],
+            glob: Some("*_test.*".to_string()),
+            ..FileFilter::default()
+        };
+        assert!(filter.accepts("menu_test.rs"));
+        assert!(!filter.accepts("menu.rs"));
+        assert!(!filter.accepts("menu_test.py"));
+        assert!(FileFilter::default().accepts("anything"));
+    }
+}
@@ -0,0 +1,49 @@
+use crate::render::Line;
+use crossterm::style::{Attribute, ContentStyle};
+
+/// Where a menu's preview pane is drawn.
+///
+/// # Variants
+/// * `Right` - In the right half of the terminal, next to the items.
+/// * `Bottom(n)` - In `n` lines below the items.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PreviewLayout {
+    #[default]
+    Right,
+    Bottom(usize),
+}
+
+/// Splits preview text into display lines, expanding tabs so that column counts stay right.
+fn preview_lines(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.lines().map(|line| line.replace('\t', "    "))
+}
+
+fn dimmed() -> ContentStyle {
+    let mut style = ContentStyle::new();
+    style.attributes.set(Attribute::Dim);
+    style
+}
+
+/// Draws `text` to the right of `rows`, which are cut or padded to `list_width` columns first.
+/// Preview lines beyond the number of rows are dropped.
+pub(crate) fn attach_right(rows: &mut [Line], text: &str, list_width: usize) {
+    let mut lines = preview_lines(text);
+    for row in rows {
+        row.truncate(list_width);
+        row.pad(list_width, ContentStyle::new());
+        row.push(" │ ", dimmed());
+        if let Some(line) = lines.next() {
+            row.push(line, ContentStyle::new());
+        }
+    }
+}
+
+/// Builds the lines of a preview pane `height` lines tall, under a rule `width` columns wide.
+/// Short previews are padded with blank lines so the menu does not change height as the
+/// highlight moves.
+pub(crate) fn below(text: &str, height: usize, width: usize) -> Vec<Line> {
+    let mut lines = vec![Line::styled("─".repeat(width), dimmed())];
+    lines.extend(preview_lines(text).take(height).map(Line::plain));
+    lines.resize(height + 1, Line::default());
+    lines
+}
@@ -0,0 +1,494 @@
+use crate::output::FrameRecorder;
+use crossterm::cursor::{self, MoveTo, MoveToPreviousLine, RestorePosition, SavePosition};
+use crossterm::queue;
+use crossterm::style::{ContentStyle, Print, PrintStyledContent, StyledContent};
+use crossterm::terminal::{Clear, ClearType};
+use std::io::{self, Write};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Marks text that was cut off to fit the terminal.
+const ELLIPSIS: &str = "…";
+
+/// A run of text drawn with a single style.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Span {
+    pub(crate) text: String,
+    pub(crate) style: ContentStyle,
+}
+
+/// One terminal row of a frame.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Line {
+    pub(crate) spans: Vec<Span>,
+}
+
+impl Line {
+    /// A line of unstyled text.
+    pub(crate) fn plain(text: impl Into<String>) -> Self {
+        Self::styled(text, ContentStyle::new())
+    }
+
+    /// A line of text drawn with a single style.
+    pub(crate) fn styled(text: impl Into<String>, style: ContentStyle) -> Self {
+        let mut line = Self::default();
+        line.push(text, style);
+        line
+    }
+
+    /// Appends `text` drawn with `style`.
+    pub(crate) fn push(&mut self, text: impl Into<String>, style: ContentStyle) {
+        self.spans.push(Span {
+            text: text.into(),
+            style,
+        });
+    }
+
+    /// Number of terminal columns the line takes up. Wide characters such as CJK and most emoji
+    /// count as two columns.
+    pub(crate) fn width(&self) -> usize {
+        self.spans.iter().map(|span| span.text.width()).sum()
+    }
+
+    /// Appends spaces drawn with `style` until the line is `width` columns wide.
+    pub(crate) fn pad(&mut self, width: usize, style: ContentStyle) {
+        let missing = width.saturating_sub(self.width());
+        if missing > 0 {
+            self.push(" ".repeat(missing), style);
+        }
+    }
+
+    /// Queues the styled text of the line, without a line break.
+    pub(crate) fn queue<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        for span in &self.spans {
+            queue!(
+                out,
+                PrintStyledContent(StyledContent::new(span.style, span.text.as_str()))
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Shortens the line to at most `max_width` columns, ending it with `…` if anything was cut.
+    /// The ellipsis takes the style of the text it replaces.
+    pub(crate) fn truncate(&mut self, max_width: usize) {
+        if self.width() <= max_width {
+            return;
+        }
+        let budget = max_width.saturating_sub(ELLIPSIS.width());
+        let mut used = 0;
+        let mut kept = Vec::new();
+        for span in self.spans.drain(..) {
+            let mut text = String::new();
+            let mut cut = false;
+            for c in span.text.chars() {
+                let width = c.width().unwrap_or(0);
+                if used + width > budget {
+                    cut = true;
+                    break;
+                }
+                used += width;
+                text.push(c);
+            }
+            if cut {
+                if max_width > 0 {
+                    text.push_str(ELLIPSIS);
+                }
+                kept.push(Span {
+                    text,
+                    style: span.style,
+                });
+                break;
+            }
+            kept.push(Span {
+                text,
+                style: span.style,
+            });
+        }
+        self.spans = kept;
+    }
+
+    /// Splits the line into lines of at most `width` columns, breaking after the last space that
+    /// fits, or inside words longer than a line. The lines after the first start with `indent`
+    /// columns of space drawn in the style of the first character, so that the text continues
+    /// below where it started; spaces within the indent are not broken at. Lines that fit, and
+    /// widths that leave no room past the indent, give the line back unchanged.
+    pub(crate) fn wrap(self, width: usize, indent: usize) -> Vec<Line> {
+        if self.width() <= width || width <= indent {
+            return vec![self];
+        }
+        let cells: Vec<(char, ContentStyle)> = self
+            .spans
+            .iter()
+            .flat_map(|span| span.text.chars().map(move |c| (c, span.style)))
+            .collect();
+        let indent_style = cells
+            .first()
+            .map_or(ContentStyle::new(), |&(_, style)| style);
+        let mut lines = Vec::new();
+        let mut current: Vec<(char, ContentStyle)> = Vec::new();
+        let mut used = 0;
+        // Index in `current` of the last space a line may break at.
+        let mut last_space = None;
+        for cell in cells {
+            let cell_width = cell.0.width().unwrap_or(0);
+            if used + cell_width > width {
+                let carried = match last_space {
+                    Some(space) => {
+                        let carried = current.split_off(space + 1);
+                        current.pop();
+                        carried
+                    }
+                    None => Vec::new(),
+                };
+                lines.push(Line::from_cells(&current));
+                current = vec![(' ', indent_style); indent];
+                current.extend(carried);
+                used = current.iter().map(|(c, _)| c.width().unwrap_or(0)).sum();
+                last_space = None;
+            }
+            if cell.0 == ' ' && used >= indent {
+                last_space = Some(current.len());
+            }
+            used += cell_width;
+            current.push(cell);
+        }
+        lines.push(Line::from_cells(&current));
+        lines
+    }
+
+    /// A line of the characters in `cells`, each in its style.
+    fn from_cells(cells: &[(char, ContentStyle)]) -> Line {
+        let mut line = Line::default();
+        for &(c, style) in cells {
+            match line.spans.last_mut() {
+                Some(span) if span.style == style => span.text.push(c),
+                _ => line.push(c, style),
+            }
+        }
+        line
+    }
+}
+
+/// Everything a menu draws for one state, independent of where it ends up on the terminal.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Frame {
+    pub(crate) lines: Vec<Line>,
+}
+
+impl Frame {
+    pub(crate) fn push(&mut self, line: Line) {
+        self.lines.push(line);
+    }
+
+    /// Queues every line, each followed by a carriage return and line feed.
+    ///
+    /// The explicit carriage return makes lines start at the left edge while the terminal is in
+    /// raw mode, where a bare line feed only moves the cursor down.
+    pub(crate) fn queue<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        for line in &self.lines {
+            line.queue(out)?;
+            queue!(out, Print("\r\n"))?;
+        }
+        Ok(())
+    }
+
+    /// The text of every line without any styling, separated by `\n`.
+    pub(crate) fn plain_text(&self) -> String {
+        let lines: Vec<String> = self
+            .lines
+            .iter()
+            .map(|line| line.spans.iter().map(|span| span.text.as_str()).collect())
+            .collect();
+        lines.join("\n")
+    }
+
+    /// The text of every line with its styling as ANSI escape sequences, separated by `\n`.
+    pub(crate) fn ansi_text(&self) -> String {
+        let lines: Vec<String> = self
+            .lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| StyledContent::new(span.style, span.text.as_str()).to_string())
+                    .collect()
+            })
+            .collect();
+        lines.join("\n")
+    }
+
+    /// Writes the text of every line without any styling or other escape sequences.
+    fn write_plain<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        for line in &self.lines {
+            for span in &line.spans {
+                out.write_all(span.text.as_bytes())?;
+            }
+            out.write_all(b"\r\n")?;
+        }
+        Ok(())
+    }
+}
+
+/// Draws menu frames in place of the previous one.
+#[derive(Debug)]
+pub(crate) enum Renderer {
+    /// Draws from the top-left corner of the screen, rewriting only the rows that changed.
+    FullScreen(ScreenRenderer),
+    /// Draws below the cursor and rewinds over the previous frame.
+    Inline(InlineRenderer),
+    /// Draws at a fixed position over the screen contents, and puts them back afterwards.
+    Popup(PopupRenderer),
+    /// Prints every frame below the previous one as plain text, for terminals that do not
+    /// understand escape sequences.
+    Plain,
+    /// Hands every frame to a recorder instead of drawing it.
+    Record(FrameRecorder),
+}
+
+impl Renderer {
+    /// Replaces the previously drawn frame with `frame`.
+    pub(crate) fn draw<W: Write>(&mut self, out: &mut W, frame: &Frame) -> io::Result<()> {
+        match self {
+            Renderer::FullScreen(screen) => screen.draw(out, frame)?,
+            Renderer::Inline(inline) => inline.draw(out, frame)?,
+            Renderer::Popup(popup) => popup.draw(out, frame)?,
+            Renderer::Plain => {
+                out.write_all(b"\r\n")?;
+                frame.write_plain(out)?;
+            }
+            Renderer::Record(recorder) => recorder.record(frame.plain_text()),
+        }
+        out.flush()
+    }
+
+    /// Removes the menu from the screen once the interaction is over. Only inline menus and
+    /// popups clean up after themselves; full-screen and plain menus are left for the
+    /// confirm/cancel message to follow.
+    pub(crate) fn finish<W: Write>(&mut self, out: &mut W) -> io::Result<()> {
+        match self {
+            Renderer::FullScreen(_) | Renderer::Plain | Renderer::Record(_) => Ok(()),
+            Renderer::Inline(inline) => {
+                inline.erase(out)?;
+                out.flush()
+            }
+            Renderer::Popup(popup) => {
+                popup.erase(out)?;
+                out.flush()
+            }
+        }
+    }
+
+    /// Terminal row on which the first line of the current frame is drawn.
+    pub(crate) fn origin_row(&self) -> u16 {
+        match self {
+            Renderer::FullScreen(_) | Renderer::Plain | Renderer::Record(_) => 0,
+            Renderer::Inline(inline) => inline.origin_row,
+            Renderer::Popup(popup) => popup.row,
+        }
+    }
+
+    /// Terminal column at which every line of the current frame starts.
+    pub(crate) fn origin_column(&self) -> u16 {
+        match self {
+            Renderer::Popup(popup) => popup.column,
+            _ => 0,
+        }
+    }
+}
+
+/// Draws frames at the top of the screen.
+///
+/// The first frame clears the screen. After that only the rows that differ from the previous
+/// frame are rewritten, usually just the old and new highlighted rows, which avoids the flicker
+/// of clearing and reprinting everything on every key press over slow connections.
+#[derive(Debug, Default)]
+pub(crate) struct ScreenRenderer {
+    /// The frame currently on screen, or `None` if the screen must be cleared first.
+    previous: Option<Frame>,
+}
+
+impl ScreenRenderer {
+    /// Forgets what is on screen, so that the next frame clears it and draws everything, e.g.
+    /// after the terminal was resized and may have reflowed its contents.
+    pub(crate) fn invalidate(&mut self) {
+        self.previous = None;
+    }
+
+    fn draw<W: Write>(&mut self, out: &mut W, frame: &Frame) -> io::Result<()> {
+        let Some(previous) = &self.previous else {
+            // Clear screen and reset cursor position
+            queue!(out, Clear(ClearType::All), MoveTo(0, 0))?;
+            frame.queue(out)?;
+            self.previous = Some(frame.clone());
+            return Ok(());
+        };
+        for (row, line) in frame.lines.iter().enumerate() {
+            if previous.lines.get(row) == Some(line) {
+                continue;
+            }
+            queue!(out, MoveTo(0, row as u16))?;
+            line.queue(out)?;
+            queue!(out, Clear(ClearType::UntilNewLine))?;
+        }
+        // Leave the cursor below the frame, where a full redraw would have left it.
+        queue!(out, MoveTo(0, frame.lines.len() as u16))?;
+        if frame.lines.len() < previous.lines.len() {
+            queue!(out, Clear(ClearType::FromCursorDown))?;
+        }
+        self.previous = Some(frame.clone());
+        Ok(())
+    }
+}
+
+/// Draws frames below the cursor position at which the menu was opened.
+///
+/// Remembers how many lines the last frame took so that the next one can move back up and
+/// overwrite it, leaving everything above the menu untouched. [`InlineRenderer::erase`] removes
+/// the menu entirely, so the caller can collapse it into a one-line summary.
+#[derive(Debug, Default)]
+pub(crate) struct InlineRenderer {
+    lines: usize,
+    origin_row: u16,
+    /// Whether to query the cursor position after each frame to keep `origin_row` accurate.
+    /// Only needed to map mouse clicks to rows, and costs a terminal round trip.
+    track_origin: bool,
+}
+
+impl InlineRenderer {
+    pub(crate) fn new(track_origin: bool) -> Self {
+        Self {
+            track_origin,
+            ..Self::default()
+        }
+    }
+
+    fn draw<W: Write>(&mut self, out: &mut W, frame: &Frame) -> io::Result<()> {
+        self.erase(out)?;
+        frame.queue(out)?;
+        self.lines = frame.lines.len();
+        out.flush()?;
+        // Printing at the bottom of the screen scrolls, so measure where the frame ended up.
+        if self.track_origin
+            && let Ok((_, row)) = cursor::position()
+        {
+            self.origin_row = row.saturating_sub(self.lines as u16);
+        }
+        Ok(())
+    }
+
+    /// Moves back to the first line of the previous frame and clears everything below it.
+    fn erase<W: Write>(&mut self, out: &mut W) -> io::Result<()> {
+        if self.lines > 0 {
+            queue!(out, MoveToPreviousLine(self.lines as u16))?;
+        }
+        queue!(out, Clear(ClearType::FromCursorDown))?;
+        self.lines = 0;
+        Ok(())
+    }
+}
+
+/// Draws frames with their top-left corner at a fixed cell, over whatever is on screen, for
+/// context menus inside a larger TUI.
+///
+/// Nothing outside the cells of the frame is touched. Terminals cannot report what they show, so
+/// the cells a frame covers are restored from a copy of the screen handed in by the caller,
+/// `backdrop`, once a smaller frame or the end of the interaction uncovers them. Cells the copy
+/// does not reach are blanked.
+#[derive(Debug)]
+pub(crate) struct PopupRenderer {
+    row: u16,
+    column: u16,
+    /// The text of the screen, one string per terminal row.
+    backdrop: Vec<String>,
+    /// Width of each line of the frame currently on screen, or `None` before the first frame.
+    covered: Option<Vec<usize>>,
+}
+
+impl PopupRenderer {
+    pub(crate) fn new(row: u16, column: u16, backdrop: Vec<String>) -> Self {
+        Self {
+            row,
+            column,
+            backdrop,
+            covered: None,
+        }
+    }
+
+    fn draw<W: Write>(&mut self, out: &mut W, frame: &Frame) -> io::Result<()> {
+        // Put the cursor back where the caller left it once the popup closes.
+        let covered = match self.covered.take() {
+            Some(covered) => covered,
+            None => {
+                queue!(out, SavePosition)?;
+                Vec::new()
+            }
+        };
+        for (i, line) in frame.lines.iter().enumerate() {
+            queue!(out, MoveTo(self.column, self.row + i as u16))?;
+            line.queue(out)?;
+            let width = line.width();
+            if let Some(&old) = covered.get(i).filter(|&&old| old > width) {
+                self.restore(out, i, width, old - width)?;
+            }
+        }
+        for (i, &old) in covered.iter().enumerate().skip(frame.lines.len()) {
+            self.restore(out, i, 0, old)?;
+        }
+        self.covered = Some(frame.lines.iter().map(Line::width).collect());
+        Ok(())
+    }
+
+    /// Puts back every cell the current frame covers and returns the cursor to where it was.
+    fn erase<W: Write>(&mut self, out: &mut W) -> io::Result<()> {
+        let Some(covered) = self.covered.take() else {
+            return Ok(());
+        };
+        for (i, &width) in covered.iter().enumerate() {
+            self.restore(out, i, 0, width)?;
+        }
+        queue!(out, RestorePosition)
+    }
+
+    /// Redraws `width` cells of the backdrop on the `line`th row of the popup, starting `from`
+    /// columns into it.
+    fn restore<W: Write>(
+        &self,
+        out: &mut W,
+        line: usize,
+        from: usize,
+        width: usize,
+    ) -> io::Result<()> {
+        let text = self
+            .backdrop
+            .get(usize::from(self.row) + line)
+            .map_or("", String::as_str);
+        let start = usize::from(self.column) + from;
+        queue!(
+            out,
+            MoveTo(start as u16, self.row + line as u16),
+            Print(cells(text, start, width))
+        )
+    }
+}
+
+/// The `width` columns of `text` starting at column `start`, padded with spaces. Wide
+/// characters cut in half by either edge are replaced by spaces.
+fn cells(text: &str, start: usize, width: usize) -> String {
+    let end = start + width;
+    let mut cut = String::new();
+    let mut column = 0;
+    for c in text.chars() {
+        if column >= end {
+            break;
+        }
+        let next = column + c.width().unwrap_or(0);
+        if column >= start && next <= end {
+            cut.push(c);
+        } else if next > start {
+            cut.push_str(&" ".repeat(next.min(end) - column.max(start)));
+        }
+        column = next;
+    }
+    let padding = width.saturating_sub(cut.width());
+    cut + &" ".repeat(padding)
+}
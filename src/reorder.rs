@@ -0,0 +1,94 @@
+use crate::error::TerMenuError;
+use crate::events::{EventSource, SharedEvents};
+use crate::item::MenuEntry;
+use crate::menu::{Menu, MenuConfig};
+use crate::output::Output;
+use crate::theme::Theme;
+
+/// Lets the user put a list of items in order and returns them in that order.
+///
+/// Up and Down move the highlight as usual, Shift+Up and Shift+Down move the highlighted item
+/// itself, and Enter accepts the new order. Meant for playlists, rebase todo lists and other
+/// lists whose order matters.
+///
+/// # Example
+/// ```no_run
+/// use ter_menu::Reorder;
+///
+/// let commits = vec!["fix typo", "add parser", "add tests"];
+/// if let Some(commits) = Reorder::new(commits).prompt("Order the commits:").run()? {
+///     println!("Applying {:?}", commits);
+/// }
+/// # Ok::<(), ter_menu::TerMenuError>(())
+/// ```
+pub struct Reorder<T> {
+    items: Vec<T>,
+    config: MenuConfig,
+}
+
+impl<T: MenuEntry> Reorder<T> {
+    /// Creates a list of `items` in their current order.
+    pub fn new(items: Vec<T>) -> Self {
+        Self {
+            items,
+            config: MenuConfig {
+                prompt: "Reorder the items:".to_string(),
+                reorder: true,
+                confirm_message: String::new(),
+                cancel_message: String::new(),
+                ..MenuConfig::default()
+            },
+        }
+    }
+
+    /// Sets the title line printed above the items.
+    pub fn prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.config.prompt = prompt.into();
+        self
+    }
+
+    /// Sets the maximum number of items to display at once. Without it, as many as fit in the
+    /// terminal are shown.
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.config.page_size = Some(page_size);
+        self
+    }
+
+    /// Sets the colors and glyphs used for drawing.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.config.theme = theme;
+        self
+    }
+
+    /// Sets where the list is drawn. Defaults to [`Output::Stdout`].
+    pub fn output(mut self, output: Output) -> Self {
+        self.config.output = output;
+        self
+    }
+
+    /// Reads the input from `source` instead of the terminal.
+    pub fn events(mut self, source: impl EventSource + 'static) -> Self {
+        self.config.events = Some(SharedEvents::new(source));
+        self
+    }
+
+    /// Shows the list on the current thread and blocks until the user decides.
+    ///
+    /// # Returns
+    /// `Ok(Some(items))` with every item in the order the user left them in, `Ok(None)` if the
+    /// user cancelled, or a [`TerMenuError`] if the terminal could not be driven.
+    pub fn run(self) -> Result<Option<Vec<T>>, TerMenuError> {
+        let mut menu = Menu::new(&self.items, self.config);
+        if menu.run()?.is_none() {
+            return Ok(None);
+        }
+        let order = menu.display_order().to_vec();
+        let mut items: Vec<Option<T>> = self.items.into_iter().map(Some).collect();
+        Ok(Some(
+            order
+                .into_iter()
+                .filter_map(|idx| items[idx].take())
+                .collect(),
+        ))
+    }
+}
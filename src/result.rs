@@ -0,0 +1,105 @@
+use crate::error::TerMenuError;
+use std::fmt::Display;
+use std::process::ExitCode;
+
+/// The outcome of a dropdown interaction, returned by [`TerminalDropDown::wait`].
+///
+/// # Variants
+/// * `Selected` - The user confirmed an item with Enter. Carries a clone of the chosen item.
+/// * `Cancelled` - The user pressed Escape, or there were no options to choose from.
+/// * `Interrupted` - The user pressed Ctrl-C. The terminal has already been restored, so the
+///   caller can exit right away.
+/// * `Error` - The interaction could not be completed (terminal failure, or a panicking or
+///   failing callback). Carries the [`TerMenuError`] describing the failure.
+#[derive(Debug)]
+pub enum SelectionResult<T> {
+    Selected(T),
+    Cancelled,
+    Interrupted,
+    Error(TerMenuError),
+}
+
+impl<T> SelectionResult<T> {
+    /// Returns `true` if the user confirmed an item.
+    pub fn is_selected(&self) -> bool {
+        matches!(self, SelectionResult::Selected(_))
+    }
+
+    /// Returns `true` if the user cancelled the interaction.
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, SelectionResult::Cancelled)
+    }
+
+    /// Returns `true` if the user pressed Ctrl-C.
+    pub fn is_interrupted(&self) -> bool {
+        matches!(self, SelectionResult::Interrupted)
+    }
+
+    /// Converts the result into a `Result`, mapping cancellation to `Ok(None)` and an interrupt
+    /// to `Err(TerMenuError::Interrupted)`.
+    pub fn into_result(self) -> Result<Option<T>, TerMenuError> {
+        match self {
+            SelectionResult::Selected(item) => Ok(Some(item)),
+            SelectionResult::Cancelled => Ok(None),
+            SelectionResult::Interrupted => Err(TerMenuError::Interrupted),
+            SelectionResult::Error(e) => Err(e),
+        }
+    }
+
+    /// Maps the selected item with `f`, leaving the other outcomes untouched.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> SelectionResult<U> {
+        match self {
+            SelectionResult::Selected(item) => SelectionResult::Selected(f(item)),
+            SelectionResult::Cancelled => SelectionResult::Cancelled,
+            SelectionResult::Interrupted => SelectionResult::Interrupted,
+            SelectionResult::Error(e) => SelectionResult::Error(e),
+        }
+    }
+
+    /// Converts the result into the selected item, discarding cancellation and error details.
+    pub fn selected(self) -> Option<T> {
+        match self {
+            SelectionResult::Selected(item) => Some(item),
+            _ => None,
+        }
+    }
+
+    /// The exit code a command-line tool reports for the outcome, following fzf: `0` for a
+    /// selection, `1` for a cancelled menu, `130` for Ctrl-C and `2` for an error.
+    pub fn exit_code(&self) -> ExitCode {
+        ExitCode::from(match self {
+            SelectionResult::Selected(_) => 0,
+            SelectionResult::Cancelled => 1,
+            SelectionResult::Interrupted => 130,
+            SelectionResult::Error(_) => 2,
+        })
+    }
+
+    /// Ends a shell-pipeline tool: prints the selected item on a line of its own to standard
+    /// output, or the error to standard error, and returns the [`exit_code`](Self::exit_code)
+    /// for `main` to return. See
+    /// [`DropDownBuilder::shell_mode`](crate::DropDownBuilder::shell_mode).
+    pub fn print_selected(self) -> ExitCode
+    where
+        T: Display,
+    {
+        let code = self.exit_code();
+        match self {
+            SelectionResult::Selected(item) => println!("{}", item),
+            SelectionResult::Error(e) => eprintln!("{}", e),
+            SelectionResult::Cancelled | SelectionResult::Interrupted => {}
+        }
+        code
+    }
+}
+
+impl<T> From<TerMenuError> for SelectionResult<T> {
+    /// Wraps `e` as `Error`, except for [`TerMenuError::Interrupted`], which becomes
+    /// `Interrupted`.
+    fn from(e: TerMenuError) -> Self {
+        match e {
+            TerMenuError::Interrupted => SelectionResult::Interrupted,
+            e => SelectionResult::Error(e),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use crate::drop_down::{AfterFailure, after_failure, cause};
+use crate::error::TerMenuError;
+use crate::hooks::{FinishHook, Hooks, TryCallback};
+use crate::item::{MenuEntry, follow};
+use crate::menu::{Menu, MenuConfig};
+use crate::result::SelectionResult;
+use std::collections::HashMap;
+
+/// A dropdown that can be presented any number of times.
+///
+/// [`TerminalDropDown`](crate::TerminalDropDown) consumes its `FnOnce` callbacks and is finished
+/// after one interaction. `ReusableDropDown` keeps its items and `FnMut` callbacks, and runs each
+/// interaction on the calling thread via [`ReusableDropDown::show`], which makes it suitable for
+/// main-menu style loops.
+///
+/// # Type Parameters
+/// * `T` - The type of items in the dropdown.
+/// * `F` - The type of callback function triggered when an item is selected. Called once per
+///   confirmed selection with a reference to the item.
+pub struct ReusableDropDown<T, F>
+where
+    T: MenuEntry + Clone,
+    F: FnMut(&T),
+{
+    options: Vec<T>,
+    callbacks: Vec<F>,
+    /// The callbacks of items added with [`try_item`](crate::DropDownBuilder::try_item), by
+    /// index into `options`, run in place of theirs.
+    fallible: HashMap<usize, TryCallback<T>>,
+    /// Produces the status line printed in place of the confirm message.
+    finish: Option<FinishHook<T>>,
+    config: MenuConfig,
+    hooks: Hooks<T>,
+}
+
+impl<T, F> ReusableDropDown<T, F>
+where
+    T: MenuEntry + Clone,
+    F: FnMut(&T),
+{
+    /// Creates a reusable dropdown whose items are rendered in the given order.
+    ///
+    /// # Parameters
+    /// * `drop_down` - A Vec of `(item, callback)` pairs.
+    /// * `item_n` - Maximum number of items to display in the terminal at once.
+    pub fn new(drop_down: Vec<(T, F)>, item_n: usize) -> Self {
+        Self::with_config(
+            drop_down,
+            HashMap::new(),
+            MenuConfig::new(item_n),
+            Hooks::default(),
+        )
+    }
+
+    pub(crate) fn with_config(
+        drop_down: Vec<(T, F)>,
+        fallible: HashMap<usize, TryCallback<T>>,
+        mut config: MenuConfig,
+        mut hooks: Hooks<T>,
+    ) -> Self {
+        let (options, callbacks) = drop_down.into_iter().unzip();
+        let finish = hooks.finish_message.take();
+        if finish.is_some() {
+            // The status line takes the place of the confirm message.
+            config.confirm_message.clear();
+        }
+        Self {
+            options,
+            callbacks,
+            fallible,
+            finish,
+            config,
+            hooks,
+        }
+    }
+
+    /// Shows the dropdown on the current thread and blocks until the user decides.
+    ///
+    /// # Returns
+    /// `SelectionResult::Selected` with a clone of the confirmed item after its callback has run
+    /// (for an item inside a submenu, the callback of the top-level item it belongs to),
+    /// `Cancelled` on Escape, `Interrupted` on Ctrl-C, or `Error` if the terminal could not be
+    /// driven or a [`try_item`](crate::DropDownBuilder::try_item) callback failed. Failed
+    /// callbacks are offered for retrying as in a one-shot dropdown if
+    /// [`retry_on_error`](crate::DropDownBuilder::retry_on_error) is set. The dropdown stays
+    /// usable afterwards and can be shown again.
+    pub fn show(&mut self) -> SelectionResult<T> {
+        let mut menu = Menu::new(&self.options, self.config.clone()).with_hooks(&mut self.hooks);
+        loop {
+            let path = match menu.run() {
+                Ok(Some(idx)) => menu.path_to(idx),
+                Ok(None) => return SelectionResult::Cancelled,
+                Err(e) => return e.into(),
+            };
+            let item = *follow(&self.options, &path)
+                .last()
+                .expect("a selection path is never empty");
+            let decided = loop {
+                let result = match self.fallible.get_mut(&path[0]) {
+                    Some(func) => func(item).map_err(TerMenuError::Callback),
+                    None => {
+                        (self.callbacks[path[0]])(item);
+                        Ok(())
+                    }
+                };
+                let Err(e) = result else {
+                    break Ok(false);
+                };
+                if !self.config.retry_prompt {
+                    break Err(e);
+                }
+                match after_failure(&self.config, &e) {
+                    Ok(AfterFailure::Retry) => {}
+                    Ok(AfterFailure::PickAnother) => break Ok(true),
+                    Ok(AfterFailure::Abort) => break Err(e),
+                    Err(e) => return e.into(),
+                }
+            };
+            let status = match &decided {
+                Ok(true) => {
+                    menu.reopen();
+                    continue;
+                }
+                Ok(false) => self.finish.as_ref().map(|finish| finish(item, Ok(()))),
+                Err(e) => self
+                    .finish
+                    .as_ref()
+                    .map(|finish| finish(item, Err(cause(e)))),
+            };
+            if let Some(status) = status
+                && let Err(e) = menu.print_status(status)
+            {
+                return TerMenuError::from(e).into();
+            }
+            return match decided {
+                Err(e) => e.into(),
+                Ok(_) => SelectionResult::Selected(item.clone()),
+            };
+        }
+    }
+
+    /// Items in display order.
+    pub fn items(&self) -> &[T] {
+        &self.options
+    }
+}
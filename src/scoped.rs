@@ -0,0 +1,117 @@
+use crate::cancel::CancelHandle;
+use crate::drop_down::outcome;
+use crate::error::TerMenuError;
+use crate::item::{MenuEntry, follow};
+use crate::menu::{Menu, MenuConfig};
+use crate::result::SelectionResult;
+use std::panic::{self, AssertUnwindSafe};
+use std::thread::{self, Scope, ScopedJoinHandle};
+
+/// Shows a dropdown over items borrowed from the caller on a thread of its own, and runs `f`
+/// alongside it.
+///
+/// [`TerminalDropDown`](crate::TerminalDropDown) needs `'static` items because its thread may
+/// outlive the caller. Like [`std::thread::scope`], `select_scoped` does not return before the
+/// menu has closed, so the items can live on the caller's stack and the confirmed one is handed
+/// back by reference rather than cloned.
+///
+/// # Parameters
+/// * `options` - Slice of items to choose from, rendered in the given order.
+/// * `item_n` - Maximum number of items to display in the terminal at once.
+/// * `f` - Receives a [`ScopedDropDown`] for waiting on or cancelling the menu while it is open.
+///
+/// # Returns
+/// Whatever `f` returns. If `f` returns without calling [`ScopedDropDown::wait`], the call still
+/// blocks until the user decides, and the outcome is discarded.
+///
+/// # Example
+/// ```no_run
+/// use ter_menu::select_scoped;
+///
+/// let branches = vec![String::from("main"), String::from("develop")];
+/// let chosen = select_scoped(&branches, 5, |sel| sel.wait().selected());
+/// if let Some(branch) = chosen {
+///     println!("Checking out {}", branch);
+/// }
+/// ```
+pub fn select_scoped<'env, T, R>(
+    options: &'env [T],
+    item_n: usize,
+    f: impl for<'scope> FnOnce(ScopedDropDown<'scope, 'env, T>) -> R,
+) -> R
+where
+    T: MenuEntry + Sync,
+{
+    thread::scope(|scope| f(ScopedDropDown::spawn(scope, options, item_n)))
+}
+
+/// A dropdown running inside [`select_scoped`].
+///
+/// Borrows its items for `'env`, the caller's stack frame, and lives no longer than `'scope`, the
+/// call to `select_scoped`.
+#[derive(Debug)]
+pub struct ScopedDropDown<'scope, 'env: 'scope, T> {
+    options: &'env [T],
+    /// Produces the path to the confirmed item.
+    handle: ScopedJoinHandle<'scope, SelectionResult<Vec<usize>>>,
+    cancel: CancelHandle,
+}
+
+impl<'scope, 'env, T> ScopedDropDown<'scope, 'env, T>
+where
+    T: MenuEntry + Sync,
+{
+    fn spawn(scope: &'scope Scope<'scope, 'env>, options: &'env [T], item_n: usize) -> Self {
+        let cancel = CancelHandle::default();
+        let worker_cancel = cancel.clone();
+        let handle = scope.spawn(move || {
+            let selection = panic::catch_unwind(AssertUnwindSafe(|| {
+                let mut menu =
+                    Menu::new(options, MenuConfig::new(item_n)).with_cancel(worker_cancel);
+                Ok::<_, TerMenuError>(menu.run()?.map(|idx| menu.path_to(idx)))
+            }));
+            match selection {
+                Ok(Ok(Some(path))) => SelectionResult::Selected(path),
+                Ok(Ok(None)) => SelectionResult::Cancelled,
+                Ok(Err(e)) => e.into(),
+                Err(payload) => SelectionResult::Error(TerMenuError::from_panic(payload)),
+            }
+        });
+        Self {
+            options,
+            handle,
+            cancel,
+        }
+    }
+
+    /// Closes the menu as if the user had pressed Escape. Does nothing if the interaction has
+    /// already finished.
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    /// Returns a [`CancelHandle`] for closing the menu from another thread.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+
+    /// Returns `true` once the interaction is over, so that [`wait`](ScopedDropDown::wait)
+    /// would not block.
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Blocks until the user decides.
+    ///
+    /// # Returns
+    /// A [`SelectionResult`] with a reference to the confirmed item in the borrowed slice, or
+    /// the cancellation, interrupt or error that ended the interaction.
+    pub fn wait(self) -> SelectionResult<&'env T> {
+        let options = self.options;
+        outcome(self.handle.join()).map(|path| {
+            *follow(options, &path)
+                .last()
+                .expect("a selection path is never empty")
+        })
+    }
+}
@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+/// The outcome of a dropdown interaction together with how the user got there, returned by
+/// [`TerminalDropDown::wait_selection`](crate::TerminalDropDown::wait_selection).
+///
+/// Meant for tools that log how their menus are used, e.g. to find out which entries are picked
+/// most or which menus take users longest to get through.
+///
+/// # Fields
+/// * `value` - The confirmed item, or `None` if the menu was cancelled.
+/// * `index` - Position of the confirmed item in the list, or of the top-level item whose
+///   submenu it was chosen in. `None` if the menu was cancelled.
+/// * `elapsed` - How long the menu was open.
+/// * `keystrokes` - How many keys the user pressed, including those that changed nothing.
+/// * `cancelled_by` - Why the menu closed without a choice, or `None` if an item was confirmed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection<T> {
+    pub value: Option<T>,
+    pub index: Option<usize>,
+    pub elapsed: Duration,
+    pub keystrokes: usize,
+    pub cancelled_by: Option<CancelReason>,
+}
+
+/// Why a menu closed without a choice.
+///
+/// # Variants
+/// * `Escape` - The user pressed Escape, or ended the input of the line prompt.
+/// * `Interrupt` - The user pressed Ctrl-C.
+/// * `Timeout` - The configured timeout ran out with nothing to select.
+/// * `Handle` - The owner closed the menu through its [`CancelHandle`](crate::CancelHandle).
+/// * `NoOptions` - There was nothing to choose from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CancelReason {
+    Escape,
+    Interrupt,
+    Timeout,
+    Handle,
+    NoOptions,
+}
+
+/// What a menu observed while it was open, everything of a [`Selection`] but the value.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Interaction {
+    pub(crate) index: Option<usize>,
+    pub(crate) elapsed: Duration,
+    pub(crate) keystrokes: usize,
+    pub(crate) cancelled_by: Option<CancelReason>,
+}
@@ -0,0 +1,264 @@
+use crate::events::EventSource;
+use crate::store::{escape, unescape};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Keys without data, recorded by their name.
+const NAMED_KEYS: [KeyCode; 23] = [
+    KeyCode::Backspace,
+    KeyCode::Enter,
+    KeyCode::Left,
+    KeyCode::Right,
+    KeyCode::Up,
+    KeyCode::Down,
+    KeyCode::Home,
+    KeyCode::End,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+    KeyCode::Tab,
+    KeyCode::BackTab,
+    KeyCode::Delete,
+    KeyCode::Insert,
+    KeyCode::Null,
+    KeyCode::Esc,
+    KeyCode::CapsLock,
+    KeyCode::ScrollLock,
+    KeyCode::NumLock,
+    KeyCode::PrintScreen,
+    KeyCode::Pause,
+    KeyCode::Menu,
+    KeyCode::KeypadBegin,
+];
+
+const KINDS: [KeyEventKind; 3] = [
+    KeyEventKind::Press,
+    KeyEventKind::Repeat,
+    KeyEventKind::Release,
+];
+
+/// Writes a session recording as a menu runs; see
+/// [`DropDownBuilder::record_session`](crate::DropDownBuilder::record_session).
+///
+/// The file is created when the first record is written and holds one record per line, its
+/// fields separated by tabs: the milliseconds since the first record, the record's kind, then
+/// for `key` the key code, modifier bits and event kind, for `resize` the columns and rows, and
+/// for `frame` the drawn frame with newlines escaped. Menus shown one after another, e.g. by a
+/// [`ReusableDropDown`](crate::ReusableDropDown), add to the same file.
+#[derive(Clone)]
+pub(crate) struct SessionLog(Arc<Mutex<LogFile>>);
+
+struct LogFile {
+    path: PathBuf,
+    /// The open file and when its first record was written, once there is one.
+    file: Option<(BufWriter<File>, Instant)>,
+}
+
+impl SessionLog {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self(Arc::new(Mutex::new(LogFile { path, file: None })))
+    }
+
+    /// Records a key press or resize. Other events are not replayed and left out.
+    pub(crate) fn event(&self, event: &Event) -> io::Result<()> {
+        match event {
+            Event::Key(key) => {
+                let Some(code) = code_name(key.code) else {
+                    return Ok(());
+                };
+                let kind = format!("{:?}", key.kind);
+                self.write(&["key", &code, &key.modifiers.bits().to_string(), &kind])
+            }
+            Event::Resize(columns, rows) => {
+                self.write(&["resize", &columns.to_string(), &rows.to_string()])
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Records a drawn frame.
+    pub(crate) fn frame(&self, text: &str) -> io::Result<()> {
+        self.write(&["frame", &escape(text)])
+    }
+
+    fn write(&self, fields: &[&str]) -> io::Result<()> {
+        let mut log = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        if log.file.is_none() {
+            if let Some(dir) = log.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                fs::create_dir_all(dir)?;
+            }
+            log.file = Some((BufWriter::new(File::create(&log.path)?), Instant::now()));
+        }
+        let Some((file, start)) = &mut log.file else {
+            unreachable!("the file was just created");
+        };
+        writeln!(
+            file,
+            "{}\t{}",
+            start.elapsed().as_millis(),
+            fields.join("\t")
+        )?;
+        // Flushed on every record, so that a crash leaves the session up to the crash behind.
+        file.flush()
+    }
+}
+
+impl fmt::Debug for SessionLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SessionLog(..)")
+    }
+}
+
+/// How `code` is written in a recording, or `None` for media and modifier keys, which menus
+/// ignore anyway.
+fn code_name(code: KeyCode) -> Option<String> {
+    match code {
+        KeyCode::Char(c) => Some(format!("Char:{}", escape(&c.to_string()))),
+        KeyCode::F(n) => Some(format!("F:{}", n)),
+        KeyCode::Media(_) | KeyCode::Modifier(_) => None,
+        named => Some(format!("{:?}", named)),
+    }
+}
+
+/// Reverses [`code_name`].
+fn parse_code(name: &str) -> Option<KeyCode> {
+    if let Some(c) = name.strip_prefix("Char:") {
+        let c = unescape(c);
+        let mut chars = c.chars();
+        return match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(KeyCode::Char(c)),
+            _ => None,
+        };
+    }
+    if let Some(n) = name.strip_prefix("F:") {
+        return n.parse().ok().map(KeyCode::F);
+    }
+    NAMED_KEYS
+        .into_iter()
+        .find(|code| format!("{:?}", code) == name)
+}
+
+/// A session recorded with
+/// [`DropDownBuilder::record_session`](crate::DropDownBuilder::record_session), played back as
+/// an [`EventSource`].
+///
+/// Plugged into a menu built the same way as the recorded one with
+/// [`DropDownBuilder::events`](crate::DropDownBuilder::events), it presses the recorded keys in
+/// order, which reproduces the user's navigation step by step. The recorded frames are kept
+/// for comparison, or for turning into a demo.
+///
+/// # Example
+/// ```no_run
+/// use ter_menu::{Replay, TerminalDropDown};
+///
+/// let replay = Replay::open("bug-report.session")?.paced();
+/// let result = TerminalDropDown::builder()
+///     .items(["staging", "production"].map(|env| (env, |_: &&str| {})))
+///     .events(replay)
+///     .build()
+///     .wait();
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Replay {
+    events: VecDeque<(Duration, Event)>,
+    frames: Vec<(Duration, String)>,
+    paced: bool,
+    /// When playback started, once it has.
+    started: Option<Instant>,
+}
+
+impl Replay {
+    /// Reads the recording at `path`.
+    ///
+    /// # Errors
+    /// If the file cannot be read, or with [`io::ErrorKind::InvalidData`] if a line is not a
+    /// record.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let contents = fs::read_to_string(path.into())?;
+        let mut replay = Self {
+            events: VecDeque::new(),
+            frames: Vec::new(),
+            paced: false,
+            started: None,
+        };
+        for (n, line) in contents.lines().enumerate() {
+            replay.parse_record(line).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {} is not a session record", n + 1),
+                )
+            })?;
+        }
+        Ok(replay)
+    }
+
+    /// Waits between key presses as long as the user did, instead of pressing them all at once.
+    pub fn paced(mut self) -> Self {
+        self.paced = true;
+        self
+    }
+
+    /// The recorded frames, each with the time it was drawn at since the recording began.
+    pub fn frames(&self) -> &[(Duration, String)] {
+        &self.frames
+    }
+
+    fn parse_record(&mut self, line: &str) -> Option<()> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let at = Duration::from_millis(fields.first()?.parse().ok()?);
+        match fields[1..] {
+            ["key", code, modifiers, kind] => {
+                let code = parse_code(code)?;
+                let modifiers = KeyModifiers::from_bits(modifiers.parse().ok()?)?;
+                let kind = KINDS
+                    .into_iter()
+                    .find(|known| format!("{:?}", known) == kind)?;
+                let key = KeyEvent::new_with_kind(code, modifiers, kind);
+                self.events.push_back((at, Event::Key(key)));
+            }
+            ["resize", columns, rows] => {
+                let event = Event::Resize(columns.parse().ok()?, rows.parse().ok()?);
+                self.events.push_back((at, event));
+            }
+            ["frame", text] => self.frames.push((at, unescape(text))),
+            _ => return None,
+        }
+        Some(())
+    }
+
+    /// How long until the next event is due, when paced.
+    fn until_next(&mut self) -> Duration {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        match self.events.front() {
+            Some((at, _)) if self.paced => {
+                (started + *at).saturating_duration_since(Instant::now())
+            }
+            _ => Duration::ZERO,
+        }
+    }
+}
+
+impl EventSource for Replay {
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool> {
+        let left = self.until_next();
+        thread::sleep(left.min(timeout));
+        Ok(left <= timeout)
+    }
+
+    /// Fails with [`io::ErrorKind::UnexpectedEof`] once every recorded event has been read, like
+    /// [`ScriptedEvents`](crate::ScriptedEvents).
+    fn read(&mut self) -> io::Result<Event> {
+        thread::sleep(self.until_next());
+        self.events
+            .pop_front()
+            .map(|(_, event)| event)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "end of the recording"))
+    }
+}
@@ -0,0 +1,89 @@
+use crate::hooks::CompareHook;
+use crate::item::MenuEntry;
+
+/// The order in which a menu lists its items.
+///
+/// Set with [`DropDownBuilder::sort`](crate::DropDownBuilder::sort) or
+/// [`DropDownBuilder::sort_by`](crate::DropDownBuilder::sort_by). While a menu is sorted, `s`
+/// ([`Action::CycleSort`](crate::Action::CycleSort)) switches to the next mode: unsorted,
+/// alphabetical, natural, then custom if a comparator was given, and round again.
+///
+/// # Variants
+/// * `Unsorted` - The order the items were given in.
+/// * `Alphabetical` - By label, ignoring case: "item10" comes before "item9".
+/// * `Natural` - By label, ignoring case and comparing runs of digits as numbers: "item9" comes
+///   before "item10".
+/// * `Custom` - By the comparator passed to
+///   [`DropDownBuilder::sort_by`](crate::DropDownBuilder::sort_by). Unsorted without one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SortMode {
+    #[default]
+    Unsorted,
+    Alphabetical,
+    Natural,
+    Custom,
+}
+
+impl SortMode {
+    /// The mode [`Action::CycleSort`](crate::Action::CycleSort) switches to. `Custom` is skipped
+    /// unless the menu has a comparator.
+    pub(crate) fn next(self, has_custom: bool) -> Self {
+        match self {
+            SortMode::Unsorted => SortMode::Alphabetical,
+            SortMode::Alphabetical => SortMode::Natural,
+            SortMode::Natural if has_custom => SortMode::Custom,
+            SortMode::Natural | SortMode::Custom => SortMode::Unsorted,
+        }
+    }
+}
+
+/// Orders `rows`, indices into `options`, by `mode`. Equal items keep their relative order.
+pub(crate) fn sort_rows<T: MenuEntry>(
+    rows: &mut [usize],
+    options: &[T],
+    mode: SortMode,
+    compare: Option<&CompareHook<T>>,
+) {
+    match (mode, compare) {
+        (SortMode::Alphabetical, _) => {
+            rows.sort_by_cached_key(|&i| options[i].label().to_lowercase());
+        }
+        (SortMode::Natural, _) => {
+            rows.sort_by_cached_key(|&i| natural_key(&options[i].label()));
+        }
+        (SortMode::Custom, Some(compare)) => {
+            rows.sort_by(|&a, &b| compare(&options[a], &options[b]))
+        }
+        (SortMode::Unsorted | SortMode::Custom, _) => {}
+    }
+}
+
+/// A piece of a label for natural ordering.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Chunk {
+    /// A run of digits without leading zeros, ordered by length first so that it compares as a
+    /// number of any size. Digits sort before letters, as in ASCII.
+    Number(usize, String),
+    Text(String),
+}
+
+/// Splits `label` into runs of digits and of other characters, lowercased.
+fn natural_key(label: &str) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    let mut rest = label;
+    while let Some(first) = rest.chars().next() {
+        let digits = first.is_ascii_digit();
+        let end = rest
+            .find(|c: char| c.is_ascii_digit() != digits)
+            .unwrap_or(rest.len());
+        let (run, tail) = rest.split_at(end);
+        chunks.push(if digits {
+            let number = run.trim_start_matches('0');
+            Chunk::Number(number.len(), number.to_string())
+        } else {
+            Chunk::Text(run.to_lowercase())
+        });
+        rest = tail;
+    }
+    chunks
+}
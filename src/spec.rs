@@ -0,0 +1,121 @@
+use crate::builder::DropDownBuilder;
+use crate::item::MenuItem;
+use crate::theme::Theme;
+use serde::{Deserialize, Serialize};
+
+/// The content of a menu as written in a config file, so that it can be changed without
+/// recompiling. Needs the `serde` feature.
+///
+/// Deserialize it from any format serde supports, e.g. TOML or JSON, and turn it into a builder
+/// with [`DropDownBuilder::from`]. Every item becomes a [`MenuItem`] whose value comes back from
+/// [`wait`](crate::TerminalDropDown::wait). The builder prints nothing once the menu closes; set
+/// [`DropDownBuilder::confirm_message`] on it for that. Unknown keys are rejected, so that typos
+/// do not go unnoticed.
+///
+/// # Fields
+/// * `title` - The prompt printed above the items.
+/// * `items` - The entries, in the order they are listed.
+/// * `page_size` - How many items are shown at once. Without it, as many as fit.
+/// * `theme` - The colors to draw with, by name.
+///
+/// # Example
+/// ```no_run
+/// use ter_menu::{DropDownBuilder, MenuSpec};
+///
+/// // E.g. from `toml::from_str(&fs::read_to_string("deploy.toml")?)?`.
+/// let spec: MenuSpec = serde_json::from_str(
+///     r#"{
+///         "title": "Deploy to",
+///         "theme": "dark",
+///         "items": [
+///             { "label": "Staging", "value": "stg", "hotkey": "s" },
+///             { "label": "Production", "value": "prd", "description": "needs approval" }
+///         ]
+///     }"#,
+/// )?;
+/// let result = DropDownBuilder::from(spec).build().wait();
+/// let target = result.selected().map(|item| item.value);
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MenuSpec {
+    pub title: Option<String>,
+    pub items: Vec<ItemSpec>,
+    pub page_size: Option<usize>,
+    pub theme: ThemeName,
+}
+
+/// One entry of a [`MenuSpec`].
+///
+/// # Fields
+/// * `label` - Text shown in the menu.
+/// * `value` - What the entry stands for. Defaults to the label.
+/// * `description` - Secondary text shown dimmed after the label.
+/// * `hotkey` - Key that picks the entry straight away; see [`MenuItem::hotkey`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ItemSpec {
+    pub label: String,
+    #[serde(default)]
+    pub value: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub hotkey: Option<char>,
+}
+
+/// The [`Theme`] presets a [`MenuSpec`] can name, written in lowercase.
+///
+/// # Variants
+/// * `Default` - [`Theme::default`].
+/// * `Dark` - [`Theme::dark`].
+/// * `Light` - [`Theme::light`].
+/// * `Plain` - [`Theme::plain`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeName {
+    #[default]
+    Default,
+    Dark,
+    Light,
+    Plain,
+}
+
+impl From<ThemeName> for Theme {
+    fn from(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Default => Theme::default(),
+            ThemeName::Dark => Theme::dark(),
+            ThemeName::Light => Theme::light(),
+            ThemeName::Plain => Theme::plain(),
+        }
+    }
+}
+
+impl From<ItemSpec> for MenuItem<String> {
+    fn from(spec: ItemSpec) -> Self {
+        let value = spec.value.unwrap_or_else(|| spec.label.clone());
+        let mut item = MenuItem::new(spec.label, value);
+        item.description = spec.description;
+        item.hotkey = spec.hotkey;
+        item
+    }
+}
+
+impl From<MenuSpec> for DropDownBuilder<MenuItem<String>, fn(&MenuItem<String>)> {
+    fn from(spec: MenuSpec) -> Self {
+        let mut builder = DropDownBuilder::new()
+            .values(spec.items.into_iter().map(MenuItem::from))
+            .theme(spec.theme.into())
+            .confirm_message("")
+            .cancel_message("");
+        if let Some(title) = spec.title {
+            builder = builder.prompt(title);
+        }
+        if let Some(page_size) = spec.page_size {
+            builder = builder.page_size(page_size);
+        }
+        builder
+    }
+}
@@ -0,0 +1,189 @@
+use crate::item::MenuEntry;
+use crate::keymap::KeyMap;
+use crate::menu::{Menu, MenuConfig, Step};
+use crate::render::Frame;
+use crate::theme::Theme;
+use crossterm::event::{Event, KeyEvent};
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// What a key press fed into a [`MenuState`] did.
+///
+/// # Variants
+/// * `Unchanged` - Nothing visible changed, so there is no need to redraw.
+/// * `Changed` - The highlight, the filter or the checked items changed; render the menu again.
+/// * `Selected(idx)` - The user confirmed the item at index `idx` of the options, or of the open
+///   submenu's entries; see [`MenuState::submenu_path`]. In multi-select mode the checked items
+///   are available from [`MenuState::checked`].
+/// * `Cancelled` - The user dismissed the menu.
+/// * `Interrupted` - The user pressed Ctrl-C.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyOutcome {
+    Unchanged,
+    Changed,
+    Selected(usize),
+    Cancelled,
+    Interrupted,
+}
+
+/// The selection logic of a menu without its event loop, for embedding in an application that
+/// already reads terminal events itself.
+///
+/// [`TerminalDropDown`](crate::TerminalDropDown) and [`select`](crate::select) own the terminal
+/// while they run: they enable raw mode, read events and draw on stdout. A `MenuState` does none
+/// of that. The application feeds it key presses through [`MenuState::handle_key`] and draws it
+/// wherever it likes with [`MenuState::render`], so the menu does not compete with the
+/// application for `event::read()`.
+///
+/// # Type Parameters
+/// * `T` - The type of items in the menu, as for [`select`](crate::select).
+///
+/// # Example
+/// ```no_run
+/// use crossterm::event::{self, Event};
+/// use ter_menu::{KeyOutcome, MenuState};
+///
+/// let options = ["staging", "production"];
+/// let mut state = MenuState::new(&options, 10);
+/// let mut stdout = std::io::stdout();
+/// state.render(&mut stdout)?;
+/// loop {
+///     if let Event::Key(key) = event::read()? {
+///         match state.handle_key(key) {
+///             KeyOutcome::Changed => state.render(&mut stdout)?,
+///             KeyOutcome::Selected(idx) => break println!("picked {}", options[idx]),
+///             KeyOutcome::Cancelled | KeyOutcome::Interrupted => break,
+///             KeyOutcome::Unchanged => {}
+///         }
+///     }
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct MenuState<'a, T> {
+    menu: Menu<'a, T>,
+}
+
+impl<'a, T: MenuEntry> MenuState<'a, T> {
+    /// Creates the state of a single-selection menu over `options`, showing at most `page_size`
+    /// items at once.
+    pub fn new(options: &'a [T], page_size: usize) -> Self {
+        Self {
+            menu: Menu::new(options, embedded(MenuConfig::new(page_size))),
+        }
+    }
+
+    /// Like [`MenuState::new`], but with a fuzzy filter line: typed characters narrow the list.
+    pub fn filtered(options: &'a [T], page_size: usize) -> Self {
+        let config = MenuConfig {
+            filter: true,
+            ..MenuConfig::new(page_size)
+        };
+        Self {
+            menu: Menu::new(options, embedded(config)),
+        }
+    }
+
+    /// Creates the state of a multi-selection menu over `options` with nothing checked.
+    pub fn multi(options: &'a [T], page_size: usize) -> Self {
+        Self {
+            menu: Menu::multi(options, embedded(MenuConfig::new(page_size))),
+        }
+    }
+
+    /// Replaces the key bindings. Defaults to [`KeyMap::default`].
+    pub fn keymap(mut self, keymap: KeyMap) -> Self {
+        self.menu.config.keymap = keymap;
+        self
+    }
+
+    /// Sets the colors and glyphs used by [`MenuState::render`]. Ignored when `NO_COLOR` is set
+    /// or `TERM=dumb`, where [`Theme::plain`] is always used.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        if !self.menu.config.plain {
+            self.menu.config.theme = theme;
+        }
+        self
+    }
+
+    /// Sets the title line rendered above the items.
+    pub fn prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.menu.config.prompt = prompt.into();
+        self
+    }
+
+    /// Applies one key press: moves the highlight, edits the filter, toggles checkboxes, or
+    /// confirms or cancels the menu, according to the key map.
+    pub fn handle_key(&mut self, key: KeyEvent) -> KeyOutcome {
+        match self.menu.update(Event::Key(key)) {
+            Step::Ignore => KeyOutcome::Unchanged,
+            Step::Redraw => KeyOutcome::Changed,
+            Step::Confirm => self
+                .menu
+                .current()
+                .map_or(KeyOutcome::Unchanged, KeyOutcome::Selected),
+            Step::Cancel => KeyOutcome::Cancelled,
+            Step::Interrupt => KeyOutcome::Interrupted,
+        }
+    }
+
+    /// Sets the area the menu is rendered into. Pages are shortened to fit `rows` lines and
+    /// longer lines are cut at `columns`.
+    pub fn resize(&mut self, columns: u16, rows: u16) {
+        self.menu.update(Event::Resize(columns, rows));
+    }
+
+    /// Index into the options (or the open submenu's entries) of the highlighted item, or `None`
+    /// if the filter matches nothing.
+    pub fn highlighted(&self) -> Option<usize> {
+        self.menu.current()
+    }
+
+    /// Indices of the entries whose submenus are open, from the top-level options down. Empty
+    /// outside submenus. [`MenuState::highlighted`] and [`KeyOutcome::Selected`] refer to the
+    /// entries of the innermost one.
+    pub fn submenu_path(&self) -> Vec<usize> {
+        self.menu.submenu_path()
+    }
+
+    /// Indices of the checked items, in the options' order. Empty in single-selection mode.
+    pub fn checked(&self) -> Vec<usize> {
+        self.menu.checked_indices()
+    }
+
+    /// The filter text typed so far, or `None` if the menu has no filter line.
+    pub fn query(&self) -> Option<&str> {
+        self.menu.query()
+    }
+
+    /// Writes the menu's lines to `out`, starting at the cursor position. Every line ends in
+    /// `\r\n`, so the output also lines up in raw mode. Clearing or positioning is left to the
+    /// caller.
+    pub fn render<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        self.frame().queue(out)?;
+        out.flush()
+    }
+
+    /// Lends a running menu out as a `MenuState`, e.g. to a key interceptor. Give it back with
+    /// [`MenuState::into_menu`].
+    pub(crate) fn from_menu(menu: Menu<'a, T>) -> Self {
+        Self { menu }
+    }
+
+    pub(crate) fn into_menu(self) -> Menu<'a, T> {
+        self.menu
+    }
+
+    /// Builds the lines [`MenuState::render`] writes.
+    pub(crate) fn frame(&self) -> Frame {
+        self.menu.frame()
+    }
+}
+
+/// Adjusts the defaults for menus driven by the application: it reads the events itself, so
+/// there is no reason to drop repeated key presses.
+fn embedded(config: MenuConfig) -> MenuConfig {
+    MenuConfig {
+        repeat_interval: Duration::ZERO,
+        ..config
+    }
+}
@@ -0,0 +1,112 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Remembers the item last chosen in a menu, so that the next run can start on it.
+///
+/// Used through [`DropDownBuilder::remember`](crate::DropDownBuilder::remember). Items are
+/// identified by their [`label`](crate::MenuEntry::label) and menus by an ID chosen by the
+/// caller, so one store can serve every menu of a tool. [`FileStore`] keeps the choices in a
+/// file; other implementations might use a database or the tool's own config file.
+pub trait SelectionStore: Send {
+    /// The label last saved for `menu_id`, if any.
+    fn load(&self, menu_id: &str) -> Option<String>;
+
+    /// Records `label` as the last choice in `menu_id`, replacing any earlier one.
+    fn save(&mut self, menu_id: &str, label: &str) -> io::Result<()>;
+}
+
+/// A [`SelectionStore`] that keeps the last choices in a text file, one menu per line.
+///
+/// The file is read on every [`load`](SelectionStore::load) and rewritten on every
+/// [`save`](SelectionStore::save), so several tools, or several runs of one tool, can share
+/// it. A missing or unreadable file simply has no choices in it.
+///
+/// # Example
+/// ```no_run
+/// use ter_menu::{FileStore, TerminalDropDown};
+///
+/// let store = FileStore::new("/home/me/.config/deploy/last-choices");
+/// let ignore: fn(&&str) = |_| {};
+/// let result = TerminalDropDown::builder()
+///     .items(["staging", "production"].map(|env| (env, ignore)))
+///     .remember("environment", store)
+///     .build()
+///     .wait();
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    path: PathBuf,
+}
+
+impl FileStore {
+    /// Creates a store backed by the file at `path`. Neither the file nor its directory need
+    /// exist yet; both are created on the first save.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// The `(menu ID, label)` pairs in the file, in file order.
+    fn entries(&self) -> Vec<(String, String)> {
+        let Ok(contents) = fs::read_to_string(&self.path) else {
+            return Vec::new();
+        };
+        contents
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .map(|(menu_id, label)| (unescape(menu_id), unescape(label)))
+            .collect()
+    }
+}
+
+impl SelectionStore for FileStore {
+    fn load(&self, menu_id: &str) -> Option<String> {
+        self.entries()
+            .into_iter()
+            .find(|(id, _)| id == menu_id)
+            .map(|(_, label)| label)
+    }
+
+    fn save(&mut self, menu_id: &str, label: &str) -> io::Result<()> {
+        let mut entries = self.entries();
+        match entries.iter_mut().find(|(id, _)| id == menu_id) {
+            Some(entry) => entry.1 = label.to_string(),
+            None => entries.push((menu_id.to_string(), label.to_string())),
+        }
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let contents: String = entries
+            .iter()
+            .map(|(id, label)| format!("{}\t{}\n", escape(id), escape(label)))
+            .collect();
+        fs::write(&self.path, contents)
+    }
+}
+
+/// Escapes the characters that separate entries and fields in a [`FileStore`] file or a session
+/// recording.
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+/// Reverses [`escape`].
+pub(crate) fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
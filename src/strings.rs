@@ -0,0 +1,363 @@
+use crate::keymap::Action;
+use crate::matcher::MatchMode;
+use crate::sort::SortMode;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// Every piece of text a menu shows on its own, for translating menus into other languages.
+///
+/// Set with [`DropDownBuilder::strings`](crate::DropDownBuilder::strings). [`Strings::english`],
+/// the default, and [`Strings::zh_cn`] are built in; other languages start from either and
+/// replace the fields they need:
+///
+/// ```
+/// use ter_menu::Strings;
+///
+/// let german = Strings {
+///     prompt: "Bitte wählen:".to_string(),
+///     no_matches: "Keine Treffer.".to_string(),
+///     ..Strings::english()
+/// };
+/// ```
+///
+/// Texts in braces are placeholders filled in when the text is shown, e.g. `{total}` in
+/// [`totals`](Strings::totals). Texts the application passes in itself, such as item labels and
+/// [`DropDownBuilder::help_line`](crate::DropDownBuilder::help_line), are not affected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Strings {
+    /// Title line above the items, unless the menu sets its own prompt.
+    pub prompt: String,
+    /// Printed once an item is confirmed. `{item}` is the confirmed item.
+    pub confirm_message: String,
+    /// Printed when the menu is cancelled.
+    pub cancel_message: String,
+    /// Shown instead of the menu when there is nothing to choose from.
+    pub no_options: String,
+    /// Shown below [`no_options`](Strings::no_options).
+    pub exit_hint: String,
+    /// Shown while a stream has not delivered any items yet.
+    pub no_items: String,
+    /// Shown when no item matches the filter.
+    pub no_matches: String,
+    /// Shown below the items while a stream is still loading.
+    pub loading: String,
+    /// The filter line. `{filter}` is the typed text.
+    pub filter: String,
+    /// The totals line: `{total}` items, of which `{first}` to `{last}` are on the page.
+    pub totals: String,
+    /// The totals line while filtering, with `{matched}` items matching the filter.
+    pub totals_matched: String,
+    /// Added to the totals line in multi-select mode: `{selected}` of `{total}` items checked.
+    pub selected_count: String,
+    /// Key instructions in the footer.
+    pub help: String,
+    /// Key instructions inside a submenu.
+    pub help_submenu: String,
+    /// Key instructions with a filter line.
+    pub help_filter: String,
+    /// Key instructions with a filter line inside a submenu.
+    pub help_filter_submenu: String,
+    /// Key instructions in multi-select mode.
+    pub help_multi: String,
+    /// Key instructions of a [`Reorder`](crate::Reorder) list.
+    pub help_reorder: String,
+    /// Footer note in a grid layout.
+    pub help_grid: String,
+    /// Footer note while a filter predicate hides items.
+    pub show_all: String,
+    /// Footer note while a filter predicate is switched off.
+    pub only_matching: String,
+    /// Footer note of a sorted menu. `{mode}` is the name of the current sort mode.
+    pub sort: String,
+    /// Names of the [`SortMode`]s, in declaration order.
+    pub sort_modes: [String; 4],
+    /// Footer note of the filter line: `{mode}` is how it matches, `{case}` whether it ignores
+    /// case.
+    pub match_mode: String,
+    /// Names of the [`MatchMode`]s, in declaration order.
+    pub match_modes: [String; 3],
+    /// `{case}` in [`match_mode`](Strings::match_mode) when the filter ignores case.
+    pub ignore_case: String,
+    /// `{case}` in [`match_mode`](Strings::match_mode) when the filter respects case.
+    pub case_sensitive: String,
+    /// Countdown of a multi-select timeout that confirms. `{seconds}` are left.
+    pub confirming_in: String,
+    /// Countdown of a timeout that selects `{item}` when `{seconds}` have run out.
+    pub auto_selecting: String,
+    /// Countdown of a timeout that cancels.
+    pub closing_in: String,
+    /// Selection limits with only a minimum, `{min}`.
+    pub select_at_least: String,
+    /// Selection limits with only a maximum, `{max}`.
+    pub select_up_to: String,
+    /// Selection limits with equal minimum and maximum, `{max}`.
+    pub select_exactly: String,
+    /// Selection limits from `{min}` to `{max}`.
+    pub select_between: String,
+    /// Footer note on selection limits: `{limits}` is one of the texts above, `{selected}` the
+    /// number of checked items.
+    pub limits: String,
+    /// Like [`limits`](Strings::limits), once the maximum is checked.
+    pub limits_reached: String,
+    /// Title of the help screen.
+    pub keys_title: String,
+    /// Bottom line of the help screen.
+    pub keys_footer: String,
+    /// The search line below the items while a search is typed. `{search}` is the typed text.
+    pub search: String,
+    /// Footer note while a search is active.
+    pub search_keys: String,
+    /// Shown in place of the footer during the undo window: `{item}` was confirmed, and `{seconds}`
+    /// are left to take it back.
+    pub undo: String,
+    /// Shown in place of the footer once a destructive `{item}` is chosen that needs Enter
+    /// pressed again.
+    pub confirm_again: String,
+    /// Shown in place of the footer once a destructive `{item}` is chosen whose label must be
+    /// typed. `{typed}` is the text typed so far.
+    pub confirm_by_label: String,
+    /// What each action does on the help screen. Actions left out are described in English.
+    pub actions: HashMap<Action, String>,
+    /// Question of the line prompt used when the input is not a terminal, with `{total}` items.
+    pub enter_choice: String,
+    /// Like [`enter_choice`](Strings::enter_choice), in multi-select mode.
+    pub enter_choices: String,
+    /// Answer to input the line prompt cannot use. `{input}` is what was typed.
+    pub invalid_choice: String,
+    /// Question of the line prompt once a destructive `{item}` is chosen that needs confirming
+    /// again, by entering its number `{choice}` once more or `y`.
+    pub confirm_choice_again: String,
+    /// Marks disabled items in the line prompt's list.
+    pub unavailable: String,
+    /// The answers of a yes/no step of a [`Flow`](crate::Flow).
+    pub yes: String,
+    /// See [`yes`](Strings::yes).
+    pub no: String,
+    /// Question asked when the confirmed item's callback fails; see
+    /// [`DropDownBuilder::retry_on_error`](crate::DropDownBuilder::retry_on_error). `{error}`
+    /// is the error.
+    pub action_failed: String,
+    /// The answers to [`action_failed`](Strings::action_failed): run the callback again.
+    pub retry: String,
+    /// Go back to the menu to choose a different item.
+    pub pick_another: String,
+    /// Give up and end with the error.
+    pub abort: String,
+}
+
+impl Strings {
+    /// The built-in English texts.
+    pub fn english() -> Self {
+        let text = |s: &str| s.to_string();
+        Self {
+            prompt: text("Please select.（ESC for canceling）:"),
+            confirm_message: text("Confirm delete: {item}"),
+            cancel_message: text("Delete canceled."),
+            no_options: text("No options available."),
+            exit_hint: text("Press ESC to exit."),
+            no_items: text("No items yet."),
+            no_matches: text("No matches."),
+            loading: text("Loading…"),
+            filter: text("Filter: {filter}"),
+            totals: text("Total: {total} | Showing: {first} - {last}"),
+            totals_matched: text("Total: {total} | Matched: {matched} | Showing: {first} - {last}"),
+            selected_count: text(" | {selected}/{total} selected"),
+            help: text("↑: Up | ↓: Down | Enter: Confirm | ESC: Cancel"),
+            help_submenu: text("↑: Up | ↓: Down | Enter: Confirm | ←/ESC: Back"),
+            help_filter: text(
+                "Type to filter | ↑: Up | ↓: Down | Enter: Confirm | ESC: Clear/Cancel",
+            ),
+            help_filter_submenu: text(
+                "Type to filter | ↑: Up | ↓: Down | Enter: Confirm | ESC: Clear/Back",
+            ),
+            help_multi: text(
+                "↑: Up | ↓: Down | Space: Toggle | a: All | i: Invert | n: None | Enter: Confirm \
+                 | ESC: Cancel",
+            ),
+            help_reorder: text(
+                "↑: Up | ↓: Down | Shift+↑/↓: Move item | Enter: Confirm | ESC: Cancel",
+            ),
+            help_grid: text(" | ←/→: Left/Right"),
+            show_all: text(" | Tab: Show all"),
+            only_matching: text(" | Tab: Only matching"),
+            sort: text(" | s: Sort ({mode})"),
+            sort_modes: [text("none"), text("A-Z"), text("natural"), text("custom")],
+            match_mode: text(" | Ctrl-R: {mode} | Alt-C: {case}"),
+            match_modes: [text("fuzzy"), text("substring"), text("regex")],
+            ignore_case: text("ignore case"),
+            case_sensitive: text("match case"),
+            confirming_in: text("Confirming in {seconds}s"),
+            auto_selecting: text("Auto-selecting '{item}' in {seconds}s"),
+            closing_in: text("Closing in {seconds}s"),
+            select_at_least: text("Select at least {min}"),
+            select_up_to: text("Select up to {max}"),
+            select_exactly: text("Select {max}"),
+            select_between: text("Select {min}-{max}"),
+            limits: text(" | {limits} ({selected} selected)"),
+            limits_reached: text(" | {limits} ({selected} selected, max reached)"),
+            keys_title: text("Keys:"),
+            keys_footer: text("Press any key to return"),
+            search: text("/{search}"),
+            search_keys: text(" | n/N: Next/previous match"),
+            undo: text("Selected {item} — press U within {seconds}s to undo"),
+            confirm_again: text("Press Enter again to confirm {item}, or ESC to go back"),
+            confirm_by_label: text("Type \"{item}\" to confirm: {typed}"),
+            actions: HashMap::new(),
+            enter_choice: text("Enter choice [1-{total}]: "),
+            enter_choices: text("Enter choices [1-{total}], separated by spaces or commas: "),
+            invalid_choice: text("Invalid choice: {input}"),
+            confirm_choice_again: text("Enter {choice} again or y to confirm {item}: "),
+            unavailable: text(" (unavailable)"),
+            yes: text("Yes"),
+            no: text("No"),
+            action_failed: text("Action failed: {error}."),
+            retry: text("Retry"),
+            pick_another: text("Pick another"),
+            abort: text("Abort"),
+        }
+    }
+
+    /// Simplified Chinese texts.
+    pub fn zh_cn() -> Self {
+        let text = |s: &str| s.to_string();
+        let actions = [
+            (Action::Up, "上移"),
+            (Action::Down, "下移"),
+            (Action::PageUp, "上一页"),
+            (Action::PageDown, "下一页"),
+            (Action::HalfPageUp, "上移半页"),
+            (Action::HalfPageDown, "下移半页"),
+            (Action::First, "第一项"),
+            (Action::Last, "最后一项"),
+            (Action::Confirm, "确认"),
+            (Action::ConfirmAndStay, "确认并保持菜单打开"),
+            (Action::Cancel, "清除筛选、返回或取消"),
+            (Action::Back, "返回上级菜单"),
+            (Action::Left, "在网格中左移，或返回"),
+            (Action::Right, "在网格中右移"),
+            (Action::Interrupt, "退出"),
+            (Action::Toggle, "勾选或取消勾选"),
+            (Action::SelectAll, "全选"),
+            (Action::SelectNone, "全不选"),
+            (Action::ToggleAll, "全选或全不选"),
+            (Action::Invert, "反选"),
+            (Action::TogglePredicate, "只显示匹配项，或显示全部"),
+            (Action::CycleSort, "切换排序方式"),
+            (Action::MoveItemUp, "将该项上移"),
+            (Action::MoveItemDown, "将该项下移"),
+            (Action::Help, "显示本帮助"),
+            (Action::Search, "搜索"),
+            (Action::NextMatch, "下一个匹配项"),
+            (Action::PrevMatch, "上一个匹配项"),
+            (Action::CycleMatchMode, "切换筛选的匹配方式"),
+            (Action::ToggleCase, "筛选时忽略或区分大小写"),
+            (Action::Undo, "撤销选择"),
+        ];
+        Self {
+            prompt: text("请选择（按 ESC 取消）："),
+            confirm_message: text("确认删除：{item}"),
+            cancel_message: text("已取消删除。"),
+            no_options: text("没有可选项。"),
+            exit_hint: text("按 ESC 退出。"),
+            no_items: text("暂无选项。"),
+            no_matches: text("没有匹配项。"),
+            loading: text("加载中…"),
+            filter: text("筛选：{filter}"),
+            totals: text("共 {total} 项 | 显示：{first} - {last}"),
+            totals_matched: text("共 {total} 项 | 匹配：{matched} | 显示：{first} - {last}"),
+            selected_count: text(" | 已选 {selected}/{total}"),
+            help: text("↑：上 | ↓：下 | Enter：确认 | ESC：取消"),
+            help_submenu: text("↑：上 | ↓：下 | Enter：确认 | ←/ESC：返回"),
+            help_filter: text("输入以筛选 | ↑：上 | ↓：下 | Enter：确认 | ESC：清除/取消"),
+            help_filter_submenu: text("输入以筛选 | ↑：上 | ↓：下 | Enter：确认 | ESC：清除/返回"),
+            help_multi: text(
+                "↑：上 | ↓：下 | 空格：勾选 | a：全选 | i：反选 | n：全不选 | Enter：确认 | ESC：取消",
+            ),
+            help_reorder: text("↑：上 | ↓：下 | Shift+↑/↓：移动该项 | Enter：确认 | ESC：取消"),
+            help_grid: text(" | ←/→：左/右"),
+            show_all: text(" | Tab：显示全部"),
+            only_matching: text(" | Tab：只显示匹配项"),
+            sort: text(" | s：排序（{mode}）"),
+            sort_modes: [text("无"), text("A-Z"), text("自然"), text("自定义")],
+            match_mode: text(" | Ctrl-R：{mode} | Alt-C：{case}"),
+            match_modes: [text("模糊"), text("子串"), text("正则")],
+            ignore_case: text("忽略大小写"),
+            case_sensitive: text("区分大小写"),
+            confirming_in: text("{seconds} 秒后确认"),
+            auto_selecting: text("{seconds} 秒后自动选择“{item}”"),
+            closing_in: text("{seconds} 秒后关闭"),
+            select_at_least: text("至少选择 {min} 项"),
+            select_up_to: text("最多选择 {max} 项"),
+            select_exactly: text("选择 {max} 项"),
+            select_between: text("选择 {min}-{max} 项"),
+            limits: text(" | {limits}（已选 {selected} 项）"),
+            limits_reached: text(" | {limits}（已选 {selected} 项，已达上限）"),
+            keys_title: text("按键："),
+            keys_footer: text("按任意键返回"),
+            search: text("/{search}"),
+            search_keys: text(" | n/N：下一个/上一个匹配项"),
+            undo: text("已选择 {item} — {seconds} 秒内按 U 撤销"),
+            confirm_again: text("再按一次 Enter 以确认 {item}，按 ESC 返回"),
+            confirm_by_label: text("输入“{item}”以确认：{typed}"),
+            actions: actions
+                .into_iter()
+                .map(|(action, description)| (action, description.to_string()))
+                .collect(),
+            enter_choice: text("请输入选项 [1-{total}]："),
+            enter_choices: text("请输入选项 [1-{total}]，用空格或逗号分隔："),
+            invalid_choice: text("无效的选项：{input}"),
+            confirm_choice_again: text("再次输入 {choice} 或 y 以确认 {item}："),
+            unavailable: text("（不可用）"),
+            yes: text("是"),
+            no: text("否"),
+            action_failed: text("操作失败：{error}。"),
+            retry: text("重试"),
+            pick_another: text("另选一项"),
+            abort: text("中止"),
+        }
+    }
+
+    /// The name of `mode` in the footer.
+    pub(crate) fn sort_mode(&self, mode: SortMode) -> &str {
+        let index = match mode {
+            SortMode::Unsorted => 0,
+            SortMode::Alphabetical => 1,
+            SortMode::Natural => 2,
+            SortMode::Custom => 3,
+        };
+        &self.sort_modes[index]
+    }
+
+    /// The name of `mode` in the footer.
+    pub(crate) fn match_mode(&self, mode: MatchMode) -> &str {
+        let index = match mode {
+            MatchMode::Fuzzy => 0,
+            MatchMode::Substring => 1,
+            #[cfg(feature = "regex")]
+            MatchMode::Regex => 2,
+        };
+        &self.match_modes[index]
+    }
+
+    /// What `action` does, on the help screen.
+    pub(crate) fn action(&self, action: Action) -> &str {
+        self.actions
+            .get(&action)
+            .map_or(action.description(), String::as_str)
+    }
+}
+
+impl Default for Strings {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+/// `template` with each `{name}` replaced by its value in `values`.
+pub(crate) fn fill(template: &str, values: &[(&str, &dyn Display)]) -> String {
+    values
+        .iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), &value.to_string())
+        })
+}
@@ -0,0 +1,144 @@
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Space between two columns of a table.
+const GAP: &str = "  ";
+
+/// One column of a menu whose items are shown as table rows; see
+/// [`DropDownBuilder::columns`](crate::DropDownBuilder::columns).
+///
+/// # Example
+/// ```
+/// use ter_menu::Column;
+///
+/// struct Pod {
+///     name: String,
+///     status: String,
+///     restarts: u32,
+/// }
+///
+/// let columns = [
+///     Column::new("NAME", |pod: &Pod| pod.name.clone()).width(30),
+///     Column::new("STATUS", |pod: &Pod| pod.status.clone()),
+///     Column::new("RESTARTS", |pod: &Pod| pod.restarts.to_string()).align_right(),
+/// ];
+/// ```
+pub struct Column<T> {
+    title: String,
+    /// Fixed width in terminal columns. `None` fits the widest cell.
+    width: Option<usize>,
+    align_right: bool,
+    cell: Box<dyn Fn(&T) -> String + Send>,
+}
+
+impl<T> Column<T> {
+    /// Creates a left-aligned column headed `title` whose cells show what `cell` returns for
+    /// each item. It is as wide as its widest cell or title.
+    pub fn new(title: impl Into<String>, cell: impl Fn(&T) -> String + Send + 'static) -> Self {
+        Self {
+            title: title.into(),
+            width: None,
+            align_right: false,
+            cell: Box::new(cell),
+        }
+    }
+
+    /// Makes the column `width` terminal columns wide. Longer cells are cut off with `…`.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Aligns the cells and the title to the right edge of the column, as suits numbers.
+    pub fn align_right(mut self) -> Self {
+        self.align_right = true;
+        self
+    }
+}
+
+/// Columns with their widths settled, ready to lay out rows.
+pub(crate) struct Table<T> {
+    columns: Vec<Column<T>>,
+}
+
+impl<T> Table<T> {
+    /// Settles the width of every column without a fixed one to its widest cell among `items`,
+    /// or its title if that is wider.
+    pub(crate) fn new<'t>(
+        mut columns: Vec<Column<T>>,
+        items: impl Iterator<Item = &'t T> + Clone,
+    ) -> Self
+    where
+        T: 't,
+    {
+        for column in &mut columns {
+            if column.width.is_none() {
+                let widest = items
+                    .clone()
+                    .map(|item| (column.cell)(item).width())
+                    .max()
+                    .unwrap_or_default();
+                column.width = Some(widest.max(column.title.width()));
+            }
+        }
+        Self { columns }
+    }
+
+    /// The line of column titles.
+    pub(crate) fn header(&self) -> String {
+        self.layout(|column| column.title.clone())
+    }
+
+    /// The row shown for `item`.
+    pub(crate) fn row(&self, item: &T) -> String {
+        self.layout(|column| (column.cell)(item))
+    }
+
+    /// Joins the texts `text` returns for each column, each fitted to its column.
+    fn layout(&self, text: impl Fn(&Column<T>) -> String) -> String {
+        let mut line = String::new();
+        for (i, column) in self.columns.iter().enumerate() {
+            if i > 0 {
+                line.push_str(GAP);
+            }
+            let last = i + 1 == self.columns.len();
+            line.push_str(&fit(
+                &text(column),
+                column.width.unwrap_or_default(),
+                column.align_right,
+                last,
+            ));
+        }
+        line
+    }
+}
+
+/// `text` cut off with `…` or padded with spaces to exactly `width` columns. The last column of a
+/// row is not padded on the right, so that rows carry no trailing spaces.
+fn fit(text: &str, width: usize, align_right: bool, last: bool) -> String {
+    let mut fitted = String::new();
+    if text.width() <= width {
+        fitted.push_str(text);
+    } else {
+        let budget = width.saturating_sub(1);
+        let mut used = 0;
+        for c in text.chars() {
+            let c_width = c.width().unwrap_or_default();
+            if used + c_width > budget {
+                break;
+            }
+            used += c_width;
+            fitted.push(c);
+        }
+        if width > 0 {
+            fitted.push('…');
+        }
+    }
+    let padding = " ".repeat(width.saturating_sub(fitted.width()));
+    if align_right {
+        padding + &fitted
+    } else if last {
+        fitted
+    } else {
+        fitted + &padding
+    }
+}
@@ -0,0 +1,225 @@
+use crate::error::TerMenuError;
+use crate::menu::MenuConfig;
+use crate::output::Output;
+use crossterm::ExecutableCommand;
+use crossterm::cursor::{Hide, RestorePosition, SavePosition, Show};
+use crossterm::event::{
+    DisableMouseCapture, EnableMouseCapture, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
+    PushKeyboardEnhancementFlags,
+};
+use crossterm::terminal::{
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+    supports_keyboard_enhancement,
+};
+use std::io;
+
+/// Keeps the terminal in raw mode for as long as it is alive.
+///
+/// Raw mode is enabled by [`RawModeGuard::new`] and disabled again when the guard is dropped,
+/// including while unwinding from a panic, so the user's shell is never left in raw mode.
+#[derive(Debug)]
+pub(crate) struct RawModeGuard {
+    _private: (),
+}
+
+impl RawModeGuard {
+    /// Enables raw mode.
+    ///
+    /// # Returns
+    /// The guard on success, or the error reported by the terminal.
+    pub(crate) fn new() -> io::Result<Self> {
+        enable_raw_mode()?;
+        Ok(Self { _private: () })
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        // 处理可能的错误而不是忽略
+        if let Err(e) = disable_raw_mode() {
+            eprintln!("Failed to disable raw mode: {}", e);
+        }
+    }
+}
+
+/// Reports mouse events to the application for as long as it is alive.
+///
+/// Mouse capture is enabled by [`MouseCaptureGuard::new`] and disabled again on drop, so the
+/// terminal's own selection and scrolling behaviour comes back once the menu closes.
+#[derive(Debug)]
+pub(crate) struct MouseCaptureGuard {
+    out: Output,
+}
+
+impl MouseCaptureGuard {
+    /// Enables mouse capture on the terminal behind `out`.
+    pub(crate) fn new(mut out: Output) -> io::Result<Self> {
+        out.execute(EnableMouseCapture)?;
+        Ok(Self { out })
+    }
+}
+
+impl Drop for MouseCaptureGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.out.execute(DisableMouseCapture) {
+            eprintln!("Failed to disable mouse capture: {}", e);
+        }
+    }
+}
+
+/// Hides the terminal's cursor for as long as it is alive, so that it does not blink somewhere
+/// under the menu.
+#[derive(Debug)]
+pub(crate) struct HiddenCursorGuard {
+    out: Output,
+}
+
+impl HiddenCursorGuard {
+    /// Hides the cursor of the terminal behind `out`.
+    pub(crate) fn new(mut out: Output) -> io::Result<Self> {
+        out.execute(Hide)?;
+        Ok(Self { out })
+    }
+}
+
+impl Drop for HiddenCursorGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.out.execute(Show) {
+            eprintln!("Failed to show the cursor: {}", e);
+        }
+    }
+}
+
+/// Shows the alternate screen buffer for as long as it is alive.
+///
+/// The cursor position is saved before switching and restored after switching back, so the
+/// user's screen looks exactly as it did before the menu opened.
+#[derive(Debug)]
+pub(crate) struct AlternateScreenGuard {
+    out: Output,
+}
+
+impl AlternateScreenGuard {
+    /// Saves the cursor position and switches the terminal behind `out` to the alternate screen.
+    pub(crate) fn new(mut out: Output) -> io::Result<Self> {
+        out.execute(SavePosition)?.execute(EnterAlternateScreen)?;
+        Ok(Self { out })
+    }
+}
+
+impl Drop for AlternateScreenGuard {
+    fn drop(&mut self) {
+        let result = self
+            .out
+            .execute(LeaveAlternateScreen)
+            .and_then(|stdout| stdout.execute(RestorePosition).map(|_| ()));
+        if let Err(e) = result {
+            eprintln!("Failed to leave the alternate screen: {}", e);
+        }
+    }
+}
+
+/// Has the terminal report keys with the kitty keyboard protocol for as long as it is alive, so
+/// that e.g. Shift-Enter and Enter, or Ctrl-I and Tab, arrive as different keys, and releases
+/// arrive as events of their own.
+#[derive(Debug)]
+pub(crate) struct KeyboardEnhancementGuard {
+    out: Output,
+}
+
+impl KeyboardEnhancementGuard {
+    /// Turns the protocol on for the terminal behind `out`, or returns `None` if the terminal
+    /// does not support it. Asking the terminal needs raw mode.
+    pub(crate) fn new(mut out: Output) -> io::Result<Option<Self>> {
+        if !supports_keyboard_enhancement()? {
+            return Ok(None);
+        }
+        out.execute(PushKeyboardEnhancementFlags(
+            KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                | KeyboardEnhancementFlags::REPORT_EVENT_TYPES,
+        ))?;
+        Ok(Some(Self { out }))
+    }
+}
+
+impl Drop for KeyboardEnhancementGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.out.execute(PopKeyboardEnhancementFlags) {
+            eprintln!("Failed to restore the keyboard protocol: {}", e);
+        }
+    }
+}
+
+/// Everything a running menu changes about the terminal, restored together on drop.
+///
+/// Fields are dropped in declaration order, which undoes the setup in reverse.
+#[derive(Debug)]
+pub(crate) struct TerminalSession {
+    _keyboard_enhancement: Option<KeyboardEnhancementGuard>,
+    _mouse_capture: Option<MouseCaptureGuard>,
+    _hidden_cursor: Option<HiddenCursorGuard>,
+    _raw_mode: RawModeGuard,
+    _alternate_screen: Option<AlternateScreenGuard>,
+}
+
+impl TerminalSession {
+    /// Prepares the terminal according to `config`: alternate screen if requested, raw mode, a
+    /// hidden cursor, and mouse capture and the kitty keyboard protocol if requested. Plain menus
+    /// only use raw mode, which needs no escape sequences.
+    pub(crate) fn start(config: &MenuConfig) -> Result<Self, TerMenuError> {
+        Self::open(config, true)
+    }
+
+    /// Like [`TerminalSession::start`], but leaves the cursor visible, for prompts where the
+    /// user types text.
+    pub(crate) fn start_with_cursor(config: &MenuConfig) -> Result<Self, TerMenuError> {
+        Self::open(config, false)
+    }
+
+    /// Whether held keys are reported as repeats rather than as more presses, which is the case
+    /// once the kitty keyboard protocol is on.
+    pub(crate) fn reports_repeats(&self) -> bool {
+        self._keyboard_enhancement.is_some()
+    }
+
+    fn open(config: &MenuConfig, hide_cursor: bool) -> Result<Self, TerMenuError> {
+        let alternate_screen = if config.alternate_screen && !config.plain {
+            Some(AlternateScreenGuard::new(config.output.clone())?)
+        } else {
+            None
+        };
+        let raw_mode = RawModeGuard::new().map_err(TerMenuError::RawMode)?;
+        let hidden_cursor = if config.plain || !hide_cursor {
+            None
+        } else {
+            Some(HiddenCursorGuard::new(config.output.clone())?)
+        };
+        let mouse_capture = if config.mouse && !config.plain {
+            Some(MouseCaptureGuard::new(config.output.clone())?)
+        } else {
+            None
+        };
+        let keyboard_enhancement = if config.keyboard_enhancement && !config.plain {
+            KeyboardEnhancementGuard::new(config.output.clone())?
+        } else {
+            None
+        };
+        #[cfg(feature = "tracing")]
+        tracing::debug!("terminal prepared");
+        Ok(Self {
+            _keyboard_enhancement: keyboard_enhancement,
+            _mouse_capture: mouse_capture,
+            _hidden_cursor: hidden_cursor,
+            _raw_mode: raw_mode,
+            _alternate_screen: alternate_screen,
+        })
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Drop for TerminalSession {
+    fn drop(&mut self) {
+        // Runs before the fields are dropped, which is what restores the terminal.
+        tracing::debug!("restoring the terminal");
+    }
+}
@@ -0,0 +1,117 @@
+use crossterm::style::{Attribute, Color, ContentStyle};
+
+/// Colors and glyphs used to draw a menu.
+///
+/// [`Theme::default`] matches the classic look: a reverse-video bar on the highlighted row and no
+/// colors. [`Theme::dark`] and [`Theme::light`] add colors suited to dark and light terminal
+/// backgrounds, and [`Theme::plain`] draws without any colors or attributes. Every field is public,
+/// so a preset can be adjusted with struct update syntax; styles accept any crossterm color,
+/// including `Color::AnsiValue` (256 colors) and `Color::Rgb` (truecolor).
+///
+/// # Fields
+/// * `highlight` - Style of the highlighted row.
+/// * `marker` - Drawn in front of the highlighted row. Other rows are indented by the same width.
+/// * `prompt` - Style of the prompt line.
+/// * `matched` - Added to the characters of a label that match the filter or search, so that it
+///   is clear why an item matched.
+/// * `help` - Style of the key-instructions footer.
+/// * `checked` / `unchecked` - Checkbox glyphs in multi-select mode.
+/// * `scroll_up` / `scroll_down` - Shown above/below the items when the list continues in that
+///   direction. Empty strings hide the indicators.
+/// * `spinner` - Glyphs shown one after another in front of the "Loading…" row while items are
+///   still arriving. An empty string shows the row without a spinner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    pub highlight: ContentStyle,
+    pub marker: String,
+    pub prompt: ContentStyle,
+    pub matched: ContentStyle,
+    pub help: ContentStyle,
+    pub checked: String,
+    pub unchecked: String,
+    pub scroll_up: String,
+    pub scroll_down: String,
+    pub spinner: String,
+}
+
+impl Theme {
+    /// Colors for terminals with a dark background: a cyan bar and prompt, and a gray footer.
+    pub fn dark() -> Self {
+        Self {
+            highlight: ContentStyle {
+                foreground_color: Some(Color::Black),
+                background_color: Some(Color::Cyan),
+                ..ContentStyle::new()
+            },
+            marker: "❯ ".to_string(),
+            prompt: bold(Color::Cyan),
+            matched: bold(Color::Yellow),
+            help: colored(Color::Grey),
+            checked: "◉ ".to_string(),
+            unchecked: "○ ".to_string(),
+            scroll_up: "  ▲".to_string(),
+            scroll_down: "  ▼".to_string(),
+            spinner: "⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏".to_string(),
+        }
+    }
+
+    /// Colors for terminals with a light background: a blue bar and prompt, and a dark gray
+    /// footer.
+    pub fn light() -> Self {
+        Self {
+            highlight: ContentStyle {
+                foreground_color: Some(Color::White),
+                background_color: Some(Color::DarkBlue),
+                ..ContentStyle::new()
+            },
+            prompt: bold(Color::DarkBlue),
+            matched: bold(Color::DarkMagenta),
+            help: colored(Color::DarkGrey),
+            ..Self::dark()
+        }
+    }
+
+    /// No colors or text attributes at all; the highlighted row is only marked by `> `.
+    pub fn plain() -> Self {
+        Self {
+            highlight: ContentStyle::new(),
+            matched: ContentStyle::new(),
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let mut highlight = ContentStyle::new();
+        highlight.attributes.set(Attribute::Reverse);
+        let mut matched = ContentStyle::new();
+        matched.attributes.set(Attribute::Bold);
+        matched.attributes.set(Attribute::Underlined);
+        Self {
+            highlight,
+            marker: "> ".to_string(),
+            prompt: ContentStyle::new(),
+            matched,
+            help: ContentStyle::new(),
+            checked: "[x] ".to_string(),
+            unchecked: "[ ] ".to_string(),
+            scroll_up: String::new(),
+            scroll_down: String::new(),
+            spinner: "|/-\\".to_string(),
+        }
+    }
+}
+
+fn colored(color: Color) -> ContentStyle {
+    ContentStyle {
+        foreground_color: Some(color),
+        ..ContentStyle::new()
+    }
+}
+
+fn bold(color: Color) -> ContentStyle {
+    let mut style = colored(color);
+    style.attributes.set(Attribute::Bold);
+    style
+}
@@ -0,0 +1,13 @@
+/// What a menu does when its timeout runs out before the user decides.
+///
+/// # Variants
+/// * `SelectDefault` - Confirms the item highlighted when the menu opened, i.e. the default item.
+///   In multi-select mode the checked items are confirmed. A destructive default, or one that
+///   opens a submenu, is never confirmed this way; the menu closes as with `Cancel` instead.
+/// * `Cancel` - Closes the menu as if the user had pressed Escape.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimeoutAction {
+    #[default]
+    SelectDefault,
+    Cancel,
+}
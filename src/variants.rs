@@ -0,0 +1,63 @@
+use crate::error::TerMenuError;
+use crate::menu::select_index;
+use std::fmt::Display;
+
+/// A type with a fixed set of values, usually a fieldless enum, that [`select_enum`] can offer.
+///
+/// With the `strum` feature, every enum deriving `strum::EnumIter` implements it, listing its
+/// variants in declaration order.
+///
+/// # Example
+/// ```no_run
+/// use std::fmt;
+/// use ter_menu::{Variants, select_enum};
+///
+/// #[derive(Debug)]
+/// enum Region {
+///     Europe,
+///     Asia,
+/// }
+///
+/// impl fmt::Display for Region {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         f.write_str(match self {
+///             Region::Europe => "eu-west-1",
+///             Region::Asia => "ap-southeast-1",
+///         })
+///     }
+/// }
+///
+/// impl Variants for Region {
+///     fn variants() -> Vec<Self> {
+///         vec![Region::Europe, Region::Asia]
+///     }
+/// }
+///
+/// if let Some(region) = select_enum::<Region>()? {
+///     println!("deploying to {:?}", region);
+/// }
+/// # Ok::<(), ter_menu::TerMenuError>(())
+/// ```
+pub trait Variants: Sized {
+    /// Every value, in the order they are listed.
+    fn variants() -> Vec<Self>;
+}
+
+#[cfg(feature = "strum")]
+impl<E: strum::IntoEnumIterator> Variants for E {
+    fn variants() -> Vec<Self> {
+        E::iter().collect()
+    }
+}
+
+/// Lets the user pick one variant of `E`, listed by its [`Display`] name, like [`select`](crate::select)
+/// over [`Variants::variants`]. All variants are shown at once, as far as the terminal allows.
+///
+/// # Returns
+/// `Ok(Some(variant))` with the confirmed variant, `Ok(None)` if the user cancelled or `E` has no
+/// variants, or a [`TerMenuError`] if the terminal could not be driven.
+pub fn select_enum<E: Variants + Display>() -> Result<Option<E>, TerMenuError> {
+    let mut variants = E::variants();
+    let chosen = select_index(&variants, variants.len())?;
+    Ok(chosen.map(|idx| variants.swap_remove(idx)))
+}
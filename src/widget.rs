@@ -0,0 +1,115 @@
+use crate::item::MenuEntry;
+use crate::state::MenuState;
+use crossterm::style::{Attribute, Color as TermColor, ContentStyle};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{StatefulWidget, Widget};
+use std::marker::PhantomData;
+
+/// Draws a [`MenuState`] into a ratatui [`Buffer`]. Requires the `ratatui` feature.
+///
+/// The selection logic, key map, filter and theme are the same as for the other menus; only the
+/// output goes through ratatui instead of the raw-stdout renderer. Key presses are still fed to
+/// the state with [`MenuState::handle_key`].
+///
+/// Rendering fits the menu to `area`: pages are shortened to its height and lines are cut at
+/// its width. `&MenuState` also implements [`Widget`] for drawing without the wrapper.
+///
+/// # Example
+/// ```ignore
+/// let mut state = MenuState::new(&options, 10);
+/// terminal.draw(|f| f.render_stateful_widget(DropDownWidget::new(), f.area(), &mut state))?;
+/// ```
+#[derive(Debug)]
+pub struct DropDownWidget<'a, T> {
+    state: PhantomData<MenuState<'a, T>>,
+}
+
+impl<T> DropDownWidget<'_, T> {
+    /// Creates the widget. Everything about the menu's look is configured on the [`MenuState`].
+    pub fn new() -> Self {
+        Self { state: PhantomData }
+    }
+}
+
+impl<T> Default for DropDownWidget<'_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T: MenuEntry> StatefulWidget for DropDownWidget<'a, T> {
+    type State = MenuState<'a, T>;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        state.resize(area.width, area.height);
+        Widget::render(&*state, area, buf);
+    }
+}
+
+impl<T: MenuEntry> Widget for &MenuState<'_, T> {
+    /// Draws the menu as last sized by [`MenuState::resize`], cut to fit `area`.
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let lines = self.frame().lines;
+        for (y, line) in (area.top()..area.bottom()).zip(&lines) {
+            let mut x = area.left();
+            for span in &line.spans {
+                let room = usize::from(area.right().saturating_sub(x));
+                (x, _) = buf.set_stringn(x, y, &span.text, room, style(span.style));
+            }
+        }
+    }
+}
+
+/// Converts a crossterm style to ratatui's, following ratatui's own crossterm backend.
+fn style(style: ContentStyle) -> Style {
+    let mut converted = Style::new();
+    if let Some(color) = style.foreground_color {
+        converted = converted.fg(color_of(color));
+    }
+    if let Some(color) = style.background_color {
+        converted = converted.bg(color_of(color));
+    }
+    let modifiers = [
+        (Attribute::Bold, Modifier::BOLD),
+        (Attribute::Dim, Modifier::DIM),
+        (Attribute::Italic, Modifier::ITALIC),
+        (Attribute::Underlined, Modifier::UNDERLINED),
+        (Attribute::SlowBlink, Modifier::SLOW_BLINK),
+        (Attribute::RapidBlink, Modifier::RAPID_BLINK),
+        (Attribute::Reverse, Modifier::REVERSED),
+        (Attribute::Hidden, Modifier::HIDDEN),
+        (Attribute::CrossedOut, Modifier::CROSSED_OUT),
+    ];
+    for (attribute, modifier) in modifiers {
+        if style.attributes.has(attribute) {
+            converted = converted.add_modifier(modifier);
+        }
+    }
+    converted
+}
+
+fn color_of(color: TermColor) -> Color {
+    match color {
+        TermColor::Reset => Color::Reset,
+        TermColor::Black => Color::Black,
+        TermColor::DarkRed => Color::Red,
+        TermColor::DarkGreen => Color::Green,
+        TermColor::DarkYellow => Color::Yellow,
+        TermColor::DarkBlue => Color::Blue,
+        TermColor::DarkMagenta => Color::Magenta,
+        TermColor::DarkCyan => Color::Cyan,
+        TermColor::Grey => Color::Gray,
+        TermColor::DarkGrey => Color::DarkGray,
+        TermColor::Red => Color::LightRed,
+        TermColor::Green => Color::LightGreen,
+        TermColor::Yellow => Color::LightYellow,
+        TermColor::Blue => Color::LightBlue,
+        TermColor::Magenta => Color::LightMagenta,
+        TermColor::Cyan => Color::LightCyan,
+        TermColor::White => Color::White,
+        TermColor::Rgb { r, g, b } => Color::Rgb(r, g, b),
+        TermColor::AnsiValue(value) => Color::Indexed(value),
+    }
+}
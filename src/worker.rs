@@ -0,0 +1,57 @@
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// The interaction thread behind a [`TerminalDropDown`](crate::TerminalDropDown) or
+/// [`TerminalMultiSelect`](crate::TerminalMultiSelect), which can be joined without blocking.
+///
+/// `JoinHandle` alone cannot wait with a timeout, so the thread also holds the sending half of a
+/// channel that is dropped when it exits, even by panicking.
+#[derive(Debug)]
+pub(crate) struct Worker<R> {
+    /// `None` once the thread's outcome has been handed out.
+    handle: Option<JoinHandle<R>>,
+    finished: Receiver<()>,
+}
+
+impl<R: Send + 'static> Worker<R> {
+    pub(crate) fn spawn(f: impl FnOnce() -> R + Send + 'static) -> Self {
+        let (sender, finished) = mpsc::channel();
+        let handle = thread::spawn(move || {
+            let _sender = sender;
+            f()
+        });
+        Self {
+            handle: Some(handle),
+            finished,
+        }
+    }
+
+    /// Whether the thread has finished, or its outcome was already handed out.
+    pub(crate) fn is_finished(&self) -> bool {
+        self.handle.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    /// Joins the thread if it has finished, without blocking.
+    pub(crate) fn try_join(&mut self) -> Option<thread::Result<R>> {
+        self.join_timeout(Duration::ZERO)
+    }
+
+    /// Joins the thread if it finishes within `timeout`.
+    pub(crate) fn join_timeout(&mut self, timeout: Duration) -> Option<thread::Result<R>> {
+        match self.finished.recv_timeout(timeout) {
+            Err(RecvTimeoutError::Timeout) => None,
+            // Nothing is ever sent, so anything else means the sender was dropped.
+            _ => self.handle.take().map(JoinHandle::join),
+        }
+    }
+
+    /// Blocks until the thread finishes.
+    ///
+    /// # Returns
+    /// `None` if the outcome was already handed out by [`Worker::try_join`] or
+    /// [`Worker::join_timeout`].
+    pub(crate) fn join(self) -> Option<thread::Result<R>> {
+        self.handle.map(JoinHandle::join)
+    }
+}
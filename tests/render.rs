@@ -0,0 +1,39 @@
+use ter_menu::{MenuItem, Status, render_to_string};
+
+const ITEMS: [&str; 5] = ["one", "two", "three", "four", "five"];
+
+#[test]
+fn plain_frame_shows_the_page_around_the_cursor() {
+    let frame = render_to_string(&ITEMS, 3, 2, false);
+    let expected = [
+        "Please select.（ESC for canceling）:",
+        "Total: 5 | Showing: 3 - 4",
+        "",
+        "  three",
+        // The highlight bar is padded to the widest row on the page.
+        "> four ",
+        "",
+        "↑: Up | ↓: Down | Enter: Confirm | ESC: Cancel",
+    ];
+    assert_eq!(frame, expected.join("\n"));
+}
+
+#[test]
+fn ansi_frame_styles_only_the_highlighted_row() {
+    let frame = render_to_string(&ITEMS, 0, 2, true);
+    let lines: Vec<&str> = frame.lines().collect();
+    assert_eq!(lines[3], "\u{1b}[7m> \u{1b}[0m\u{1b}[7mone\u{1b}[0m");
+    assert_eq!(lines[4], "  two");
+}
+
+#[test]
+fn status_badges_are_drawn_in_their_color() {
+    let services = [
+        MenuItem::new("api", ()).status(Status::Ok),
+        MenuItem::new("worker", ()).status(Status::Error),
+    ];
+    let frame = render_to_string(&services, 0, 2, true);
+    let lines: Vec<&str> = frame.lines().collect();
+    assert_eq!(lines[4], "  \u{1b}[38;5;9m\u{1b}[1m[ERROR] \u{1b}[0mworker");
+    assert!(render_to_string(&services, 0, 2, false).contains("\n> [OK] api      \n"));
+}
@@ -0,0 +1,1851 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Color, ContentStyle, Stylize};
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use ter_menu::{
+    Action, Autocomplete, Border, CancelReason, Column, Completion, Confirmation,
+    DescriptionLayout, DropDownBuilder, EventSource, FileStore, Flow, FrameRecorder, Handled,
+    Input, KeyMap, KeyOutcome, MenuEntry, MenuItem, MenuState, Output, Password, PathPicker,
+    Reorder, Replay, ScriptedEvents, SelectionResult, SortMode, Strings, TerMenuError,
+    TerminalDropDown, TerminalMultiSelect, Theme, TimeoutAction,
+};
+
+const ITEMS: [&str; 4] = ["apple", "banana", "cherry", "date"];
+
+fn menu(
+    script: ScriptedEvents,
+    recorder: &FrameRecorder,
+) -> DropDownBuilder<&'static str, fn(&&str)> {
+    let ignore: fn(&&str) = |_| {};
+    TerminalDropDown::builder()
+        .items(ITEMS.map(|item| (item, ignore)))
+        .page_size(3)
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+}
+
+#[test]
+fn arrows_then_enter_select_the_highlighted_item() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::Down, KeyCode::Down, KeyCode::Enter]);
+    let result = menu(script, &recorder).build().wait();
+
+    assert_eq!(result.selected(), Some(ITEMS[2]));
+    let frames = recorder.frames();
+    assert_eq!(frames.len(), 3, "initial frame plus one per arrow key");
+    assert!(frames[0].contains("> apple"));
+    assert!(frames[2].contains("> cherry"));
+    assert!(recorder.printed().contains("Confirm delete: cherry"));
+}
+
+#[test]
+fn paging_past_the_window_scrolls_the_list() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::End, KeyCode::Esc]);
+    let result = menu(script, &recorder).build().wait();
+
+    assert!(result.is_cancelled());
+    let last = recorder.last_frame().unwrap();
+    assert!(last.contains("> date"));
+    assert!(!last.contains("apple"));
+    assert!(recorder.printed().contains("Delete canceled."));
+}
+
+#[test]
+fn typing_narrows_a_filtered_menu() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::text("ery").then(ScriptedEvents::keys([KeyCode::Enter]));
+    let result = menu(script, &recorder).filter(true).build().wait();
+
+    assert_eq!(result.selected(), Some("cherry"));
+    let last = recorder.last_frame().unwrap();
+    assert!(last.contains("Filter: ery"));
+    assert!(!last.contains("banana"));
+}
+
+#[test]
+fn the_filter_predicate_toggles_and_keeps_the_cursor_nearby() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([
+        KeyCode::Down,
+        KeyCode::Down,
+        KeyCode::Tab,
+        KeyCode::Tab,
+        KeyCode::Enter,
+    ]);
+    let result = menu(script, &recorder)
+        .filter_predicate(|item| item.contains('a'))
+        .build()
+        .wait();
+
+    assert_eq!(result.selected(), Some("date"));
+    let frames = recorder.frames();
+    assert!(frames[3].contains("> date") && !frames[3].contains("cherry"));
+    assert!(frames[3].contains("Matched: 3"));
+    assert!(frames[4].contains("> date") && frames[4].contains("cherry"));
+}
+
+#[test]
+fn an_exhausted_script_ends_with_an_error() {
+    let recorder = FrameRecorder::new();
+    let result = menu(ScriptedEvents::keys([KeyCode::Down]), &recorder)
+        .build()
+        .wait();
+
+    assert!(matches!(
+        result,
+        SelectionResult::Error(TerMenuError::EventRead(_))
+    ));
+}
+
+#[test]
+fn a_key_press_stops_the_countdown() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::Down, KeyCode::Enter]);
+    let result = menu(script, &recorder)
+        .default_index(1)
+        .timeout(Duration::from_secs(5), TimeoutAction::SelectDefault)
+        .build()
+        .wait();
+
+    assert_eq!(result.selected(), Some("cherry"));
+    let frames = recorder.frames();
+    assert!(frames[0].contains("Auto-selecting 'banana' in 5s"));
+    assert!(!frames[1].contains("Auto-selecting"));
+}
+
+#[test]
+fn a_destructive_default_is_not_confirmed_when_the_time_runs_out() {
+    let recorder = FrameRecorder::new();
+    let (_sender, receiver) = mpsc::channel();
+    let chosen = Arc::new(AtomicUsize::new(0));
+    let callback = {
+        let chosen = Arc::clone(&chosen);
+        move |_: &MenuItem<&str>| {
+            chosen.fetch_add(1, Ordering::SeqCst);
+        }
+    };
+    let selection = TerminalDropDown::builder()
+        .items(
+            [
+                MenuItem::new("Keep", "keep"),
+                MenuItem::new("Drop database", "drop").destructive(Confirmation::TypeLabel),
+            ]
+            .map(|item| (item, callback.clone())),
+        )
+        .default_index(1)
+        .timeout(Duration::from_millis(50), TimeoutAction::SelectDefault)
+        .events(ChannelEvents {
+            receiver,
+            next: None,
+        })
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait_selection()
+        .unwrap();
+
+    assert_eq!(selection.value, None);
+    assert_eq!(selection.cancelled_by, Some(CancelReason::Timeout));
+    assert_eq!(chosen.load(Ordering::SeqCst), 0);
+    assert!(recorder.frames()[0].contains("Closing in 1s"));
+}
+
+#[test]
+fn a_submenu_default_is_not_returned_when_the_time_runs_out() {
+    let (_sender, receiver) = mpsc::channel();
+    let ignore: fn(&MenuItem<&str>) = |_| {};
+    let selection = TerminalDropDown::builder()
+        .items(
+            [
+                MenuItem::submenu("Network", [MenuItem::new("Proxy", "proxy")]),
+                MenuItem::new("Quit", "quit"),
+            ]
+            .map(|item| (item, ignore)),
+        )
+        .timeout(Duration::from_millis(50), TimeoutAction::SelectDefault)
+        .events(ChannelEvents {
+            receiver,
+            next: None,
+        })
+        .output(Output::Frames(FrameRecorder::new()))
+        .build()
+        .wait_selection()
+        .unwrap();
+
+    assert_eq!(selection.value, None);
+    assert_eq!(selection.cancelled_by, Some(CancelReason::Timeout));
+}
+
+#[test]
+fn ctrl_c_interrupts_even_while_filtering() {
+    let recorder = FrameRecorder::new();
+    let ctrl_c = Event::Key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+    let script = ScriptedEvents::text("an").then(ScriptedEvents::new([ctrl_c]));
+    let result = menu(script, &recorder).filter(true).build().wait();
+
+    assert!(result.is_interrupted());
+    assert!(!recorder.printed().contains("Delete canceled."));
+}
+
+#[test]
+fn submenus_open_go_back_and_report_the_path() {
+    let settings = MenuItem::submenu(
+        "Settings",
+        [
+            MenuItem::new("Theme", "theme"),
+            MenuItem::submenu(
+                "Network",
+                [MenuItem::new("DNS", "dns"), MenuItem::new("Proxy", "proxy")],
+            ),
+        ],
+    );
+    let ignore: fn(&MenuItem<&str>) = |_| {};
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([
+        KeyCode::Enter,
+        KeyCode::Down,
+        KeyCode::Enter,
+        KeyCode::Left,
+        KeyCode::Enter,
+        KeyCode::Down,
+        KeyCode::Enter,
+    ]);
+    let result = TerminalDropDown::builder()
+        .items([(MenuItem::new("Quit", "quit"), ignore), (settings, ignore)])
+        .default_index(1)
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait_path();
+
+    let path: Vec<String> = result
+        .selected()
+        .unwrap()
+        .into_iter()
+        .map(|item| item.label)
+        .collect();
+    assert_eq!(path, ["Settings", "Network", "Proxy"]);
+    let frames = recorder.frames();
+    assert!(frames[1].contains("Settings\n"));
+    assert!(frames[3].contains("Settings > Network"));
+    assert!(
+        frames[4].contains("> Network ›"),
+        "Left returns to the parent"
+    );
+}
+
+#[test]
+fn a_flow_passes_earlier_answers_to_later_steps() {
+    #[derive(Default)]
+    struct Setup {
+        environment: &'static str,
+        features: Vec<&'static str>,
+        deploy: bool,
+    }
+
+    let script = ScriptedEvents::keys([
+        KeyCode::Down,
+        KeyCode::Enter,
+        KeyCode::Char(' '),
+        KeyCode::Enter,
+        KeyCode::Enter,
+    ]);
+    let recorder = FrameRecorder::new();
+    let setup = Flow::new(Setup::default())
+        .select(
+            "Environment:",
+            |_| vec!["staging", "production"],
+            |setup, environment| setup.environment = environment,
+        )
+        .multi_select(
+            "Features:",
+            |setup| match setup.environment {
+                "staging" => vec!["metrics", "debug-ui"],
+                _ => vec!["audit-log", "metrics"],
+            },
+            |setup, features| setup.features = features,
+        )
+        .confirm(
+            |setup| format!("Deploy to {}?", setup.environment),
+            |setup, deploy| setup.deploy = deploy,
+        )
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .run()
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(setup.environment, "production");
+    assert_eq!(setup.features, ["audit-log"]);
+    assert!(setup.deploy);
+    assert!(
+        recorder
+            .last_frame()
+            .unwrap()
+            .starts_with("Deploy to production?")
+    );
+}
+
+#[test]
+fn input_edits_at_the_cursor_and_validates_on_enter() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::text("mian")
+        .then(ScriptedEvents::keys([
+            KeyCode::Left,
+            KeyCode::Left,
+            KeyCode::Backspace,
+            KeyCode::Enter,
+        ]))
+        .then(ScriptedEvents::keys([KeyCode::Right]))
+        .then(ScriptedEvents::text("i"))
+        .then(ScriptedEvents::keys([KeyCode::End, KeyCode::Enter]));
+    let branch = Input::new("Branch: ")
+        .validate(|text| match text {
+            "man" => Err("Too short".to_string()),
+            _ => Ok(()),
+        })
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .run()
+        .unwrap();
+
+    assert_eq!(branch.as_deref(), Some("main"));
+    assert!(
+        recorder
+            .frames()
+            .contains(&"Branch: man\nToo short".to_string())
+    );
+}
+
+#[test]
+fn password_masks_the_typed_text() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::text("hunter2")
+        .then(ScriptedEvents::keys([KeyCode::Backspace, KeyCode::Enter]));
+    let secret = Password::new("Token: ")
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .run()
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(secret.expose(), "hunter");
+    assert_eq!(recorder.last_frame().unwrap(), "Token: ******");
+    assert!(!recorder.frames().concat().contains("hunter"));
+    assert_eq!(format!("{:?}", secret), "Secret(..)");
+}
+
+#[test]
+fn autocomplete_lists_matches_below_the_text() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::text("web").then(ScriptedEvents::keys([
+        KeyCode::Tab,
+        KeyCode::Tab,
+        KeyCode::Enter,
+    ]));
+    let hosts = ["web-1.prod", "db-1.prod", "web-2.prod"];
+    let completion = Autocomplete::new("Host: ", hosts)
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .run()
+        .unwrap();
+
+    assert_eq!(completion, Some(Completion::Item("web-2.prod")));
+    assert_eq!(
+        recorder.last_frame().unwrap(),
+        "Host: web\n  web-1.prod\n> web-2.prod"
+    );
+}
+
+#[test]
+fn autocomplete_submits_the_typed_text_from_the_text_field() {
+    let script = ScriptedEvents::text("new-host").then(ScriptedEvents::keys([KeyCode::Enter]));
+    let completion =
+        Autocomplete::with_provider("Host: ", |text: &str| vec![format!("{}.prod", text)])
+            .events(script)
+            .output(Output::Frames(FrameRecorder::new()))
+            .run()
+            .unwrap();
+
+    assert_eq!(completion, Some(Completion::Text("new-host".to_string())));
+}
+
+#[test]
+fn path_picker_descends_ascends_and_filters_by_extension() {
+    let root = std::env::temp_dir().join(format!("ter_menu_path_picker_{}", std::process::id()));
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    for file in [
+        "Cargo.toml",
+        "notes.txt",
+        ".env",
+        "src/main.rs",
+        "src/lib.rs",
+        "src/README.md",
+    ] {
+        std::fs::write(root.join(file), "").unwrap();
+    }
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([
+        KeyCode::Enter,
+        KeyCode::Backspace,
+        KeyCode::Enter,
+        KeyCode::Down,
+        KeyCode::Enter,
+    ]);
+    let picked = PathPicker::new(&root)
+        .extensions(["rs", "toml"])
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .run();
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(picked.unwrap(), Some(root.join("src").join("main.rs")));
+    let frames = recorder.frames();
+    assert!(frames[0].contains("src/") && frames[0].contains("Cargo.toml"));
+    assert!(!frames[0].contains("notes.txt") && !frames[0].contains(".env"));
+    assert!(frames[1].contains("lib.rs") && !frames[1].contains("README.md"));
+    assert!(frames[2].contains("Cargo.toml"));
+}
+
+/// Events sent by the test while the menu runs, so that it can act between key presses.
+struct ChannelEvents {
+    receiver: Receiver<Event>,
+    next: Option<Event>,
+}
+
+impl EventSource for ChannelEvents {
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool> {
+        if self.next.is_none() {
+            self.next = self.receiver.recv_timeout(timeout).ok();
+        }
+        Ok(self.next.is_some())
+    }
+
+    fn read(&mut self) -> io::Result<Event> {
+        match self.next.take() {
+            Some(event) => Ok(event),
+            None => self
+                .receiver
+                .recv()
+                .map_err(|e| io::Error::new(io::ErrorKind::UnexpectedEof, e)),
+        }
+    }
+}
+
+/// Blocks until `recorder` holds `count` frames.
+fn wait_for_frames(recorder: &FrameRecorder, count: usize) -> Vec<String> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let frames = recorder.frames();
+        if frames.len() >= count {
+            return frames;
+        }
+        assert!(Instant::now() < deadline, "the menu stopped drawing");
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn items_added_and_removed_while_open_keep_the_highlight() {
+    let recorder = FrameRecorder::new();
+    let (sender, receiver) = mpsc::channel();
+    let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+    let ignore: fn(&&str) = |_| {};
+    let drop_down = TerminalDropDown::builder()
+        .items(ITEMS.map(|item| (item, ignore)))
+        .events(ChannelEvents {
+            receiver,
+            next: None,
+        })
+        .output(Output::Frames(recorder.clone()))
+        .build();
+    let items = drop_down.items_handle();
+
+    sender.send(key(KeyCode::Down)).unwrap();
+    sender.send(key(KeyCode::Down)).unwrap();
+    wait_for_frames(&recorder, 3);
+    items.remove("banana");
+    items.push("elderberry");
+    let frames = wait_for_frames(&recorder, 4);
+    assert!(frames[3].contains("> cherry") && frames[3].contains("elderberry"));
+    assert!(!frames[3].contains("banana"));
+    items.remove("cherry");
+    let frames = wait_for_frames(&recorder, 5);
+    assert!(frames[4].contains("> date") && frames[4].contains("Total: 3"));
+    sender.send(key(KeyCode::Enter)).unwrap();
+
+    assert_eq!(drop_down.wait().selected(), Some("date"));
+}
+
+/// Blocks until the last frame `recorder` holds satisfies `done`.
+fn wait_for_frame(recorder: &FrameRecorder, done: impl Fn(&str) -> bool) -> String {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        if let Some(frame) = recorder.last_frame().filter(|frame| done(frame)) {
+            return frame;
+        }
+        assert!(
+            Instant::now() < deadline,
+            "the menu never drew the expected frame"
+        );
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn streamed_items_show_a_loading_row_until_the_source_ends() {
+    let recorder = FrameRecorder::new();
+    let (keys, receiver) = mpsc::channel();
+    let (stream, items) = mpsc::channel();
+    let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+    let ignore: fn(&&str) = |_| {};
+    let drop_down = TerminalDropDown::builder()
+        .items(ITEMS.map(|item| (item, ignore)))
+        .item_stream(items)
+        .events(ChannelEvents {
+            receiver,
+            next: None,
+        })
+        .output(Output::Frames(recorder.clone()))
+        .build();
+
+    assert!(wait_for_frames(&recorder, 1)[0].contains("Loading…"));
+    stream.send("fig").unwrap();
+    let frame = wait_for_frame(&recorder, |frame| frame.contains("fig"));
+    assert!(frame.contains("Loading…"));
+    drop(stream);
+    wait_for_frame(&recorder, |frame| !frame.contains("Loading…"));
+    keys.send(key(KeyCode::End)).unwrap();
+    keys.send(key(KeyCode::Enter)).unwrap();
+
+    assert_eq!(drop_down.wait().selected(), Some("fig"));
+}
+
+/// Counts how often any row's label is formatted.
+static LABELS_FORMATTED: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Row(usize);
+
+impl MenuEntry for Row {
+    fn label(&self) -> String {
+        LABELS_FORMATTED.fetch_add(1, Ordering::Relaxed);
+        format!("row {}", self.0)
+    }
+}
+
+#[test]
+fn only_the_rows_on_the_page_are_formatted() {
+    let ignore: fn(&Row) = |_| {};
+    let script = ScriptedEvents::keys([KeyCode::Down, KeyCode::PageDown, KeyCode::End])
+        .then(ScriptedEvents::keys([KeyCode::Enter]));
+    let result = TerminalDropDown::builder()
+        .items((0..200_000).map(|i| (Row(i), ignore)))
+        .page_size(10)
+        .events(script)
+        .output(Output::Frames(FrameRecorder::new()))
+        .build()
+        .wait();
+
+    assert_eq!(result.selected(), Some(Row(199_999)));
+    assert!(LABELS_FORMATTED.load(Ordering::Relaxed) < 200);
+}
+
+static ITEMS_CLONED: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct Costly(&'static str);
+
+impl Clone for Costly {
+    fn clone(&self) -> Self {
+        ITEMS_CLONED.fetch_add(1, Ordering::SeqCst);
+        Costly(self.0)
+    }
+}
+
+impl std::fmt::Display for Costly {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+#[test]
+fn a_dropdown_never_clones_its_items() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::Down, KeyCode::Enter]);
+    let ignore: fn(&Costly) = |_| {};
+    let result = TerminalDropDown::builder()
+        .items(ITEMS.map(|item| (Costly(item), ignore)))
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+
+    assert_eq!(result.selected(), Some(Costly("banana")));
+    assert_eq!(ITEMS_CLONED.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn items_without_eq_or_hash_can_be_listed() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::End, KeyCode::Enter]);
+    let chosen = Arc::new(Mutex::new(None));
+    let on_select = {
+        let chosen = chosen.clone();
+        move |scale: &f64| *chosen.lock().unwrap() = Some(*scale)
+    };
+    let result = TerminalDropDown::builder()
+        .items([0.5, 1.0, 1.5].map(|scale| (scale, on_select.clone())))
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+
+    assert_eq!(result.selected(), Some(1.5));
+    assert_eq!(*chosen.lock().unwrap(), Some(1.5));
+}
+
+#[test]
+fn waiting_after_try_wait_returned_the_outcome_is_an_error() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::Down, KeyCode::Enter]);
+    let mut drop_down = menu(script, &recorder).build();
+
+    assert_eq!(
+        drop_down
+            .wait_timeout(Duration::from_secs(5))
+            .unwrap()
+            .selected(),
+        Some("banana")
+    );
+    assert!(drop_down.is_finished());
+    assert!(drop_down.try_wait().is_none());
+    assert!(matches!(
+        drop_down.wait(),
+        SelectionResult::Error(TerMenuError::OutcomeTaken)
+    ));
+
+    let mut multi = TerminalMultiSelect::builder()
+        .items(ITEMS)
+        .events(ScriptedEvents::keys([KeyCode::Char(' '), KeyCode::Enter]))
+        .output(Output::Frames(FrameRecorder::new()))
+        .build();
+    assert!(multi.wait_timeout(Duration::from_secs(5)).is_some());
+    assert!(matches!(
+        multi.wait(),
+        SelectionResult::Error(TerMenuError::OutcomeTaken)
+    ));
+}
+
+#[test]
+fn wait_selection_reports_how_the_menu_was_used() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::Down, KeyCode::Up, KeyCode::Down, KeyCode::Enter]);
+    let selection = menu(script, &recorder).build().wait_selection().unwrap();
+
+    assert_eq!(selection.value, Some("banana"));
+    assert_eq!(selection.index, Some(1));
+    assert_eq!(selection.keystrokes, 4);
+    assert_eq!(selection.cancelled_by, None);
+
+    let script = ScriptedEvents::keys([KeyCode::Down, KeyCode::Esc]);
+    let selection = menu(script, &recorder).build().wait_selection().unwrap();
+    assert_eq!(selection.value, None);
+    assert_eq!(selection.index, None);
+    assert_eq!(selection.keystrokes, 2);
+    assert_eq!(selection.cancelled_by, Some(CancelReason::Escape));
+}
+
+#[test]
+fn the_default_value_is_highlighted_when_the_menu_opens() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::Enter]);
+    let result = menu(script, &recorder)
+        .default_value(&"cherry")
+        .build()
+        .wait();
+
+    assert_eq!(result.selected(), Some("cherry"));
+    assert!(recorder.frames()[0].contains("> cherry"));
+}
+
+#[test]
+fn the_last_choice_is_remembered_across_runs() {
+    let dir = std::env::temp_dir().join(format!("ter_menu_store_{}", std::process::id()));
+    let store = FileStore::new(dir.join("choices"));
+    let recorder = FrameRecorder::new();
+
+    let script = ScriptedEvents::keys([KeyCode::Down, KeyCode::Down, KeyCode::Enter]);
+    let first = menu(script, &recorder)
+        .remember("fruit", store.clone())
+        .build()
+        .wait();
+    let script = ScriptedEvents::keys([KeyCode::Enter]);
+    let second = menu(script, &recorder)
+        .remember("fruit", store.clone())
+        .build()
+        .wait();
+    let script = ScriptedEvents::keys([KeyCode::Enter]);
+    let other_menu = menu(script, &recorder)
+        .remember("vegetable", store)
+        .build()
+        .wait();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(first.selected(), Some("cherry"));
+    assert_eq!(second.selected(), Some("cherry"));
+    assert_eq!(other_menu.selected(), Some("apple"));
+}
+
+#[test]
+fn without_wrap_around_the_ends_ring_the_bell() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([
+        KeyCode::Up,
+        KeyCode::Down,
+        KeyCode::Down,
+        KeyCode::Down,
+        KeyCode::Down,
+        KeyCode::Enter,
+    ]);
+    let result = menu(script, &recorder)
+        .wrap_navigation(false)
+        .bell_at_edges(true)
+        .build()
+        .wait();
+
+    assert_eq!(result.selected(), Some("date"));
+    assert!(recorder.frames()[1].contains("> apple"));
+    assert_eq!(recorder.printed().matches('\x07').count(), 2);
+}
+
+#[test]
+fn sorting_cycles_at_runtime_and_follows_the_highlight() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([
+        KeyCode::Down,
+        KeyCode::Char('s'),
+        KeyCode::Char('s'),
+        KeyCode::Enter,
+    ]);
+    let ignore: fn(&&str) = |_| {};
+    let result = TerminalDropDown::builder()
+        .items(["file10", "File9", "file1"].map(|item| (item, ignore)))
+        .sort(SortMode::Alphabetical)
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+
+    let frames = recorder.frames();
+    assert!(frames[0].contains("> file1 \n  file10\n  File9"));
+    assert!(frames[0].contains("s: Sort (A-Z)"));
+    assert!(frames[2].contains("  file1\n  File9\n> file10"));
+    assert!(frames[2].contains("s: Sort (natural)"));
+    assert!(frames[3].contains("> file10\n  File9\n  file1"));
+    assert_eq!(result.selected(), Some("file10"));
+}
+
+#[test]
+fn a_formatter_replaces_the_label_everywhere() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::Char('6'), KeyCode::Enter]);
+    let result = menu(script, &recorder)
+        .filter(true)
+        .format(|item| format!("{} ({} letters)", item, item.len()))
+        .build()
+        .wait();
+
+    assert_eq!(result.selected(), Some("banana"));
+    let frames = recorder.frames();
+    assert!(frames[0].contains("> apple (5 letters)"));
+    assert!(frames[1].contains("> banana (6 letters)"));
+    assert!(
+        recorder
+            .printed()
+            .contains("Confirm delete: banana (6 letters)")
+    );
+}
+
+#[test]
+fn columns_line_up_under_their_titles() {
+    type Pod = MenuItem<(&'static str, u32)>;
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::Down, KeyCode::Enter]);
+    let ignore: fn(&Pod) = |_| {};
+    let pods = [
+        ("web-7d9f", "Running", 0),
+        ("worker-with-a-very-long-name", "CrashLoopBackOff", 12),
+    ];
+    let result = TerminalDropDown::builder()
+        .items(
+            pods.map(|(name, status, restarts)| (MenuItem::new(name, (status, restarts)), ignore)),
+        )
+        .columns([
+            Column::new("NAME", |pod: &Pod| pod.label.clone()).width(12),
+            Column::new("RESTARTS", |pod: &Pod| pod.value.1.to_string()).align_right(),
+            Column::new("STATUS", |pod: &Pod| pod.value.0.to_string()),
+        ])
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+
+    assert_eq!(result.selected().map(|pod| pod.value.1), Some(12));
+    let frame = &recorder.frames()[0];
+    assert!(frame.contains("\n  NAME          RESTARTS  STATUS\n"));
+    assert!(frame.contains("\n> web-7d9f             0  Running"));
+    assert!(frame.contains("\n  worker-with…        12  CrashLoopBackOff\n"));
+}
+
+#[test]
+fn a_grid_moves_across_rows_and_columns() {
+    let recorder = FrameRecorder::new();
+    let script =
+        ScriptedEvents::keys([KeyCode::Right, KeyCode::Down, KeyCode::Left, KeyCode::Enter]);
+    let ignore: fn(&&str) = |_| {};
+    let result = TerminalDropDown::builder()
+        .items(["en", "de", "fr", "es", "it"].map(|item| (item, ignore)))
+        .grid(2)
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+
+    let frames = recorder.frames();
+    assert!(frames[0].contains("> en    de\n  fr    es\n  it\n"));
+    assert!(frames[0].contains("←/→: Left/Right"));
+    assert!(frames[1].contains("  en  > de"));
+    assert_eq!(result.selected(), Some("fr"));
+}
+
+#[test]
+fn a_horizontal_menu_fits_on_one_line() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([
+        KeyCode::Right,
+        KeyCode::Right,
+        KeyCode::Left,
+        KeyCode::Enter,
+    ]);
+    let ignore: fn(&&str) = |_| {};
+    let result = TerminalDropDown::builder()
+        .items(["staging", "prod", "dev"].map(|item| (item, ignore)))
+        .prompt("Environment:")
+        .horizontal(true)
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+
+    let frames = recorder.frames();
+    assert_eq!(frames[0], "Environment: < [staging] | prod | dev >");
+    assert_eq!(frames[3], "Environment: < staging | [prod] | dev >");
+    assert_eq!(result.selected(), Some("prod"));
+}
+
+#[test]
+fn enter_waits_until_the_checked_items_are_within_limits() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([
+        KeyCode::Enter,
+        KeyCode::Char(' '),
+        KeyCode::Down,
+        KeyCode::Char(' '),
+        KeyCode::Down,
+        KeyCode::Char(' '),
+        KeyCode::Enter,
+    ]);
+    let result = TerminalMultiSelect::builder()
+        .items(ITEMS)
+        .min_selected(1)
+        .max_selected(2)
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+
+    let frames = recorder.frames();
+    assert!(frames[0].contains("Select 1-2 (0 selected)"));
+    assert!(frames.last().unwrap().contains("[ ] cherry"));
+    assert!(
+        frames
+            .last()
+            .unwrap()
+            .contains("Select 1-2 (2 selected, max reached)")
+    );
+    assert_eq!(result.selected(), Some(vec!["apple", "banana"]));
+}
+
+#[test]
+fn bulk_checks_change_only_the_items_the_filter_shows() {
+    let ctrl = |c| Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL));
+    let backspace = || Event::Key(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+    let script = ScriptedEvents::text("an")
+        .then(ScriptedEvents::new([ctrl('a'), backspace(), backspace()]))
+        .then(ScriptedEvents::text("e"))
+        .then(ScriptedEvents::new([ctrl('i'), ctrl('a'), backspace()]))
+        .then(ScriptedEvents::keys([KeyCode::Enter]));
+    let recorder = FrameRecorder::new();
+    let result = TerminalMultiSelect::builder()
+        .items(ITEMS)
+        .filter(true)
+        .keymap(
+            KeyMap::default()
+                .bind_with(KeyCode::Char('a'), KeyModifiers::CONTROL, Action::ToggleAll)
+                .bind_with(KeyCode::Char('i'), KeyModifiers::CONTROL, Action::Invert),
+        )
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+
+    let frames = recorder.frames();
+    assert!(frames[3].contains("1/4 selected"));
+    // Inverting apple, cherry and date keeps banana, which the filter hides.
+    assert!(frames[7].contains("4/4 selected"));
+    // All shown items are checked, so they are all unchecked; banana stays.
+    assert!(frames[8].contains("1/4 selected"));
+    assert_eq!(result.selected(), Some(vec!["banana"]));
+}
+
+#[test]
+fn all_and_invert_check_in_bulk_and_the_header_counts_them() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([
+        KeyCode::Char('a'),
+        KeyCode::Char('a'),
+        KeyCode::Char(' '),
+        KeyCode::Char('i'),
+        KeyCode::Enter,
+    ]);
+    let result = TerminalMultiSelect::builder()
+        .items(ITEMS)
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+
+    let frames = recorder.frames();
+    assert!(frames[0].contains("Total: 4 | Showing: 1 - 4 | 0/4 selected"));
+    assert!(frames[1].contains("4/4 selected"));
+    assert!(frames[2].contains("0/4 selected"));
+    assert!(frames[4].contains("3/4 selected"));
+    assert_eq!(result.selected(), Some(vec!["banana", "cherry", "date"]));
+}
+
+#[test]
+fn an_ordered_list_returns_the_items_in_pick_order() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([
+        KeyCode::Up,
+        KeyCode::Char(' '),
+        KeyCode::Up,
+        KeyCode::Char(' '),
+        KeyCode::Up,
+        KeyCode::Up,
+        KeyCode::Char(' '),
+        KeyCode::Down,
+        KeyCode::Down,
+        KeyCode::Char(' '),
+        KeyCode::Enter,
+    ]);
+    let result = TerminalMultiSelect::builder()
+        .items(ITEMS)
+        .ordered(true)
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+
+    let frames = recorder.frames();
+    assert!(frames[7].contains("> [3] apple \n  [ ] banana\n  [2] cherry\n  [1] date"));
+    assert!(frames[10].contains("  [2] apple\n  [ ] banana\n> [ ] cherry\n  [1] date"));
+    assert_eq!(result.selected(), Some(vec!["date", "apple"]));
+}
+
+#[test]
+fn shift_arrows_reorder_the_items() {
+    let recorder = FrameRecorder::new();
+    let shift = |code| Event::Key(KeyEvent::new(code, KeyModifiers::SHIFT));
+    let script = ScriptedEvents::new([shift(KeyCode::Down), shift(KeyCode::Down)])
+        .then(ScriptedEvents::keys([KeyCode::Up]))
+        .then(ScriptedEvents::new([shift(KeyCode::Up)]))
+        .then(ScriptedEvents::keys([KeyCode::Enter]));
+    let order = Reorder::new(ITEMS.to_vec())
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .run()
+        .unwrap();
+
+    assert!(recorder.frames()[2].contains("  banana\n  cherry\n> apple"));
+    assert_eq!(order, Some(vec!["cherry", "banana", "apple", "date"]));
+}
+
+#[test]
+fn a_hotkey_picks_its_item_at_once() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::Char('D')]);
+    let ignore: fn(&MenuItem<&str>) = |_| {};
+    let result = TerminalDropDown::builder()
+        .items(
+            [
+                MenuItem::new("Open", "open").hotkey('o'),
+                MenuItem::new("Delete", "delete").hotkey('d'),
+                MenuItem::new("Quit", "quit").hotkey('x'),
+            ]
+            .map(|item| (item, ignore)),
+        )
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+
+    assert_eq!(result.selected().map(|item| item.value), Some("delete"));
+    assert!(recorder.frames()[0].contains("  Quit (x)"));
+}
+
+#[test]
+fn question_mark_shows_the_bindings_until_the_next_key() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::Char('?'), KeyCode::Char('d'), KeyCode::Enter]);
+    let result = menu(script, &recorder).keymap(KeyMap::vim()).build().wait();
+
+    let frames = recorder.frames();
+    let help = &frames[1];
+    assert!(help.starts_with("Keys:"));
+    assert!(help.contains("↓, j"));
+    assert!(help.contains("Move down"));
+    assert!(
+        !help.contains("Check all"),
+        "multi-select keys do not apply"
+    );
+    assert!(frames[2].contains("> apple"));
+    assert_eq!(result.selected(), Some("apple"));
+}
+
+#[test]
+fn the_footer_fills_in_placeholders_and_the_header_can_be_hidden() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::Down, KeyCode::Enter]);
+    menu(script, &recorder)
+        .help_line("{position}/{total}: {item}")
+        .show_header(false)
+        .build()
+        .wait();
+
+    let frames = recorder.frames();
+    assert!(frames[0].starts_with("\n> apple "));
+    assert!(frames[1].ends_with("\n2/4: banana"));
+
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::Enter]);
+    menu(script, &recorder)
+        .show_header(false)
+        .show_footer(false)
+        .build()
+        .wait();
+    assert_eq!(recorder.frames()[0], "\n> apple \n  banana\n  cherry\n");
+}
+
+#[test]
+fn strings_translate_the_built_in_texts() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::Enter]);
+    menu(script, &recorder)
+        .strings(Strings::zh_cn())
+        .build()
+        .wait();
+
+    let frame = &recorder.frames()[0];
+    assert!(frame.starts_with("请选择（按 ESC 取消）：\n共 4 项 | 显示：1 - 3\n"));
+    assert!(frame.ends_with("↑：上 | ↓：下 | Enter：确认 | ESC：取消"));
+    assert!(recorder.printed().contains("确认删除：apple"));
+}
+
+#[test]
+fn a_border_draws_the_menu_in_a_titled_box() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::Enter]);
+    menu(script, &recorder)
+        .show_header(false)
+        .show_footer(false)
+        .border_title("Fruit")
+        .plain(false)
+        .build()
+        .wait();
+    assert_eq!(
+        recorder.frames()[0],
+        "┌─ Fruit ──┐\n│          │\n│ > apple  │\n│   banana │\n│   cherry │\n│          │\n└──────────┘"
+    );
+
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::Enter]);
+    menu(script, &recorder)
+        .show_header(false)
+        .show_footer(false)
+        .border(Border::rounded())
+        .plain(true)
+        .build()
+        .wait();
+    assert!(recorder.frames()[0].starts_with("+----------+\n|          |\n| > apple  |\n"));
+}
+
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn a_popup_draws_in_place_and_restores_the_cells_it_covered() {
+    let buffer = SharedBuffer::default();
+    let script = ScriptedEvents::keys([KeyCode::Down, KeyCode::Enter]);
+    menu(script, &FrameRecorder::new())
+        .output(Output::writer(buffer.clone()))
+        .plain(false)
+        .show_header(false)
+        .show_footer(false)
+        .popup(2, 4)
+        .backdrop(["abcdefghijklmnop"; 8])
+        .build()
+        .wait();
+
+    let written = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    assert!(!written.contains("\u{1b}[2J"));
+    assert!(written.starts_with("\u{1b}7\u{1b}[3;5H"));
+    // "> apple " shrinks to "  apple", uncovering one cell.
+    assert!(written.contains("\u{1b}[4;12Hl"));
+    assert!(written.contains("\u{1b}[4;5Hefghijk\u{1b}[5;5Hefghijkl"));
+    assert!(written.ends_with("\u{1b}8Confirm delete: banana\n"));
+}
+
+#[test]
+fn descriptions_can_go_on_a_line_under_the_highlighted_item() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::Down, KeyCode::Enter]);
+    let ignore: fn(&MenuItem<&str>) = |_| {};
+    TerminalDropDown::builder()
+        .items(
+            [
+                MenuItem::new("build", "build").description("Compile the workspace"),
+                MenuItem::new("test", "test").description("Run every test suite"),
+            ]
+            .map(|item| (item, ignore)),
+        )
+        .description_layout(DescriptionLayout::BelowHighlighted)
+        .show_header(false)
+        .show_footer(false)
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+
+    let frames = recorder.frames();
+    assert_eq!(frames[0], "\n> build\n  Compile the workspace\n  test\n");
+    assert_eq!(frames[1], "\n  build\n> test \n  Run every test suite\n");
+}
+
+#[test]
+fn soft_wrap_continues_long_items_under_their_label() {
+    let last_frame = |rows| {
+        let recorder = FrameRecorder::new();
+        let script = ScriptedEvents::new([
+            Event::Resize(24, rows),
+            Event::Key(KeyEvent::from(KeyCode::Down)),
+            Event::Key(KeyEvent::from(KeyCode::Enter)),
+        ]);
+        let ignore: fn(&&str) = |_| {};
+        TerminalDropDown::builder()
+            .items(
+                [
+                    "Fix the flaky login test",
+                    "Bump crossterm",
+                    "Document the keymap options",
+                ]
+                .map(|item| (item, ignore)),
+            )
+            .soft_wrap(true)
+            .show_header(false)
+            .show_footer(false)
+            .events(script)
+            .output(Output::Frames(recorder.clone()))
+            .build()
+            .wait();
+        recorder.frames().pop().unwrap()
+    };
+
+    // Five rows are left for the items, which take two, one and two lines.
+    assert_eq!(
+        last_frame(8),
+        "\n  Fix the flaky login\n  test\n> Bump crossterm     \n  Document the keymap\n  options\n"
+    );
+    // With four rows, the last item no longer fits.
+    assert_eq!(
+        last_frame(7),
+        "\n  Fix the flaky login\n  test\n> Bump crossterm     \n"
+    );
+}
+
+#[test]
+fn slash_searches_without_hiding_items_and_n_jumps_between_matches() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([
+        KeyCode::Char('/'),
+        KeyCode::Char('e'),
+        KeyCode::Enter,
+        KeyCode::Char('n'),
+        KeyCode::Char('n'),
+        KeyCode::Char('N'),
+        KeyCode::Enter,
+    ]);
+    let result = menu(script, &recorder).page_size(4).build().wait();
+
+    assert_eq!(result.selected(), Some("cherry"));
+    let frames = recorder.frames();
+    assert!(frames[1].ends_with("\n/"));
+    assert!(frames[2].ends_with("  date\n\n/e"));
+    assert!(frames[3].ends_with(" | n/N: Next/previous match"));
+    assert!(frames[5].contains("\n> date"));
+}
+
+#[test]
+fn n_jumps_to_the_next_match_but_unchecks_all_in_multi_select_menus() {
+    let recorder = FrameRecorder::new();
+    // A key bound to SelectNone does nothing outside multi-select menus.
+    let script = ScriptedEvents::keys([
+        KeyCode::Char('/'),
+        KeyCode::Char('e'),
+        KeyCode::Enter,
+        KeyCode::Char('x'),
+        KeyCode::Char('n'),
+        KeyCode::Enter,
+    ]);
+    let result = menu(script, &recorder)
+        .keymap(KeyMap::default().bind(KeyCode::Char('x'), Action::SelectNone))
+        .build()
+        .wait();
+    assert_eq!(result.selected(), Some("cherry"));
+
+    let script = ScriptedEvents::keys([
+        KeyCode::Char('a'),
+        KeyCode::Char('n'),
+        KeyCode::Char(' '),
+        KeyCode::Enter,
+    ]);
+    let result = TerminalMultiSelect::builder()
+        .items(ITEMS)
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+    assert_eq!(result.selected(), Some(vec!["apple"]));
+}
+
+#[test]
+fn ctrl_r_and_alt_c_switch_how_the_filter_matches() {
+    let recorder = FrameRecorder::new();
+    let key = |code| Event::Key(KeyEvent::from(code));
+    let script = ScriptedEvents::new([
+        key(KeyCode::Char('a')),
+        key(KeyCode::Char('e')),
+        Event::Key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL)),
+        key(KeyCode::Backspace),
+        key(KeyCode::Backspace),
+        key(KeyCode::Char('A')),
+        Event::Key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::ALT)),
+        key(KeyCode::Esc),
+        key(KeyCode::Esc),
+    ]);
+    menu(script, &recorder).filter(true).build().wait();
+
+    let frames = recorder.frames();
+    // Fuzzy: both letters appear in order.
+    assert!(frames[2].contains("> apple\n  date\n"));
+    assert!(frames[2].ends_with(" | Ctrl-R: fuzzy | Alt-C: ignore case"));
+    assert!(frames[3].contains("  No matches."));
+    assert!(frames[3].ends_with(" | Ctrl-R: substring | Alt-C: ignore case"));
+    assert!(frames[6].contains("> apple \n  banana\n  date\n"));
+    assert!(frames[7].contains("  No matches."));
+    assert!(frames[7].ends_with(" | Alt-C: match case"));
+}
+
+#[test]
+fn an_error_from_a_fallible_callback_is_returned_by_wait() {
+    let script = ScriptedEvents::keys([KeyCode::Down, KeyCode::Enter]);
+    let result = TerminalDropDown::builder()
+        .try_item("backup", |_: &&str| Ok::<_, io::Error>(()))
+        .try_item("restore", |_: &&str| {
+            Err(io::Error::new(io::ErrorKind::NotFound, "no backup yet"))
+        })
+        .events(script)
+        .output(Output::Frames(FrameRecorder::new()))
+        .build()
+        .wait();
+
+    match result {
+        SelectionResult::Error(TerMenuError::Callback(e)) => {
+            assert_eq!(e.to_string(), "no backup yet")
+        }
+        other => panic!("expected a callback error, got {:?}", other),
+    }
+}
+
+#[test]
+fn a_failed_callback_can_be_retried_or_another_item_picked() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([
+        KeyCode::Enter,
+        // Retry, which fails again.
+        KeyCode::Enter,
+        // Pick another.
+        KeyCode::Down,
+        KeyCode::Enter,
+        KeyCode::Down,
+        KeyCode::Enter,
+    ]);
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let counted = attempts.clone();
+    let result = TerminalDropDown::builder()
+        .try_item("backup", move |_: &&str| {
+            counted.fetch_add(1, Ordering::SeqCst);
+            Err(io::Error::other("disk full"))
+        })
+        .try_item("restore", |_: &&str| Ok::<_, io::Error>(()))
+        .retry_on_error(true)
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+
+    assert_eq!(result.selected(), Some("restore"));
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    let frames = recorder.frames();
+    assert!(frames[1].starts_with("Action failed: disk full.\n"));
+    assert!(frames[1].contains("\n> Retry       \n  Pick another\n  Abort\n"));
+}
+
+#[test]
+fn a_finish_message_reports_how_the_callback_went() {
+    let recorder = FrameRecorder::new();
+    let deploy = |env: &&str| match *env {
+        "prod" => Err(io::Error::new(io::ErrorKind::TimedOut, "timeout")),
+        _ => Ok(()),
+    };
+    let run = |keys: Vec<KeyCode>| {
+        TerminalDropDown::builder()
+            .try_items(["staging", "prod"].map(|env| (env, deploy)))
+            .finish_message(|env, outcome| match outcome {
+                Ok(()) => format!("✔ Deployed to {}", env).green(),
+                Err(e) => format!("✘ Failed: {}", e).red(),
+            })
+            .events(ScriptedEvents::keys(keys))
+            .output(Output::Frames(recorder.clone()))
+            .build()
+            .wait()
+    };
+
+    assert!(run(vec![KeyCode::Enter]).is_selected());
+    assert!(matches!(
+        run(vec![KeyCode::Down, KeyCode::Enter]),
+        SelectionResult::Error(TerMenuError::Callback(_))
+    ));
+    assert_eq!(
+        recorder.printed(),
+        "\n✔ Deployed to staging\n\n✘ Failed: timeout\n"
+    );
+}
+
+#[test]
+fn a_reusable_menu_keeps_its_fallible_callbacks_and_finish_message() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::Down, KeyCode::Enter])
+        .then(ScriptedEvents::keys([KeyCode::Enter]));
+    let mut menu = TerminalDropDown::builder()
+        .try_item("backup", |_: &&str| Ok::<_, io::Error>(()))
+        .try_item("restore", |_: &&str| Err(io::Error::other("no backup yet")))
+        .finish_message(|item, outcome| match outcome {
+            Ok(()) => format!("{} done", item).stylize(),
+            Err(e) => format!("{} failed: {}", item, e).stylize(),
+        })
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .build_reusable();
+
+    match menu.show() {
+        SelectionResult::Error(TerMenuError::Callback(e)) => {
+            assert_eq!(e.to_string(), "no backup yet")
+        }
+        other => panic!("expected a callback error, got {:?}", other),
+    }
+    assert_eq!(menu.show().selected(), Some("backup"));
+    assert_eq!(
+        recorder.printed(),
+        "\nrestore failed: no backup yet\n\nbackup done\n"
+    );
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn select_async_rejects_options_a_future_cannot_honour() {
+    use std::task::{Context, Poll, Waker};
+
+    let future = TerminalDropDown::builder()
+        .values(ITEMS)
+        .undo_window(Duration::from_secs(5))
+        .select_async();
+    let poll = std::pin::pin!(future).poll(&mut Context::from_waker(Waker::noop()));
+
+    match poll {
+        Poll::Ready(SelectionResult::Error(TerMenuError::Unsupported(option))) => {
+            assert_eq!(option, "undo_window")
+        }
+        other => panic!("expected the undo window to be refused, got {:?}", other),
+    }
+}
+
+#[test]
+fn u_takes_back_a_selection_during_the_undo_window() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([
+        KeyCode::Enter,
+        KeyCode::Char('u'),
+        KeyCode::Down,
+        KeyCode::Enter,
+        KeyCode::Enter,
+    ]);
+    let result = menu(script, &recorder)
+        .undo_window(Duration::from_secs(5))
+        .build()
+        .wait();
+
+    assert_eq!(result.selected(), Some("banana"));
+    let frames = recorder.frames();
+    assert!(frames[1].ends_with("\nSelected apple — press U within 5s to undo"));
+    assert!(frames[2].ends_with("ESC: Cancel"));
+    assert!(frames[4].ends_with("\nSelected banana — press U within 5s to undo"));
+    assert_eq!(frames.len(), 5);
+}
+
+#[test]
+fn a_destructive_item_is_chosen_only_once_its_label_is_typed() {
+    let recorder = FrameRecorder::new();
+    let script =
+        ScriptedEvents::keys([KeyCode::Down, KeyCode::Enter, KeyCode::Esc, KeyCode::Enter])
+            .then(ScriptedEvents::text("wip"))
+            .then(ScriptedEvents::keys([
+                KeyCode::Enter,
+                KeyCode::Char('e'),
+                KeyCode::Enter,
+            ]));
+    let ignore: fn(&MenuItem<&str>) = |_| {};
+    let result = TerminalDropDown::builder()
+        .items(
+            [
+                MenuItem::new("keep", "keep"),
+                MenuItem::new("wipe", "wipe").destructive(Confirmation::TypeLabel),
+            ]
+            .map(|item| (item, ignore)),
+        )
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+
+    assert_eq!(result.selected().map(|item| item.value), Some("wipe"));
+    let frames = recorder.frames();
+    assert!(frames[2].ends_with("\nType \"wipe\" to confirm: "));
+    assert!(frames[3].ends_with("ESC: Cancel"));
+    assert!(frames[7].ends_with("\nType \"wipe\" to confirm: wip"));
+    // Enter does nothing until the whole label is typed.
+    assert!(frames[8].ends_with("\nType \"wipe\" to confirm: wipe"));
+    assert_eq!(frames.len(), 9);
+}
+
+#[test]
+fn matched_characters_are_drawn_in_the_theme_style_in_a_grid() {
+    let buffer = SharedBuffer::default();
+    let script = ScriptedEvents::keys([KeyCode::Char('h'), KeyCode::Esc, KeyCode::Esc]);
+    let mut matched = ContentStyle::new();
+    matched.foreground_color = Some(Color::Red);
+    menu(script, &FrameRecorder::new())
+        .output(Output::writer(buffer.clone()))
+        .plain(false)
+        .filter(true)
+        .grid(2)
+        .theme(Theme {
+            matched,
+            ..Theme::default()
+        })
+        .build()
+        .wait();
+
+    let written = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    // Red on top of the highlight's reverse video, for the "h" of "cherry" only.
+    assert!(written.contains("\u{1b}[7mc\u{1b}[0m\u{1b}[38;5;9m\u{1b}[7mh\u{1b}[0m\u{1b}[7merry"));
+}
+
+#[test]
+fn a_key_interceptor_sees_keys_before_the_key_map() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::Char('j'), KeyCode::Char('j'), KeyCode::Char('x')]);
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let log = seen.clone();
+    let result = menu(script, &recorder)
+        .key_interceptor(move |key: KeyEvent, menu: &mut MenuState<'_, &str>| {
+            log.lock().unwrap().push((key.code, menu.highlighted()));
+            Handled::No
+        })
+        .key_interceptor(
+            |key: KeyEvent, menu: &mut MenuState<'_, &str>| match key.code {
+                KeyCode::Char('j') => Handled::Yes(menu.handle_key(KeyEvent::from(KeyCode::Down))),
+                KeyCode::Char('x') => Handled::Yes(KeyOutcome::Selected(0)),
+                _ => Handled::No,
+            },
+        )
+        .build()
+        .wait();
+
+    assert_eq!(result.selected(), Some("cherry"));
+    assert_eq!(
+        *seen.lock().unwrap(),
+        [
+            (KeyCode::Char('j'), Some(0)),
+            (KeyCode::Char('j'), Some(1)),
+            (KeyCode::Char('x'), Some(2)),
+        ]
+    );
+    assert_eq!(recorder.frames().len(), 3);
+}
+
+#[test]
+fn a_recorded_session_replays_the_same_navigation() {
+    let path = std::env::temp_dir()
+        .join(format!("ter_menu_session_{}", std::process::id()))
+        .join("menu.session");
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::new([Event::Resize(40, 10)]).then(ScriptedEvents::keys([
+        KeyCode::Down,
+        KeyCode::Char('\t'),
+        KeyCode::Down,
+        KeyCode::Enter,
+    ]));
+    let recorded = menu(script, &recorder)
+        .theme(Theme::plain())
+        .record_session(&path)
+        .build()
+        .wait();
+
+    let replay = Replay::open(&path).unwrap();
+    let frames: Vec<&str> = replay
+        .frames()
+        .iter()
+        .map(|(_, text)| text.as_str())
+        .collect();
+    assert_eq!(frames, recorder.frames());
+    let replayed = menu(ScriptedEvents::default(), &FrameRecorder::new())
+        .events(replay)
+        .build()
+        .wait();
+    assert_eq!(recorded.selected(), Some("cherry"));
+    assert_eq!(replayed.selected(), Some("cherry"));
+}
+
+#[test]
+fn menus_can_be_built_from_a_vec_or_a_map() {
+    let recorder = FrameRecorder::new();
+    let result = DropDownBuilder::from(vec!["a", "b", "c"])
+        .events(ScriptedEvents::keys([KeyCode::Down, KeyCode::Enter]))
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+    assert_eq!(result.selected(), Some("b"));
+
+    let ports = std::collections::BTreeMap::from([("ssh", 22), ("http", 80), ("https", 443)]);
+    let result = DropDownBuilder::from(ports)
+        .events(ScriptedEvents::keys([KeyCode::Down, KeyCode::Enter]))
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+    assert_eq!(result.selected().map(|item| item.value), Some(443));
+    assert!(recorder.frames()[3].contains("  http\n> https\n  ssh"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn a_menu_spec_from_a_config_file_builds_the_menu() {
+    let spec: ter_menu::MenuSpec = serde_json::from_str(
+        r#"{
+            "title": "Deploy to",
+            "page_size": 2,
+            "theme": "plain",
+            "items": [
+                { "label": "Staging", "value": "stg" },
+                { "label": "Production", "description": "needs approval", "hotkey": "p" },
+                { "label": "Local" }
+            ]
+        }"#,
+    )
+    .unwrap();
+    let recorder = FrameRecorder::new();
+    let result = DropDownBuilder::from(spec)
+        .events(ScriptedEvents::keys([KeyCode::Char('p')]))
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+
+    assert_eq!(
+        result.selected().map(|item| item.value),
+        Some("Production".to_string())
+    );
+    let first = &recorder.frames()[0];
+    assert!(first.starts_with("Deploy to\nTotal: 3 | Showing: 1 - 2\n"));
+    assert!(first.contains("\n> Staging  "));
+    assert!(first.contains("\n  Production  needs approval\n"));
+    assert!(!first.contains("Local"));
+    assert_eq!(recorder.printed(), "");
+    assert!(serde_json::from_str::<ter_menu::MenuSpec>(r#"{ "titel": "typo" }"#).is_err());
+}
+
+#[cfg(feature = "clap")]
+#[test]
+fn arguments_given_on_the_command_line_are_not_asked_for() {
+    let cmd = clap::Command::new("deploy").arg(
+        clap::Arg::new("env")
+            .long("env")
+            .required(true)
+            .value_parser(["staging", "prod"]),
+    );
+    let matches =
+        ter_menu::try_get_matches_from_interactive(cmd.clone(), ["deploy", "--env", "prod"])
+            .unwrap();
+    assert_eq!(matches.get_one::<String>("env").unwrap(), "prod");
+    let error =
+        ter_menu::try_get_matches_from_interactive(cmd, ["deploy", "--env", "dev"]).unwrap_err();
+    assert_eq!(error.kind(), clap::error::ErrorKind::InvalidValue);
+}
+
+#[test]
+fn shell_mode_prints_no_messages_and_reports_exit_codes() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys([KeyCode::Down, KeyCode::Enter]);
+    let result = menu(script, &recorder)
+        .shell_mode()
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+    assert_eq!(result.exit_code(), std::process::ExitCode::SUCCESS);
+    assert_eq!(result.selected(), Some("banana"));
+
+    let script = ScriptedEvents::keys([KeyCode::Esc]);
+    let result = menu(script, &recorder)
+        .shell_mode()
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+    assert_eq!(result.exit_code(), std::process::ExitCode::from(1));
+    assert_eq!(recorder.printed(), "");
+}
+
+#[test]
+fn shift_enter_can_be_bound_apart_from_enter_and_releases_reach_only_interceptors() {
+    let recorder = FrameRecorder::new();
+    let released = Arc::new(AtomicUsize::new(0));
+    let count = released.clone();
+    let script = ScriptedEvents::new([
+        Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::SHIFT)),
+        Event::Key(KeyEvent::new_with_kind(
+            KeyCode::Enter,
+            KeyModifiers::SHIFT,
+            KeyEventKind::Release,
+        )),
+        Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)),
+    ]);
+    let result = menu(script, &recorder)
+        .keyboard_enhancement(true)
+        .keymap(KeyMap::default().bind_with(KeyCode::Enter, KeyModifiers::SHIFT, Action::Down))
+        .key_interceptor(move |key: KeyEvent, _: &mut MenuState<'_, &str>| {
+            if key.kind == KeyEventKind::Release {
+                count.fetch_add(1, Ordering::SeqCst);
+            }
+            Handled::No
+        })
+        .build()
+        .wait();
+
+    assert_eq!(result.selected(), Some("banana"));
+    assert_eq!(released.load(Ordering::SeqCst), 1);
+    assert_eq!(recorder.frames().len(), 2);
+}
+
+#[test]
+fn shift_enter_confirms_an_item_and_keeps_the_menu_open() {
+    let recorder = FrameRecorder::new();
+    let confirmed = Arc::new(Mutex::new(Vec::new()));
+    let seen = confirmed.clone();
+    let script = ScriptedEvents::new([
+        Event::Key(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE)),
+        Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::SHIFT)),
+        Event::Key(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE)),
+        Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::SHIFT)),
+        Event::Key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)),
+    ]);
+    let result = menu(script, &recorder)
+        .keyboard_enhancement(true)
+        .on_confirm_and_stay(move |item: &&str| seen.lock().unwrap().push(item.to_string()))
+        .build()
+        .wait();
+
+    assert_eq!(*confirmed.lock().unwrap(), ["banana", "cherry"]);
+    assert!(result.selected().is_none());
+}
+
+#[test]
+fn shift_enter_asks_again_for_destructive_items_and_skips_submenus() {
+    let recorder = FrameRecorder::new();
+    let confirmed = Arc::new(Mutex::new(Vec::new()));
+    let seen = confirmed.clone();
+    let shift_enter = || Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::SHIFT));
+    let script = ScriptedEvents::new([
+        shift_enter(),
+        Event::Key(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE)),
+        shift_enter(),
+        shift_enter(),
+    ])
+    .then(ScriptedEvents::text("drop"))
+    .then(ScriptedEvents::new([
+        shift_enter(),
+        Event::Key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)),
+    ]));
+    let ignore: fn(&MenuItem<&str>) = |_| {};
+    let result = TerminalDropDown::builder()
+        .items(
+            [
+                MenuItem::submenu("Network", [MenuItem::new("Proxy", "proxy")]),
+                MenuItem::new("drop", "drop").destructive(Confirmation::TypeLabel),
+            ]
+            .map(|item| (item, ignore)),
+        )
+        .keyboard_enhancement(true)
+        .on_confirm_and_stay(move |item: &MenuItem<&str>| seen.lock().unwrap().push(item.value))
+        .events(script)
+        .output(Output::Frames(recorder.clone()))
+        .build()
+        .wait();
+
+    assert_eq!(*confirmed.lock().unwrap(), ["drop"]);
+    assert!(result.selected().is_none());
+    let frames = recorder.frames();
+    // The submenu parent is neither handed over nor opened.
+    assert!(frames[1].contains("> drop"));
+    assert!(frames[2].ends_with("\nType \"drop\" to confirm: "));
+    // Shift+Enter before the label is typed does nothing.
+    assert_eq!(frames.len(), 8);
+    assert!(!frames[7].contains("to confirm"));
+}
+
+/// Each key the way a Windows console reports it: a press followed by its release.
+fn windows_keys(codes: impl IntoIterator<Item = KeyCode>) -> ScriptedEvents {
+    ScriptedEvents::new(codes.into_iter().flat_map(|code| {
+        [KeyEventKind::Press, KeyEventKind::Release]
+            .map(|kind| Event::Key(KeyEvent::new_with_kind(code, KeyModifiers::NONE, kind)))
+    }))
+}
+
+#[test]
+fn windows_key_releases_are_ignored_and_repeats_move_the_highlight() {
+    let recorder = FrameRecorder::new();
+    let script = windows_keys([KeyCode::Down]).then(ScriptedEvents::new([
+        Event::Key(KeyEvent::new_with_kind(
+            KeyCode::Down,
+            KeyModifiers::NONE,
+            KeyEventKind::Repeat,
+        )),
+        Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)),
+    ]));
+    let result = menu(script, &recorder)
+        .key_repeat_interval(Duration::ZERO)
+        .build()
+        .wait();
+
+    assert_eq!(result.selected(), Some("cherry"));
+    assert_eq!(recorder.frames().len(), 3);
+}
+
+#[test]
+fn windows_key_releases_are_not_typed_twice() {
+    let recorder = FrameRecorder::new();
+    let name = Input::new("Name: ")
+        .events(windows_keys(
+            "ab".chars().map(KeyCode::Char).chain([KeyCode::Enter]),
+        ))
+        .output(Output::Frames(recorder.clone()))
+        .run()
+        .unwrap();
+
+    assert_eq!(name.as_deref(), Some("ab"));
+}
+
+fn key(code: KeyCode, kind: KeyEventKind) -> Event {
+    Event::Key(KeyEvent::new_with_kind(code, KeyModifiers::NONE, kind))
+}
+
+#[test]
+fn held_navigation_keys_repeat_at_most_once_per_interval_but_presses_always_count() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::new([
+        key(KeyCode::Down, KeyEventKind::Press),
+        key(KeyCode::Down, KeyEventKind::Repeat),
+        key(KeyCode::Down, KeyEventKind::Repeat),
+        key(KeyCode::Down, KeyEventKind::Press),
+        key(KeyCode::Enter, KeyEventKind::Press),
+    ]);
+    let result = menu(script, &recorder)
+        .key_repeat_interval(Duration::from_secs(60))
+        .build()
+        .wait();
+
+    assert_eq!(result.selected(), Some("cherry"));
+}
+
+#[test]
+fn doubled_letters_typed_into_the_filter_are_kept() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::new([
+        key(KeyCode::Char('a'), KeyEventKind::Press),
+        key(KeyCode::Char('a'), KeyEventKind::Repeat),
+        key(KeyCode::Enter, KeyEventKind::Press),
+    ]);
+    let result = menu(script, &recorder)
+        .filter(true)
+        .key_repeat_interval(Duration::from_secs(60))
+        .build()
+        .wait();
+
+    assert_eq!(result.selected(), Some("banana"));
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn a_regex_filter_matches_expressions() {
+    let recorder = FrameRecorder::new();
+    let script = ScriptedEvents::keys("^[bc]".chars().map(KeyCode::Char).chain([KeyCode::Enter]));
+    let result = menu(script, &recorder)
+        .filter(true)
+        .match_mode(ter_menu::MatchMode::Regex)
+        .build()
+        .wait();
+
+    assert_eq!(result.selected(), Some("banana"));
+    assert!(recorder.frames()[5].contains("\n> banana\n  cherry\n"));
+}